@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use sea_orm::DatabaseConnection;
+
+use super::env::Env;
+use crate::services::attachment::{BlobStore, LocalBlobStore, PostgresBlobStore};
+
+pub fn get_blob_store(
+    env_variables: &Env,
+    database_connection: Arc<DatabaseConnection>,
+) -> Arc<dyn BlobStore> {
+    match env_variables.attachment_storage.as_str() {
+        "postgres" => Arc::new(PostgresBlobStore::new(database_connection)),
+        _ => Arc::new(LocalBlobStore::new(&env_variables.attachment_local_path)),
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
 use dotenvy::dotenv;
 
@@ -6,8 +6,46 @@ use dotenvy::dotenv;
 pub struct Env {
     pub database_url: String,
     pub redis_url: String,
+    /// Base URL of the web app, links sent to users point there
+    pub app_url: String,
     pub recovery_keys_count: i32,
-    pub session_expire_minutes: i64,
+    /// Sessions unused for this long expire
+    pub session_idle_minutes: i64,
+    /// Sessions expire this long after login, however active
+    pub session_max_lifetime_minutes: i64,
+    /// Unlocked vaults lock again after this long without use
+    pub vault_auto_lock_minutes: i64,
+    /// Sensitive operations stay allowed this long after re-entering the master password
+    pub step_up_minutes: i64,
+    /// Failed sign-in or recovery attempts for an email before it is locked out
+    pub auth_max_failures: u64,
+    /// Failed attempts from one address before it is locked out
+    pub auth_ip_max_failures: u64,
+    /// How long a lockout lasts, and how long failures are remembered
+    pub auth_lockout_minutes: u64,
+    /// Addresses of the proxies in front of the app, whose X-Forwarded-For is believed
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Signup and email verification links stay valid this long
+    pub signup_token_minutes: u64,
+    /// Either smtp or file
+    pub mail_transport: String,
+    pub mail_from: String,
+    pub mail_file_path: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub attachment_max_bytes: u64,
+    pub attachment_quota_bytes: u64,
+    pub attachment_storage: String,
+    pub attachment_local_path: String,
+    pub import_max_bytes: u64,
+    /// Deleted accounts can be restored this long before they are erased
+    pub account_deletion_grace_days: i64,
+    /// Key of the email hashes kept in deletion receipts, required and meant to stay the same,
+    /// receipts hashed under an earlier key no longer match their email
+    pub receipt_hash_secret: String,
+    pub jobs_interval_seconds: u64,
 }
 
 pub fn new() -> Arc<Env> {
@@ -17,20 +55,230 @@ pub fn new() -> Arc<Env> {
 
     let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL is not set");
 
+    let app_url = std::env::var("APP_URL").unwrap_or("http://localhost:3000".to_string());
+
     let recovery_keys_count = std::env::var("RECOVERY_KEYS_COUNT")
         .expect("RECOVERY_KEYS_COUNT is not set")
         .parse::<i32>()
         .expect("RECOVERY_KEYS_COUNT is not a number");
 
-    let session_expire_minutes = std::env::var("SESSION_EXPIRE_MINUTES")
-        .expect("SESSION_EXPIRE_MINUTES is not set")
+    // SESSION_EXPIRE_MINUTES is the name from before the absolute lifetime
+    let session_idle_minutes = std::env::var("SESSION_IDLE_MINUTES")
+        .or_else(|_| std::env::var("SESSION_EXPIRE_MINUTES"))
+        .expect("SESSION_IDLE_MINUTES is not set")
         .parse::<i64>()
-        .expect("SESSION_EXPIRE_MINUTES is not a number");
+        .expect("SESSION_IDLE_MINUTES is not a number");
+
+    let session_max_lifetime_minutes = std::env::var("SESSION_MAX_LIFETIME_MINUTES")
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .expect("SESSION_MAX_LIFETIME_MINUTES is not a number")
+        })
+        .unwrap_or(12 * 60);
+
+    let vault_auto_lock_minutes = std::env::var("VAULT_AUTO_LOCK_MINUTES")
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .expect("VAULT_AUTO_LOCK_MINUTES is not a number")
+        })
+        .unwrap_or(15);
+
+    let step_up_minutes = std::env::var("STEP_UP_MINUTES")
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .expect("STEP_UP_MINUTES is not a number")
+        })
+        .unwrap_or(5);
+
+    let auth_max_failures = std::env::var("AUTH_MAX_FAILURES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("AUTH_MAX_FAILURES is not a number")
+        })
+        .unwrap_or(5);
+
+    let auth_ip_max_failures = std::env::var("AUTH_IP_MAX_FAILURES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("AUTH_IP_MAX_FAILURES is not a number")
+        })
+        .unwrap_or(50);
+
+    let auth_lockout_minutes = std::env::var("AUTH_LOCKOUT_MINUTES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("AUTH_LOCKOUT_MINUTES is not a number")
+        })
+        .unwrap_or(15);
+
+    let trusted_proxies = std::env::var("TRUSTED_PROXIES")
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|proxy| !proxy.is_empty())
+                .map(|proxy| {
+                    proxy
+                        .parse::<IpAddr>()
+                        .expect("TRUSTED_PROXIES is not a list of addresses")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let signup_token_minutes = std::env::var("SIGNUP_TOKEN_MINUTES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("SIGNUP_TOKEN_MINUTES is not a number")
+        })
+        .unwrap_or(60);
+
+    let mail_transport = std::env::var("MAIL_TRANSPORT").unwrap_or_else(|_| "file".to_string());
+
+    if mail_transport != "smtp" && mail_transport != "file" {
+        panic!("MAIL_TRANSPORT must be either smtp or file");
+    }
+
+    let mail_from = std::env::var("MAIL_FROM")
+        .unwrap_or_else(|_| "Password Vault <no-reply@localhost>".to_string());
+
+    let mail_file_path = std::env::var("MAIL_FILE_PATH").unwrap_or_else(|_| "mail".to_string());
+
+    let smtp_host = std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+
+    let smtp_port = std::env::var("SMTP_PORT")
+        .map(|value| value.parse::<u16>().expect("SMTP_PORT is not a number"))
+        .unwrap_or(587);
+
+    let smtp_username = std::env::var("SMTP_USERNAME").ok();
+
+    let smtp_password = std::env::var("SMTP_PASSWORD").ok();
+
+    let attachment_max_bytes = std::env::var("ATTACHMENT_MAX_BYTES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("ATTACHMENT_MAX_BYTES is not a number")
+        })
+        .unwrap_or(10 * 1024 * 1024);
+
+    let attachment_quota_bytes = std::env::var("ATTACHMENT_QUOTA_BYTES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("ATTACHMENT_QUOTA_BYTES is not a number")
+        })
+        .unwrap_or(1024 * 1024 * 1024);
+
+    let attachment_storage =
+        std::env::var("ATTACHMENT_STORAGE").unwrap_or_else(|_| "local".to_string());
+
+    if attachment_storage != "local" && attachment_storage != "postgres" {
+        panic!("ATTACHMENT_STORAGE must be either local or postgres");
+    }
+
+    let attachment_local_path =
+        std::env::var("ATTACHMENT_LOCAL_PATH").unwrap_or_else(|_| "attachments".to_string());
+
+    let import_max_bytes = std::env::var("IMPORT_MAX_BYTES")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("IMPORT_MAX_BYTES is not a number")
+        })
+        .unwrap_or(50 * 1024 * 1024);
+
+    let account_deletion_grace_days = std::env::var("ACCOUNT_DELETION_GRACE_DAYS")
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .expect("ACCOUNT_DELETION_GRACE_DAYS is not a number")
+        })
+        .unwrap_or(30);
+
+    let receipt_hash_secret =
+        std::env::var("RECEIPT_HASH_SECRET").expect("RECEIPT_HASH_SECRET is not set");
+
+    let jobs_interval_seconds = std::env::var("JOBS_INTERVAL_SECONDS")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("JOBS_INTERVAL_SECONDS is not a number")
+        })
+        .unwrap_or(60);
 
     Arc::new(Env {
         database_url,
         redis_url,
+        app_url,
         recovery_keys_count,
-        session_expire_minutes,
+        session_idle_minutes,
+        session_max_lifetime_minutes,
+        vault_auto_lock_minutes,
+        step_up_minutes,
+        auth_max_failures,
+        auth_ip_max_failures,
+        auth_lockout_minutes,
+        trusted_proxies,
+        signup_token_minutes,
+        mail_transport,
+        mail_from,
+        mail_file_path,
+        smtp_host,
+        smtp_port,
+        smtp_username,
+        smtp_password,
+        attachment_max_bytes,
+        attachment_quota_bytes,
+        attachment_storage,
+        attachment_local_path,
+        import_max_bytes,
+        account_deletion_grace_days,
+        receipt_hash_secret,
+        jobs_interval_seconds,
     })
 }
+
+#[cfg(test)]
+impl Env {
+    /// Settings for tests, nothing is read from the environment
+    pub fn for_tests() -> Env {
+        Env {
+            database_url: String::new(),
+            redis_url: String::new(),
+            app_url: String::new(),
+            recovery_keys_count: 10,
+            session_idle_minutes: 30,
+            session_max_lifetime_minutes: 720,
+            vault_auto_lock_minutes: 15,
+            step_up_minutes: 5,
+            auth_max_failures: 5,
+            auth_ip_max_failures: 50,
+            auth_lockout_minutes: 15,
+            trusted_proxies: vec![],
+            signup_token_minutes: 60,
+            mail_transport: "file".to_string(),
+            mail_from: String::new(),
+            mail_file_path: String::new(),
+            smtp_host: String::new(),
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            attachment_max_bytes: 0,
+            attachment_quota_bytes: 0,
+            attachment_storage: "local".to_string(),
+            attachment_local_path: String::new(),
+            import_max_bytes: 0,
+            account_deletion_grace_days: 30,
+            receipt_hash_secret: "receipt-hash-secret".to_string(),
+            jobs_interval_seconds: 60,
+        }
+    }
+}
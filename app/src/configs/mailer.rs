@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use super::env::Env;
+use crate::services::mail::{FileMailer, Mailer, SmtpMailer};
+
+pub fn get_mailer(env_variables: &Env) -> Arc<dyn Mailer> {
+    match env_variables.mail_transport.as_str() {
+        "smtp" => {
+            let credentials = env_variables
+                .smtp_username
+                .clone()
+                .zip(env_variables.smtp_password.clone());
+
+            match SmtpMailer::new(
+                &env_variables.smtp_host,
+                env_variables.smtp_port,
+                credentials,
+                &env_variables.mail_from,
+            ) {
+                Err(e) => panic!("Unable to Configure SMTP: {}", e),
+                Ok(mailer) => Arc::new(mailer),
+            }
+        }
+        _ => Arc::new(FileMailer::new(&env_variables.mail_file_path)),
+    }
+}
@@ -1,3 +1,5 @@
+pub mod blob_store;
 pub mod database;
 pub mod env;
+pub mod mailer;
 pub mod redis;
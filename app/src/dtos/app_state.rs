@@ -3,12 +3,21 @@ use std::sync::Arc;
 use r2d2::Pool;
 use redis::Client;
 use sea_orm::DatabaseConnection;
+use tokio::sync::broadcast;
 
-use crate::configs::env::Env;
+use crate::{
+    configs::env::Env,
+    models::event_dtos::VaultEvent,
+    services::{attachment::BlobStore, mail::Mailer},
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub database_connection: Arc<DatabaseConnection>,
     pub redis_pool_manager: Arc<Pool<Client>>,
     pub env_variables: Arc<Env>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub mailer: Arc<dyn Mailer>,
+    /// Events from every instance, for the subscriptions of this one
+    pub event_sender: broadcast::Sender<VaultEvent>,
 }
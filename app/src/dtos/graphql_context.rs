@@ -1,7 +1,11 @@
+use std::net::IpAddr;
+
 use axum::http::HeaderMap;
 
 #[derive(Default, Clone, Debug)]
 pub struct GraphQLContext {
     pub session_token: Option<String>,
     pub headers: Option<HeaderMap>,
+    /// Address the request came from, a proxy when the app runs behind one
+    pub peer_ip: Option<IpAddr>,
 }
@@ -2,8 +2,23 @@ use async_graphql::{OutputType, SimpleObject};
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    password_dtos::{PasswordResponse, PasswordsPageResponse},
+    account_dtos::AccountDeletionResponse,
+    attachment_dtos::AttachmentResponse,
+    custom_field_dtos::CustomFieldResponse,
+    emergency_access_dtos::{EmergencyAccessesResponse, EmergencyVaultResponse},
+    export_dtos::{ExportVaultResponse, RestoreVaultResponse},
+    folder_dtos::{FolderResponse, FoldersResponse},
+    import_dtos::ImportVaultResponse,
+    organization_dtos::{CollectionResponse, OrganizationResponse, OrganizationsResponse},
+    password_dtos::{
+        ChangesSinceResponse, CredentialsForUrlResponse, DuplicateClustersResponse,
+        PasswordResponse, PasswordsPageResponse,
+    },
+    session_dtos::{SessionPolicyResponse, SessionVerificationResponse, SessionsResponse},
+    share_dtos::SharedEntriesResponse,
+    tag_dtos::{TagResponse, TagsResponse},
     user_dtos::{RecoveryKeyResponse, UserSignupResponse},
+    vault_item_dtos::{VaultItemResponse, VaultItemsResponse},
 };
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
@@ -21,11 +36,89 @@ pub struct GraphqlGenericResponse {
     name = "GraphqlResponse_PasswordsPageResponse",
     params(PasswordsPageResponse)
 ))]
+#[graphql(concrete(
+    name = "GraphqlResponse_ChangesSinceResponse",
+    params(ChangesSinceResponse)
+))]
 #[graphql(concrete(name = "GraphqlResponse_PasswordResponse", params(PasswordResponse)))]
+#[graphql(concrete(
+    name = "GraphqlResponse_CredentialsForUrlResponse",
+    params(CredentialsForUrlResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_DuplicateClustersResponse",
+    params(DuplicateClustersResponse)
+))]
 #[graphql(concrete(
     name = "GraphqlResponse_RecoveryKeyResponse",
     params(RecoveryKeyResponse)
 ))]
+#[graphql(concrete(name = "GraphqlResponse_VaultItemResponse", params(VaultItemResponse)))]
+#[graphql(concrete(
+    name = "GraphqlResponse_CustomFieldResponse",
+    params(CustomFieldResponse)
+))]
+#[graphql(concrete(name = "GraphqlResponse_FolderResponse", params(FolderResponse)))]
+#[graphql(concrete(name = "GraphqlResponse_FoldersResponse", params(FoldersResponse)))]
+#[graphql(concrete(name = "GraphqlResponse_TagResponse", params(TagResponse)))]
+#[graphql(concrete(name = "GraphqlResponse_TagsResponse", params(TagsResponse)))]
+#[graphql(concrete(
+    name = "GraphqlResponse_AttachmentResponse",
+    params(AttachmentResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_VaultItemsResponse",
+    params(VaultItemsResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_SharedEntriesResponse",
+    params(SharedEntriesResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_OrganizationResponse",
+    params(OrganizationResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_OrganizationsResponse",
+    params(OrganizationsResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_CollectionResponse",
+    params(CollectionResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_EmergencyAccessesResponse",
+    params(EmergencyAccessesResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_EmergencyVaultResponse",
+    params(EmergencyVaultResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_ImportVaultResponse",
+    params(ImportVaultResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_ExportVaultResponse",
+    params(ExportVaultResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_RestoreVaultResponse",
+    params(RestoreVaultResponse)
+))]
+#[graphql(concrete(name = "GraphqlResponse_SessionsResponse", params(SessionsResponse)))]
+#[graphql(concrete(
+    name = "GraphqlResponse_SessionPolicyResponse",
+    params(SessionPolicyResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_SessionVerificationResponse",
+    params(SessionVerificationResponse)
+))]
+#[graphql(concrete(
+    name = "GraphqlResponse_AccountDeletionResponse",
+    params(AccountDeletionResponse)
+))]
 pub struct GraphqlResponse<T>
 where
     T: Send + Sync + OutputType,
@@ -0,0 +1,3 @@
+pub mod scheduler;
+
+pub use scheduler::*;
@@ -0,0 +1,50 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::time;
+
+use crate::{
+    dtos::app_state::AppState,
+    services::{
+        account::delete_due_accounts, emergency_access::grant_due_emergency_accesses,
+        events::listen_for_events, password::backfill_normalized_urls,
+    },
+};
+
+/// Run the periodic jobs and the event listener in the background for as long as the app runs
+pub fn spawn_jobs(app_state: Arc<AppState>) {
+    let interval = Duration::from_secs(app_state.env_variables.jobs_interval_seconds);
+
+    tokio::spawn(listen_for_events(app_state.clone()));
+
+    let backfill_app_state = app_state.clone();
+
+    tokio::spawn(async move {
+        match backfill_normalized_urls(backfill_app_state.database_connection.as_ref()).await {
+            Ok(0) => {}
+            Ok(backfilled) => tracing::info!("Normalized the websites of {} passwords", backfilled),
+            Err(e) => tracing::error!("Website normalization backfill failed: {}", e),
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut ticker = time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            let database_connection = app_state.database_connection.as_ref();
+
+            match grant_due_emergency_accesses(database_connection).await {
+                Ok(0) => {}
+                Ok(granted) => tracing::info!("Granted {} emergency access requests", granted),
+                Err(e) => tracing::error!("Emergency access job failed: {}", e),
+            }
+
+            match delete_due_accounts(&app_state).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Deleted {} accounts", deleted),
+                Err(e) => tracing::error!("Account deletion job failed: {}", e),
+            }
+        }
+    });
+}
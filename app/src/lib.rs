@@ -1,6 +1,7 @@
 mod configs;
 mod constants;
 mod dtos;
+mod jobs;
 mod middlewares;
 mod models;
 mod routes;
@@ -8,13 +9,14 @@ mod services;
 mod utils;
 mod validators;
 
-use std::{sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use axum::serve;
-use configs::{database, env, redis};
+use configs::{blob_store, database, env, mailer, redis};
 use constants::art::ASCII_ART;
 use dtos::app_state::AppState;
-use tokio::time;
+use services::events::EVENTS_BUFFER;
+use tokio::{sync::broadcast, time};
 use utils::common::clr;
 
 pub async fn init() {
@@ -35,12 +37,23 @@ pub async fn init() {
     };
     println!("Connection Established with Redis 🚀");
 
+    let blob_store = blob_store::get_blob_store(&env_variables, database_connection.clone());
+
+    let mailer = mailer::get_mailer(&env_variables);
+
+    let (event_sender, _) = broadcast::channel(EVENTS_BUFFER);
+
     let app_state = Arc::new(AppState {
         database_connection,
         redis_pool_manager,
         env_variables,
+        blob_store,
+        mailer,
+        event_sender,
     });
 
+    jobs::spawn_jobs(app_state.clone());
+
     let routes = routes::init_routes(app_state);
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await.unwrap();
@@ -52,5 +65,10 @@ pub async fn init() {
     clr();
     println!("{}", ASCII_ART);
 
-    serve(listener, routes).await.unwrap();
+    serve(
+        listener,
+        routes.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
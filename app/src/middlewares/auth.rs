@@ -2,12 +2,142 @@ use std::sync::Arc;
 
 use crate::{
     dtos::{app_state::AppState, graphql_context::GraphQLContext},
-    models::user_dtos::UserRedisSession,
+    models::user_dtos::{RedisSession, SessionState, UserRedisSession},
+    services::session::{
+        extend_session_dek, is_session_verified, load_session_dek, session_ttl_seconds,
+        touch_session,
+    },
     utils::error::{AppError, AppResult},
 };
 use async_graphql::Context;
+use axum::http::{HeaderMap, header};
+use chrono::Utc;
 use redis::Commands;
 
+/// Session token from the session_token cookie
+pub fn session_token_from_headers(headers: &HeaderMap) -> Option<String> {
+    let cookie = headers
+        .get(header::COOKIE)
+        .and_then(|value| value.to_str().ok())?;
+
+    cookie
+        .split(';')
+        .map(|cookie| cookie.trim())
+        .find(|cookie| cookie.starts_with("session_token="))
+        .map(|cookie| cookie.replace("session_token=", ""))
+}
+
+/// Load the session a token belongs to, locked or not
+pub fn get_session_state(app_state: &AppState, session_token: &str) -> AppResult<SessionState> {
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let redis_session_token = redis_connection
+        .get::<String, String>(session_token.to_string())
+        .map_err(|_| AppError::Authorization("Session token is invalid or expired".to_string()))?;
+
+    if redis_session_token.is_empty() {
+        return Err(AppError::Authorization(
+            "Session token is invalid or expired".to_string(),
+        ));
+    }
+
+    // redis_session_token is the RedisSession struct serialized to string
+    let redis_session =
+        serde_json::from_str::<RedisSession>(&redis_session_token).map_err(|_| {
+            AppError::Internal("Failed to deserialize RedisSession from redis".to_string())
+        })?;
+
+    // sessions from before the absolute lifetime have no deadline and log in again
+    if redis_session
+        .expires_at
+        .is_none_or(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(AppError::Authorization(
+            "Session token is invalid or expired".to_string(),
+        ));
+    }
+
+    let dek = load_session_dek(&mut redis_connection, session_token)
+        .map_err(|_| AppError::Internal("Failed to load session key".to_string()))?;
+
+    Ok(match dek {
+        Some(dek) => SessionState::Unlocked(redis_session.unlocked(dek)),
+        None => SessionState::Locked(redis_session),
+    })
+}
+
+/// Load the session a token belongs to, which needs an unlocked vault
+pub fn get_user_redis_session(
+    app_state: &AppState,
+    session_token: &str,
+) -> AppResult<UserRedisSession> {
+    match get_session_state(app_state, session_token)? {
+        SessionState::Unlocked(user_redis_session) => Ok(user_redis_session),
+        SessionState::Locked(_) => Err(AppError::Locked("Vault is locked".to_string())),
+    }
+}
+
+/// Fail unless the session re-entered the master password within the last few minutes
+pub fn require_step_up(app_state: &AppState, session_token: &str) -> AppResult<()> {
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let verified = is_session_verified(&mut redis_connection, session_token)
+        .map_err(|e| AppError::Internal(format!("Failed to check session verification: {}", e)))?;
+
+    if !verified {
+        return Err(AppError::StepUpRequired(
+            "Verify your master password to continue".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Push back the expiry of a session by its idle timeout up to its deadline, and its auto-lock
+pub fn extend_session_expire(
+    app_state: &AppState,
+    session_token: &str,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<()> {
+    let env_variables = &app_state.env_variables;
+
+    let idle_minutes = user_redis_session
+        .idle_minutes
+        .unwrap_or(env_variables.session_idle_minutes);
+    let expires_at = user_redis_session.expires_at.unwrap_or_else(Utc::now);
+
+    let expire_seconds = session_ttl_seconds(idle_minutes, expires_at, Utc::now());
+    let auto_lock_seconds = session_ttl_seconds(
+        env_variables.vault_auto_lock_minutes,
+        expires_at,
+        Utc::now(),
+    );
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    redis_connection
+        .expire::<String, usize>(session_token.to_string(), expire_seconds as i64)
+        .map_err(|_| AppError::Internal("Failed to increment session expire".to_string()))?;
+
+    touch_session(&mut redis_connection, session_token, expire_seconds)
+        .map_err(|_| AppError::Internal("Failed to increment session expire".to_string()))?;
+
+    // a lock that happened in the meantime is kept, expiring a missing key does nothing
+    extend_session_dek(&mut redis_connection, session_token, auto_lock_seconds)
+        .map_err(|_| AppError::Internal("Failed to increment session expire".to_string()))?;
+
+    Ok(())
+}
+
 pub fn session_auth_middleware(ctx: &Context<'_>) -> AppResult<UserRedisSession> {
     let app_state = ctx
         .data::<Arc<AppState>>()
@@ -24,36 +154,55 @@ pub fn session_auth_middleware(ctx: &Context<'_>) -> AppResult<UserRedisSession>
             "Session token is missing".to_string(),
         ))?;
 
-    let mut redis_connection = app_state
-        .redis_pool_manager
-        .get()
-        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+    get_user_redis_session(app_state, session_token)
+}
 
-    let redis_session_token = redis_connection
-        .get::<String, String>(session_token.to_string())
-        .map_err(|_| AppError::Authorization("Session token is invalid or expired".to_string()))?;
+/// Authenticate a session without needing its vault unlocked
+pub fn session_auth_allow_locked_middleware(ctx: &Context<'_>) -> AppResult<SessionState> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
 
-    if redis_session_token.len() == 0 {
-        return Err(AppError::Authorization(
-            "Session token is invalid or expired".to_string(),
-        ));
-    }
+    let gql_ctx = ctx
+        .data::<Arc<GraphQLContext>>()
+        .map_err(|_| AppError::Internal("GraphQL Context is not passed".to_string()))?;
 
-    // redis_session_token is the UserRedisSession struct serialized to string
-    let user_redis_session = serde_json::from_str::<UserRedisSession>(&redis_session_token)
-        .map_err(|_| {
-            AppError::Internal("Failed to deserialize UserRedisSession from redis".to_string())
-        })?;
+    let session_token = gql_ctx
+        .session_token
+        .as_ref()
+        .ok_or(AppError::Authorization(
+            "Session token is missing".to_string(),
+        ))?;
 
-    Ok(user_redis_session)
+    get_session_state(app_state, session_token)
 }
 
-pub fn increment_session_expire(ctx: &Context<'_>) -> AppResult<()> {
+pub fn increment_session_expire(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<()> {
     let app_state = ctx
         .data::<Arc<AppState>>()
         .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
 
-    let env_variables = &app_state.env_variables;
+    let gql_ctx = ctx
+        .data::<Arc<GraphQLContext>>()
+        .map_err(|_| AppError::Internal("GraphQL Context is not passed".to_string()))?;
+
+    let session_token = gql_ctx
+        .session_token
+        .as_ref()
+        .ok_or(AppError::Authorization(
+            "Session token is missing".to_string(),
+        ))?;
+
+    extend_session_expire(app_state, session_token, user_redis_session)
+}
+
+pub fn step_up_middleware(ctx: &Context<'_>) -> AppResult<()> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
 
     let gql_ctx = ctx
         .data::<Arc<GraphQLContext>>()
@@ -66,17 +215,16 @@ pub fn increment_session_expire(ctx: &Context<'_>) -> AppResult<()> {
             "Session token is missing".to_string(),
         ))?;
 
-    let mut redis_connection = app_state
-        .redis_pool_manager
-        .get()
-        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+    require_step_up(app_state, session_token)
+}
 
-    redis_connection
-        .expire::<String, usize>(
-            session_token.to_string(),
-            env_variables.session_expire_minutes * 60,
-        )
-        .map_err(|_| AppError::Internal("Failed to increment session expire".to_string()))?;
+/// Fail unless the account has verified its email
+pub fn verified_email_middleware(user_redis_session: &UserRedisSession) -> AppResult<()> {
+    if !user_redis_session.email_verified {
+        return Err(AppError::EmailNotVerified(
+            "Verify your email to continue".to_string(),
+        ));
+    }
 
     Ok(())
 }
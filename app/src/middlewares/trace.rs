@@ -1,5 +1,3 @@
-use std::{fmt::format, time::Duration};
-
 use tower_http::{
     LatencyUnit,
     classify::{ServerErrorsAsFailures, SharedClassifier},
@@ -0,0 +1,16 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub master_password: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct AccountDeletionResponse {
+    /// The account is erased at this time unless the deletion is cancelled first
+    pub deletion_scheduled_at: DateTime<Utc>,
+}
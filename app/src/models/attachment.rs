@@ -0,0 +1,80 @@
+use async_graphql::Enum;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Enum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum StorageBackend {
+    #[default]
+    #[sea_orm(string_value = "local")]
+    Local,
+    #[sea_orm(string_value = "postgres")]
+    Postgres,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "attachment")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub password_id: Uuid,
+    pub user_id: Uuid,
+    pub encrypted_file_name: String,
+    #[sea_orm(nullable)]
+    pub encrypted_content_type: Option<String>,
+    /// Plaintext size in bytes
+    pub size: i64,
+    pub client_encrypted: bool,
+    pub wrapped_file_key: String,
+    pub storage_backend: StorageBackend,
+    pub storage_key: String,
+
+    #[sea_orm(created_at)]
+    pub created_at: DateTime<Utc>,
+    #[sea_orm(updated_at)]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::password::Entity",
+        from = "Column::PasswordId",
+        to = "super::password::Column::Id"
+    )]
+    Password,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<super::password::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Password.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
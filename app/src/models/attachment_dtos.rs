@@ -0,0 +1,34 @@
+use async_graphql::{InputObject, SimpleObject, Upload};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// DTOs for API communication
+#[derive(InputObject)]
+pub struct UploadAttachmentRequest {
+    pub password_id: Uuid,
+    pub file: Upload,
+    /// Base64 key the file was already encrypted with on the client, the
+    /// server encrypts the file itself when left out
+    pub client_file_key: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct DeleteAttachmentRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentResponse {
+    pub id: Uuid,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    /// Plaintext size, or the uploaded size for client encrypted files
+    pub size: i64,
+    pub client_encrypted: bool,
+    /// Base64 key to decrypt the download with, only for client encrypted files
+    pub client_file_key: Option<String>,
+    pub download_url: String,
+    pub created_at: DateTime<Utc>,
+}
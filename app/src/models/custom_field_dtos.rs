@@ -0,0 +1,29 @@
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::custom_field::CustomFieldType;
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct CustomFieldInput {
+    pub name: String,
+    pub field_type: CustomFieldType,
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFieldResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub field_type: CustomFieldType,
+    /// Hidden values are left out until revealed with revealCustomField
+    pub value: Option<String>,
+    pub revealed: bool,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct RevealCustomFieldRequest {
+    pub id: Uuid,
+}
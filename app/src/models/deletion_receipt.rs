@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Proof that an account was erased, kept after the user is gone
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "deletion_receipt")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// The email itself is erased, its keyed hash lets a claim about it be checked
+    pub email_hash: String,
+    pub requested_at: DateTime<Utc>,
+    pub deleted_at: DateTime<Utc>,
+    pub password_count: i64,
+    pub attachment_count: i64,
+    pub recovery_code_count: i64,
+
+    #[sea_orm(created_at)]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
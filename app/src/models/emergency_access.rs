@@ -0,0 +1,113 @@
+use async_graphql::Enum;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Enum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum EmergencyAccessType {
+    #[default]
+    #[sea_orm(string_value = "view")]
+    View,
+    #[sea_orm(string_value = "takeover")]
+    Takeover,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Enum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum EmergencyAccessStatus {
+    /// Designated, no access has been requested
+    #[default]
+    #[sea_orm(string_value = "idle")]
+    Idle,
+    #[sea_orm(string_value = "requested")]
+    Requested,
+    #[sea_orm(string_value = "granted")]
+    Granted,
+    #[sea_orm(string_value = "declined")]
+    Declined,
+    /// The grantee took over the account, the grant cannot be used again
+    #[sea_orm(string_value = "taken_over")]
+    TakenOver,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "emergency_access")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub grantor_id: Uuid,
+    pub grantee_id: Uuid,
+    pub access_type: EmergencyAccessType,
+    pub status: EmergencyAccessStatus,
+    pub wait_days: i32,
+    pub wrapped_dek: String,
+    #[sea_orm(nullable)]
+    pub requested_at: Option<DateTime<Utc>>,
+    #[sea_orm(nullable)]
+    pub granted_at: Option<DateTime<Utc>>,
+    #[sea_orm(nullable)]
+    pub declined_at: Option<DateTime<Utc>>,
+    #[sea_orm(nullable)]
+    pub taken_over_at: Option<DateTime<Utc>>,
+
+    #[sea_orm(created_at)]
+    pub created_at: DateTime<Utc>,
+    #[sea_orm(updated_at)]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Model {
+    /// When a pending request is granted unless the grantor declines it first
+    pub fn grant_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            EmergencyAccessStatus::Requested => self
+                .requested_at
+                .map(|requested_at| requested_at + Duration::days(self.wait_days as i64)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::GrantorId",
+        to = "super::user::Column::Id"
+    )]
+    Grantor,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::GranteeId",
+        to = "super::user::Column::Id"
+    )]
+    Grantee,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
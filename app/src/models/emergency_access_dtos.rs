@@ -0,0 +1,84 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{
+    emergency_access::{EmergencyAccessStatus, EmergencyAccessType},
+    password_dtos::PasswordResponse,
+};
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct AddEmergencyContactRequest {
+    #[validate(email(message = "Invalid email"))]
+    pub email: String,
+    /// View only when left out
+    pub access_type: Option<EmergencyAccessType>,
+    /// Days a request waits for the grantor to decline before it is granted
+    #[validate(range(min = 1, max = 90, message = "Waiting period must be 1 to 90 days"))]
+    pub wait_days: i32,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct RemoveEmergencyContactRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct RequestEmergencyAccessRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct ApproveEmergencyAccessRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct DeclineEmergencyAccessRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct GetEmergencyVaultRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct EmergencyTakeoverRequest {
+    pub id: Uuid,
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub new_master_password: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct EmergencyAccessResponse {
+    pub id: Uuid,
+    pub grantor_email: String,
+    pub grantee_email: String,
+    pub access_type: EmergencyAccessType,
+    pub status: EmergencyAccessStatus,
+    pub wait_days: i32,
+    pub requested_at: Option<DateTime<Utc>>,
+    /// When a pending request is granted automatically
+    pub grant_at: Option<DateTime<Utc>>,
+    pub granted_at: Option<DateTime<Utc>>,
+    pub taken_over_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct EmergencyAccessesResponse {
+    /// Contacts the user trusts with their vault
+    pub trusted_contacts: Vec<EmergencyAccessResponse>,
+    /// Users who trust the user with their vault
+    pub trusted_by: Vec<EmergencyAccessResponse>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct EmergencyVaultResponse {
+    pub grantor_email: String,
+    pub passwords: Vec<PasswordResponse>,
+}
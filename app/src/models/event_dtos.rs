@@ -0,0 +1,40 @@
+use async_graphql::{Enum, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Enum, Serialize, Deserialize)]
+pub enum VaultChangeType {
+    Created,
+    Updated,
+    Deleted,
+    /// Several entries changed at once, fetch them with changesSince
+    Bulk,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct VaultChangedEvent {
+    /// Left out for bulk changes
+    pub entry_id: Option<Uuid>,
+    pub change_type: VaultChangeType,
+    pub revision: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SessionRevokedEvent {
+    pub revoked_at: DateTime<Utc>,
+}
+
+/// Published on redis, so the subscriptions on every instance see it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VaultEvent {
+    VaultChanged {
+        user_id: Uuid,
+        event: VaultChangedEvent,
+    },
+    SessionRevoked {
+        user_id: Uuid,
+        session_token_hash: String,
+        event: SessionRevokedEvent,
+    },
+}
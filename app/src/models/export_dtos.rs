@@ -0,0 +1,61 @@
+use async_graphql::{Enum, InputObject, SimpleObject, Upload};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::validators::export::validate_export_vault_request;
+
+/// Layout of an exported vault
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ExportFormat {
+    /// Archive encrypted with an Argon2id-derived key from the archive password
+    #[default]
+    EncryptedArchive,
+    /// The same archive without encryption
+    Json,
+    /// Logins only, in the column layout of browser exports
+    Csv,
+}
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+#[validate(schema(function = "validate_export_vault_request"))]
+pub struct ExportVaultRequest {
+    pub format: ExportFormat,
+    /// Password the encrypted archive is protected with
+    pub archive_password: Option<String>,
+    /// Re-entered master password, required for the plain formats
+    pub master_password: Option<String>,
+}
+
+/// Body of the KeePass download route
+#[derive(Clone, Default, Debug, Serialize, Deserialize, Validate)]
+pub struct ExportKdbxRequest {
+    /// Password the KeePass database is protected with
+    #[validate(length(min = 12, message = "Database password must be at least 12 characters"))]
+    pub password: String,
+}
+
+#[derive(InputObject)]
+pub struct RestoreVaultRequest {
+    pub file: Upload,
+    /// Password of an encrypted archive
+    pub archive_password: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct ExportVaultResponse {
+    pub file_name: String,
+    pub content_type: String,
+    /// Base64 encoded file
+    pub data: String,
+    pub entries: i32,
+    pub attachments: i32,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct RestoreVaultResponse {
+    pub folders: i32,
+    pub tags: i32,
+    pub entries: i32,
+    pub attachments: i32,
+}
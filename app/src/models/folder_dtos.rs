@@ -0,0 +1,52 @@
+use async_graphql::{Enum, InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+/// What happens to the entries and subfolders of a deleted folder
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum FolderDeleteMode {
+    /// Move them into the parent of the deleted folder
+    #[default]
+    MoveToParent,
+    /// Move the entries to the trash and delete the subfolders
+    Trash,
+}
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct CreateFolderRequest {
+    #[validate(length(min = 1, message = "Folder name is required"))]
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UpdateFolderRequest {
+    pub id: Uuid,
+    #[validate(length(min = 1, message = "Folder name is required"))]
+    pub name: String,
+    /// Folder to nest under, top level when left out
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct DeleteFolderRequest {
+    pub id: Uuid,
+    pub mode: FolderDeleteMode,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct FolderResponse {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct FoldersResponse {
+    pub folders: Vec<FolderResponse>,
+}
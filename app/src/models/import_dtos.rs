@@ -0,0 +1,61 @@
+use async_graphql::{Enum, InputObject, SimpleObject, Upload};
+use serde::{Deserialize, Serialize};
+
+/// Password manager an export file comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ImportFormat {
+    /// Bitwarden JSON, plain or protected with an export password
+    BitwardenJson,
+    /// 1Password 1PUX archive
+    OnePasswordPux,
+    LastpassCsv,
+    /// KeePass KDBX 4 database, opened with its master password
+    KeepassKdbx,
+    /// Chrome or Firefox password CSV
+    BrowserCsv,
+}
+
+/// What happens to imported entries matching an existing login
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ImportDuplicateMode {
+    /// Leave the existing login as it is and skip the imported one
+    #[default]
+    Skip,
+    /// Import the entry next to the existing login
+    KeepBoth,
+}
+
+// DTOs for API communication
+#[derive(InputObject)]
+pub struct ImportVaultRequest {
+    pub file: Upload,
+    pub format: ImportFormat,
+    /// Export or database password of encrypted files
+    pub password: Option<String>,
+    /// Preview the import without saving anything
+    pub dry_run: Option<bool>,
+    pub duplicates: Option<ImportDuplicateMode>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct ImportPreviewEntry {
+    pub name: Option<String>,
+    pub website_url: Option<String>,
+    pub account: Option<String>,
+    /// Folder path separated by `/`
+    pub folder: Option<String>,
+    pub duplicate: bool,
+    pub imported: bool,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct ImportVaultResponse {
+    pub dry_run: bool,
+    pub imported: i32,
+    pub duplicates: i32,
+    /// Entries without a website or name
+    pub skipped: i32,
+    /// Items of a kind other than logins, which are left out
+    pub unsupported: i32,
+    pub entries: Vec<ImportPreviewEntry>,
+}
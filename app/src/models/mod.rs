@@ -1,5 +1,31 @@
+pub mod account_dtos;
+pub mod attachment;
+pub mod attachment_dtos;
+pub mod collection;
+pub mod custom_field;
+pub mod custom_field_dtos;
+pub mod deletion_receipt;
+pub mod emergency_access;
+pub mod emergency_access_dtos;
+pub mod event_dtos;
+pub mod export_dtos;
+pub mod folder;
+pub mod folder_dtos;
+pub mod import_dtos;
+pub mod organization;
+pub mod organization_dtos;
+pub mod organization_member;
 pub mod password;
 pub mod password_dtos;
+pub mod password_share;
+pub mod password_tag;
+pub mod password_tombstone;
+pub mod password_uri;
 pub mod recovery_code;
+pub mod session_dtos;
+pub mod share_dtos;
+pub mod tag;
+pub mod tag_dtos;
 pub mod user;
 pub mod user_dtos;
+pub mod vault_item_dtos;
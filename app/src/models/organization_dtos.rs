@@ -0,0 +1,86 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::organization_member::OrganizationRole;
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(min = 1, message = "Organization name is required"))]
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct AddOrganizationMemberRequest {
+    pub organization_id: Uuid,
+    #[validate(email(message = "Invalid email"))]
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UpdateOrganizationMemberRequest {
+    pub organization_id: Uuid,
+    #[validate(email(message = "Invalid email"))]
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct RemoveOrganizationMemberRequest {
+    pub organization_id: Uuid,
+    #[validate(email(message = "Invalid email"))]
+    pub email: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct CreateCollectionRequest {
+    pub organization_id: Uuid,
+    #[validate(length(min = 1, message = "Collection name is required"))]
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct RenameCollectionRequest {
+    pub id: Uuid,
+    #[validate(length(min = 1, message = "Collection name is required"))]
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct DeleteCollectionRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct OrganizationMemberResponse {
+    pub user_id: Uuid,
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct CollectionResponse {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct OrganizationResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// The role of the current user
+    pub role: OrganizationRole,
+    pub members: Vec<OrganizationMemberResponse>,
+    pub collections: Vec<CollectionResponse>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct OrganizationsResponse {
+    pub organizations: Vec<OrganizationResponse>,
+}
@@ -1,25 +1,81 @@
+use async_graphql::Enum;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Enum,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum VaultItemType {
+    #[default]
+    #[sea_orm(string_value = "login")]
+    Login,
+    #[sea_orm(string_value = "secure_note")]
+    SecureNote,
+    #[sea_orm(string_value = "card")]
+    Card,
+    #[sea_orm(string_value = "identity")]
+    Identity,
+    #[sea_orm(string_value = "api_key")]
+    ApiKey,
+    #[sea_orm(string_value = "ssh_key")]
+    SshKey,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "password")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
     pub user_id: Uuid,
+    pub item_type: VaultItemType,
     #[sea_orm(nullable)]
     pub website_url: Option<String>,
     #[sea_orm(nullable)]
+    pub canonical_website_url: Option<String>,
+    #[sea_orm(nullable)]
+    pub website_host: Option<String>,
+    #[sea_orm(nullable)]
     pub app_name: Option<String>,
     #[sea_orm(nullable)]
     pub encrypted_username: Option<String>,
     #[sea_orm(nullable)]
     pub encrypted_email: Option<String>,
-    pub encrypted_password: String,
+    #[sea_orm(nullable)]
+    pub encrypted_password: Option<String>,
+    #[sea_orm(nullable)]
+    pub encrypted_payload: Option<String>,
+    #[sea_orm(nullable)]
+    pub payload_version: Option<i32>,
+    #[sea_orm(nullable)]
+    pub account_blind_index: Option<String>,
     #[sea_orm(nullable)]
     pub is_deleted: bool,
+    #[sea_orm(nullable)]
+    pub folder_id: Option<Uuid>,
+    pub favorite: bool,
+    #[sea_orm(nullable)]
+    pub encrypted_item_key: Option<String>,
+    #[sea_orm(nullable)]
+    pub collection_id: Option<Uuid>,
+    /// Revision of the owner when a personal entry last changed
+    pub revision: i64,
+    /// Revision of the owner when a personal entry was added
+    pub created_revision: i64,
+    /// Incremented on every update, updates based on an older version are rejected
+    pub version: i32,
 
     #[sea_orm(created_at)]
     pub created_at: DateTime<Utc>,
@@ -35,6 +91,28 @@ pub enum Relation {
         to = "super::user::Column::Id"
     )]
     User,
+    #[sea_orm(has_many = "super::password_uri::Entity")]
+    PasswordUri,
+    #[sea_orm(has_many = "super::custom_field::Entity")]
+    CustomField,
+    #[sea_orm(
+        belongs_to = "super::folder::Entity",
+        from = "Column::FolderId",
+        to = "super::folder::Column::Id"
+    )]
+    Folder,
+    #[sea_orm(has_many = "super::password_tag::Entity")]
+    PasswordTag,
+    #[sea_orm(has_many = "super::attachment::Entity")]
+    Attachment,
+    #[sea_orm(has_many = "super::password_share::Entity")]
+    PasswordShare,
+    #[sea_orm(
+        belongs_to = "super::collection::Entity",
+        from = "Column::CollectionId",
+        to = "super::collection::Column::Id"
+    )]
+    Collection,
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -43,4 +121,56 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::password_uri::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordUri.def()
+    }
+}
+
+impl Related<super::custom_field::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CustomField.def()
+    }
+}
+
+impl Related<super::folder::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Folder.def()
+    }
+}
+
+impl Related<super::password_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordTag.def()
+    }
+}
+
+impl Related<super::attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachment.def()
+    }
+}
+
+impl Related<super::password_share::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordShare.def()
+    }
+}
+
+impl Related<super::collection::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Collection.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::password_tag::Relation::Tag.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::password_tag::Relation::Password.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
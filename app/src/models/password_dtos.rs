@@ -4,9 +4,20 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::validators::password::{
-    validate_add_password_request, validate_get_password_request, validate_get_passwords_request,
-    validate_update_password_request,
+use crate::{
+    models::{
+        attachment_dtos::AttachmentResponse,
+        custom_field_dtos::{CustomFieldInput, CustomFieldResponse},
+        password_uri::UriMatchType,
+        tag_dtos::TagResponse,
+    },
+    validators::{
+        custom_field::validate_custom_fields,
+        password::{
+            validate_add_password_request, validate_get_password_request,
+            validate_get_passwords_request, validate_update_password_request,
+        },
+    },
 };
 
 // DTOs for API communication
@@ -18,17 +29,34 @@ pub struct AddPasswordRequest {
     pub username: Option<String>,
     pub email: Option<String>,
     pub password: String,
+    pub uris: Option<Vec<PasswordUriInput>>,
+    #[validate(custom = "validate_custom_fields")]
+    pub custom_fields: Option<Vec<CustomFieldInput>>,
+    pub folder_id: Option<Uuid>,
+    pub favorite: Option<bool>,
+    pub tag_ids: Option<Vec<Uuid>>,
+    pub allow_duplicate: Option<bool>,
+    /// Saves the entry to an organization collection instead of the personal vault
+    pub collection_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject, Validate)]
 #[validate(schema(function = "validate_update_password_request"))]
 pub struct UpdatePasswordRequest {
     pub id: Uuid,
+    /// Version of the entry the update is based on
+    pub version: i32,
     pub website_url: Option<String>,
     pub app_name: Option<String>,
     pub username: Option<String>,
     pub email: Option<String>,
     pub password: String,
+    pub uris: Option<Vec<PasswordUriInput>>,
+    #[validate(custom = "validate_custom_fields")]
+    pub custom_fields: Option<Vec<CustomFieldInput>>,
+    pub favorite: Option<bool>,
+    /// Replaces all tags of the entry when given
+    pub tag_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
@@ -36,18 +64,61 @@ pub struct DeletePasswordRequest {
     pub id: Uuid,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct RestorePasswordRequest {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct PurgePasswordRequest {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject, Validate)]
+pub struct MovePasswordsRequest {
+    #[validate(length(min = 1, message = "At least one id is required"))]
+    pub ids: Vec<Uuid>,
+    /// Target folder, top level when left out
+    pub folder_id: Option<Uuid>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
 pub struct PasswordResponse {
     pub id: Uuid,
+    pub version: i32,
     pub website_url: Option<String>,
+    pub canonical_website_url: Option<String>,
     pub app_name: Option<String>,
     pub username: Option<String>,
     pub email: Option<String>,
     pub password: String,
+    pub uris: Vec<PasswordUriResponse>,
+    pub custom_fields: Vec<CustomFieldResponse>,
+    pub folder_id: Option<Uuid>,
+    pub collection_id: Option<Uuid>,
+    pub favorite: bool,
+    pub trashed: bool,
+    pub tags: Vec<TagResponse>,
+    pub attachments: Vec<AttachmentResponse>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject)]
+pub struct PasswordUriInput {
+    pub uri: String,
+    pub match_type: Option<UriMatchType>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordUriResponse {
+    pub id: Uuid,
+    pub uri: String,
+    pub match_type: UriMatchType,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
 pub struct PasswordsPageResponse {
     pub passwords: Vec<PasswordResponse>,
@@ -55,11 +126,34 @@ pub struct PasswordsPageResponse {
     pub next_page_token: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject, Validate)]
+pub struct ChangesSinceRequest {
+    /// Revision of the last sync, 0 returns every personal entry as created
+    #[validate(range(min = 0, message = "Revision must not be negative"))]
+    pub revision: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct ChangesSinceResponse {
+    /// Revision to pass to the next sync
+    pub revision: i64,
+    pub created: Vec<PasswordResponse>,
+    pub updated: Vec<PasswordResponse>,
+    /// Ids of the entries deleted since
+    pub deleted: Vec<Uuid>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject, Validate)]
 #[validate(schema(function = "validate_get_passwords_request"))]
 pub struct GetPasswordsRequest {
     pub page: u64,
     pub next_page_token: Option<String>,
+    pub folder_id: Option<Uuid>,
+    pub tag_id: Option<Uuid>,
+    pub favorite: Option<bool>,
+    /// List the trash instead of the vault
+    pub trashed: Option<bool>,
+    pub collection_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, InputObject, Validate)]
@@ -71,3 +165,26 @@ pub struct GetPasswordRequest {
     pub username: Option<String>,
     pub email: Option<String>,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, InputObject, Validate)]
+pub struct CredentialsForUrlRequest {
+    #[validate(length(min = 1, message = "URL is required"))]
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct CredentialsForUrlResponse {
+    pub passwords: Vec<PasswordResponse>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct DuplicateCluster {
+    pub site: String,
+    pub account: String,
+    pub passwords: Vec<PasswordResponse>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct DuplicateClustersResponse {
+    pub clusters: Vec<DuplicateCluster>,
+}
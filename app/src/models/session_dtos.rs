@@ -0,0 +1,58 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct RevokeSessionRequest {
+    pub id: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SessionResponse {
+    /// Hash of the session token, the token itself is never exposed
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// Whether the vault of the session is locked
+    pub locked: bool,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SessionsResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UpdateSessionPolicyRequest {
+    /// The server setting when left out, can only be shorter than it
+    #[validate(range(min = 1, message = "Idle timeout must be at least a minute"))]
+    pub idle_minutes: Option<i32>,
+    /// The server setting when left out, can only be shorter than it
+    #[validate(range(min = 1, message = "Session lifetime must be at least a minute"))]
+    pub max_lifetime_minutes: Option<i32>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SessionPolicyResponse {
+    /// Applies to sessions created from now on
+    pub idle_minutes: i64,
+    pub max_lifetime_minutes: i64,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct VerifySessionRequest {
+    #[validate(length(min = 8, message = "Master password must be at least 8 characters"))]
+    pub master_password: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SessionVerificationResponse {
+    /// Sensitive operations are allowed until then without asking again
+    pub verified_until: DateTime<Utc>,
+}
@@ -0,0 +1,55 @@
+use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    models::{
+        custom_field_dtos::CustomFieldInput, password_dtos::PasswordResponse,
+        password_share::SharePermission,
+    },
+    validators::custom_field::validate_custom_fields,
+};
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct ShareEntryRequest {
+    pub id: Uuid,
+    #[validate(email(message = "Invalid email"))]
+    pub recipient_email: String,
+    /// Read only when left out, sharing again changes the permission
+    pub permission: Option<SharePermission>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct RevokeShareRequest {
+    pub id: Uuid,
+    #[validate(email(message = "Invalid email"))]
+    pub recipient_email: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UpdateSharedEntryRequest {
+    pub id: Uuid,
+    /// Version of the entry the update is based on
+    pub version: i32,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub password: String,
+    #[validate(custom = "validate_custom_fields")]
+    pub custom_fields: Option<Vec<CustomFieldInput>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SharedEntryResponse {
+    pub owner_email: String,
+    pub permission: SharePermission,
+    pub shared_at: DateTime<Utc>,
+    pub entry: PasswordResponse,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct SharedEntriesResponse {
+    pub entries: Vec<SharedEntryResponse>,
+}
@@ -0,0 +1,35 @@
+use async_graphql::{InputObject, SimpleObject};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// DTOs for API communication
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct CreateTagRequest {
+    #[validate(length(min = 1, message = "Tag name is required"))]
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct RenameTagRequest {
+    pub id: Uuid,
+    #[validate(length(min = 1, message = "Tag name is required"))]
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct DeleteTagRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct TagResponse {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct TagsResponse {
+    pub tags: Vec<TagResponse>,
+}
@@ -12,6 +12,26 @@ pub struct Model {
     pub email: String,
     pub master_password_hash: String,
     pub encrypted_dek: String,
+    #[sea_orm(nullable)]
+    pub public_key: Option<String>,
+    #[sea_orm(nullable)]
+    pub encrypted_private_key: Option<String>,
+    /// Bumped by every change to the user's personal entries
+    pub revision: i64,
+    /// Shorter idle timeout than the server's for new sessions
+    #[sea_orm(nullable)]
+    pub session_idle_minutes: Option<i32>,
+    /// Shorter absolute lifetime than the server's for new sessions
+    #[sea_orm(nullable)]
+    pub session_max_lifetime_minutes: Option<i32>,
+    /// Unverified accounts can't reach other users or take data out
+    #[sea_orm(nullable)]
+    pub email_verified_at: Option<DateTime<Utc>>,
+    #[sea_orm(nullable)]
+    pub deletion_requested_at: Option<DateTime<Utc>>,
+    /// The account is erased once this passes, unless the deletion is cancelled
+    #[sea_orm(nullable)]
+    pub deletion_scheduled_at: Option<DateTime<Utc>>,
 
     #[sea_orm(created_at)]
     pub created_at: DateTime<Utc>,
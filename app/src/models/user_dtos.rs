@@ -1,4 +1,5 @@
 use async_graphql::{InputObject, SimpleObject};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
@@ -7,6 +8,12 @@ use validator::Validate;
 pub struct UserSignupRequest {
     #[validate(email(message = "Invalid email"))]
     pub email: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct CompleteSignupRequest {
+    /// Token from the signup link sent to the email
+    pub token: String,
     #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
     pub master_password: String,
 }
@@ -25,11 +32,79 @@ pub struct UserLoginRequest {
     pub master_password: String,
 }
 
+/// A signed in session as kept in redis, the DEK is kept apart while the vault is unlocked
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedisSession {
+    pub id: Uuid,
+    pub email: String,
+    /// Sessions from before verification count as unverified
+    #[serde(default)]
+    pub email_verified: bool,
+    /// Left out by sessions from before session listing
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Absolute deadline, the session is rejected without one
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Idle timeout the session was created with
+    #[serde(default)]
+    pub idle_minutes: Option<i64>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    #[serde(default)]
+    pub ip_address: Option<String>,
+}
+
+impl RedisSession {
+    pub fn unlocked(self, dek: Vec<u8>) -> UserRedisSession {
+        UserRedisSession {
+            id: self.id,
+            email: self.email,
+            email_verified: self.email_verified,
+            dek,
+            expires_at: self.expires_at,
+            idle_minutes: self.idle_minutes,
+        }
+    }
+}
+
+/// A session with an unlocked vault
+#[derive(Debug, Clone, Default)]
 pub struct UserRedisSession {
     pub id: Uuid,
     pub email: String,
+    pub email_verified: bool,
     pub dek: Vec<u8>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub idle_minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SessionState {
+    /// Signed in, but the DEK was dropped by a lock or the auto-lock timeout
+    Locked(RedisSession),
+    Unlocked(UserRedisSession),
+}
+
+impl SessionState {
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            SessionState::Locked(redis_session) => redis_session.id,
+            SessionState::Unlocked(user_redis_session) => user_redis_session.id,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UnlockVaultRequest {
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub master_password: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct VerifyEmailRequest {
+    /// Token from the verification link sent to the email
+    pub token: String,
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
@@ -58,3 +133,22 @@ pub struct ChangeMasterPasswordRequest {
     #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
     pub new_master_password: String,
 }
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct ChangeEmailRequest {
+    #[validate(email(message = "Invalid email"))]
+    pub new_email: String,
+}
+
+/// Token from the link sent to the new or the old address of an email change
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct EmailChangeTokenRequest {
+    pub token: String,
+}
+
+/// An email change waiting for the new address to be confirmed, as kept in redis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEmailChange {
+    pub user_id: Uuid,
+    pub new_email: String,
+}
@@ -0,0 +1,182 @@
+use async_graphql::{InputObject, OneofObject, SimpleObject, Union};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+
+use crate::{
+    models::{
+        attachment_dtos::AttachmentResponse,
+        custom_field_dtos::{CustomFieldInput, CustomFieldResponse},
+        password::VaultItemType,
+    },
+    validators::{
+        custom_field::validate_custom_fields,
+        vault_item::{
+            validate_api_key, validate_card, validate_identity, validate_secure_note,
+            validate_ssh_key,
+        },
+    },
+};
+
+// Payloads of the non-login item types, stored as encrypted JSON
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject, InputObject, Validate)]
+#[graphql(input_name = "SecureNoteInput")]
+#[validate(schema(function = "validate_secure_note"))]
+pub struct SecureNote {
+    pub title: String,
+    pub note: String,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject, InputObject, Validate)]
+#[graphql(input_name = "CardInput")]
+#[validate(schema(function = "validate_card"))]
+pub struct Card {
+    pub title: String,
+    pub cardholder_name: String,
+    pub number: String,
+    pub brand: Option<String>,
+    pub expiry_month: u32,
+    pub expiry_year: u32,
+    pub security_code: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject, InputObject, Validate)]
+#[graphql(input_name = "IdentityInput")]
+#[validate(schema(function = "validate_identity"))]
+pub struct Identity {
+    pub title: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject, InputObject, Validate)]
+#[graphql(input_name = "ApiKeyInput")]
+#[validate(schema(function = "validate_api_key"))]
+pub struct ApiKey {
+    pub title: String,
+    pub key: String,
+    pub secret: Option<String>,
+    pub host: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject, InputObject, Validate)]
+#[graphql(input_name = "SshKeyInput")]
+#[validate(schema(function = "validate_ssh_key"))]
+pub struct SshKey {
+    pub title: String,
+    pub private_key: String,
+    pub public_key: Option<String>,
+    pub passphrase: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Union)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum VaultItemPayload {
+    SecureNote(SecureNote),
+    Card(Card),
+    Identity(Identity),
+    ApiKey(ApiKey),
+    SshKey(SshKey),
+}
+
+impl VaultItemPayload {
+    pub fn item_type(&self) -> VaultItemType {
+        match self {
+            VaultItemPayload::SecureNote(_) => VaultItemType::SecureNote,
+            VaultItemPayload::Card(_) => VaultItemType::Card,
+            VaultItemPayload::Identity(_) => VaultItemType::Identity,
+            VaultItemPayload::ApiKey(_) => VaultItemType::ApiKey,
+            VaultItemPayload::SshKey(_) => VaultItemType::SshKey,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, OneofObject)]
+pub enum VaultItemPayloadInput {
+    SecureNote(SecureNote),
+    Card(Card),
+    Identity(Identity),
+    ApiKey(ApiKey),
+    SshKey(SshKey),
+}
+
+impl Validate for VaultItemPayloadInput {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            VaultItemPayloadInput::SecureNote(secure_note) => secure_note.validate(),
+            VaultItemPayloadInput::Card(card) => card.validate(),
+            VaultItemPayloadInput::Identity(identity) => identity.validate(),
+            VaultItemPayloadInput::ApiKey(api_key) => api_key.validate(),
+            VaultItemPayloadInput::SshKey(ssh_key) => ssh_key.validate(),
+        }
+    }
+}
+
+impl From<VaultItemPayloadInput> for VaultItemPayload {
+    fn from(payload: VaultItemPayloadInput) -> Self {
+        match payload {
+            VaultItemPayloadInput::SecureNote(secure_note) => {
+                VaultItemPayload::SecureNote(secure_note)
+            }
+            VaultItemPayloadInput::Card(card) => VaultItemPayload::Card(card),
+            VaultItemPayloadInput::Identity(identity) => VaultItemPayload::Identity(identity),
+            VaultItemPayloadInput::ApiKey(api_key) => VaultItemPayload::ApiKey(api_key),
+            VaultItemPayloadInput::SshKey(ssh_key) => VaultItemPayload::SshKey(ssh_key),
+        }
+    }
+}
+
+// DTOs for API communication
+#[derive(Clone, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct AddVaultItemRequest {
+    #[validate]
+    pub payload: VaultItemPayloadInput,
+    #[validate(custom = "validate_custom_fields")]
+    pub custom_fields: Option<Vec<CustomFieldInput>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, InputObject, Validate)]
+pub struct UpdateVaultItemRequest {
+    pub id: Uuid,
+    #[validate]
+    pub payload: VaultItemPayloadInput,
+    #[validate(custom = "validate_custom_fields")]
+    pub custom_fields: Option<Vec<CustomFieldInput>>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct GetVaultItemRequest {
+    pub id: Uuid,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, InputObject)]
+pub struct GetVaultItemsRequest {
+    pub item_type: Option<VaultItemType>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct VaultItemResponse {
+    pub id: Uuid,
+    pub item_type: VaultItemType,
+    pub payload_version: i32,
+    pub payload: VaultItemPayload,
+    pub custom_fields: Vec<CustomFieldResponse>,
+    pub attachments: Vec<AttachmentResponse>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, SimpleObject)]
+pub struct VaultItemsResponse {
+    pub items: Vec<VaultItemResponse>,
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, Response, header},
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::app_state::AppState,
+    middlewares::auth::{
+        extend_session_expire, get_user_redis_session, session_token_from_headers,
+    },
+    services::attachment,
+    utils::error::{AppError, AppResult},
+};
+
+// quotes and non ascii characters are not safe in a bare header value
+fn content_disposition(file_name: &str) -> String {
+    let file_name: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || " ._-()".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    format!("attachment; filename=\"{}\"", file_name)
+}
+
+pub async fn download_attachment(
+    State(app_state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> AppResult<Response<Body>> {
+    let session_token = session_token_from_headers(&headers).ok_or(AppError::Authorization(
+        "Session token is missing".to_string(),
+    ))?;
+
+    let user_redis_session = get_user_redis_session(&app_state, &session_token)?;
+
+    let download = attachment::download_attachment(&app_state, &user_redis_session, id).await?;
+
+    extend_session_expire(&app_state, &session_token, &user_redis_session)?;
+
+    let content_type = download
+        .content_type
+        .and_then(|content_type| HeaderValue::from_str(&content_type).ok())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CONTENT_LENGTH, download.size)
+        .header(
+            header::CONTENT_DISPOSITION,
+            content_disposition(&download.file_name),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(download.stream))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    http::{HeaderMap, Response, header},
+};
+use validator::Validate;
+
+use crate::{
+    dtos::app_state::AppState,
+    middlewares::auth::{
+        extend_session_expire, get_user_redis_session, require_step_up, session_token_from_headers,
+        verified_email_middleware,
+    },
+    models::export_dtos::ExportKdbxRequest,
+    services::export,
+    utils::error::{AppError, AppResult},
+};
+
+pub async fn export_kdbx(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ExportKdbxRequest>,
+) -> AppResult<Response<Body>> {
+    let session_token = session_token_from_headers(&headers).ok_or(AppError::Authorization(
+        "Session token is missing".to_string(),
+    ))?;
+
+    let user_redis_session = get_user_redis_session(&app_state, &session_token)?;
+
+    verified_email_middleware(&user_redis_session)?;
+
+    request
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    require_step_up(&app_state, &session_token)?;
+
+    let download = export::export_kdbx(&app_state, &user_redis_session, request).await?;
+
+    extend_session_expire(&app_state, &session_token, &user_redis_session)?;
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", download.file_name),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(download.stream))
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {}", e)))
+}
@@ -1,46 +1,87 @@
 mod mutation;
 mod query;
 pub mod service_schema;
+mod subscription;
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
-use async_graphql::http::GraphiQLSource;
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{
+    Data,
+    http::{ALL_WEBSOCKET_PROTOCOLS, GraphiQLSource},
+};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::{
     Extension,
-    http::{HeaderMap, header},
-    response::{Html, IntoResponse},
+    extract::{ConnectInfo, WebSocketUpgrade},
+    http::HeaderMap,
+    response::{Html, IntoResponse, Response},
 };
 use service_schema::ServiceSchema;
 
-use crate::dtos::graphql_context::GraphQLContext;
+use crate::{
+    dtos::graphql_context::GraphQLContext, middlewares::auth::session_token_from_headers,
+    utils::error::AppError,
+};
 
 pub async fn playground() -> impl IntoResponse {
-    Html(GraphiQLSource::build().endpoint("/").finish())
+    Html(
+        GraphiQLSource::build()
+            .endpoint("/")
+            .subscription_endpoint("/ws")
+            .finish(),
+    )
 }
 
 pub async fn graphql_handler(
     schema: Extension<ServiceSchema>,
+    ConnectInfo(peer_address): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
-    let cookie = headers
-        .get(header::COOKIE)
-        .and_then(|value| value.to_str().ok())
-        .map(|s| s.to_string());
-
-    let session_token = cookie.and_then(|cookie| {
-        let token = cookie
-            .split(';')
-            .find(|cookie| cookie.starts_with("session_token="))
-            .map(|cookie| cookie.replace("session_token=", ""));
-        token
-    });
+    let session_token = session_token_from_headers(&headers);
 
     let gql_ctx = Arc::new(GraphQLContext {
         session_token,
         headers: Some(headers),
+        peer_ip: Some(peer_address.ip()),
     });
 
-    schema.execute(req.into_inner().data(gql_ctx)).await.into()
+    let mut response = schema.execute(req.into_inner().data(gql_ctx)).await;
+
+    for error in response.errors.iter_mut() {
+        if let Some(extensions) = error
+            .source::<AppError>()
+            .and_then(AppError::graphql_extensions)
+        {
+            error.extensions = Some(extensions);
+        }
+    }
+
+    response.into()
+}
+
+/// Subscriptions over the GraphQL WebSocket protocols, authenticated with the session cookie
+pub async fn graphql_ws_handler(
+    Extension(schema): Extension<ServiceSchema>,
+    protocol: GraphQLProtocol,
+    ConnectInfo(peer_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    websocket: WebSocketUpgrade,
+) -> Response {
+    let session_token = session_token_from_headers(&headers);
+
+    let mut data = Data::default();
+    data.insert(Arc::new(GraphQLContext {
+        session_token,
+        headers: Some(headers),
+        peer_ip: Some(peer_address.ip()),
+    }));
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .with_data(data)
+                .serve()
+        })
 }
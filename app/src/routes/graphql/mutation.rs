@@ -3,19 +3,79 @@ use validator::Validate;
 
 use crate::{
     dtos::response::{GraphqlGenericResponse, GraphqlResponse},
-    middlewares::auth::{increment_session_expire, session_auth_middleware},
+    middlewares::auth::{
+        increment_session_expire, session_auth_allow_locked_middleware, session_auth_middleware,
+        step_up_middleware, verified_email_middleware,
+    },
     models::{
-        password_dtos::{AddPasswordRequest, DeletePasswordRequest, UpdatePasswordRequest},
+        account_dtos::{AccountDeletionResponse, DeleteAccountRequest},
+        attachment_dtos::{AttachmentResponse, DeleteAttachmentRequest, UploadAttachmentRequest},
+        emergency_access_dtos::{
+            AddEmergencyContactRequest, ApproveEmergencyAccessRequest,
+            DeclineEmergencyAccessRequest, EmergencyTakeoverRequest, RemoveEmergencyContactRequest,
+            RequestEmergencyAccessRequest,
+        },
+        export_dtos::{
+            ExportVaultRequest, ExportVaultResponse, RestoreVaultRequest, RestoreVaultResponse,
+        },
+        folder_dtos::{
+            CreateFolderRequest, DeleteFolderRequest, FolderResponse, UpdateFolderRequest,
+        },
+        import_dtos::{ImportVaultRequest, ImportVaultResponse},
+        organization_dtos::{
+            AddOrganizationMemberRequest, CollectionResponse, CreateCollectionRequest,
+            CreateOrganizationRequest, DeleteCollectionRequest, OrganizationResponse,
+            RemoveOrganizationMemberRequest, RenameCollectionRequest,
+            UpdateOrganizationMemberRequest,
+        },
+        password_dtos::{
+            AddPasswordRequest, DeletePasswordRequest, MovePasswordsRequest, PurgePasswordRequest,
+            RestorePasswordRequest, UpdatePasswordRequest,
+        },
+        session_dtos::{
+            RevokeSessionRequest, SessionPolicyResponse, SessionVerificationResponse,
+            UpdateSessionPolicyRequest, VerifySessionRequest,
+        },
+        share_dtos::{RevokeShareRequest, ShareEntryRequest, UpdateSharedEntryRequest},
+        tag_dtos::{CreateTagRequest, DeleteTagRequest, RenameTagRequest, TagResponse},
         user_dtos::{
-            ChangeMasterPasswordRequest, RecoveryAccountRequest, RecoveryKeyResponse,
-            UserLoginRequest, UserSignupRequest, UserSignupResponse,
+            ChangeEmailRequest, ChangeMasterPasswordRequest, CompleteSignupRequest,
+            EmailChangeTokenRequest, RecoveryAccountRequest, RecoveryKeyResponse,
+            UnlockVaultRequest, UserLoginRequest, UserSignupRequest, UserSignupResponse,
+            VerifyEmailRequest,
         },
+        vault_item_dtos::{AddVaultItemRequest, UpdateVaultItemRequest},
     },
     services::{
+        account::{cancel_account_deletion, delete_account},
+        attachment::{delete_attachment, upload_attachment},
         auth::{
-            change_master_password, generate_recovery_keys, login, logout, recover_account, signup,
+            cancel_email_change, change_email, change_master_password, complete_signup,
+            confirm_email_change, generate_recovery_keys, login, logout, recover_account,
+            send_verification_email, signup, verify_email,
+        },
+        emergency_access::{
+            add_emergency_contact, approve_emergency_access, decline_emergency_access,
+            emergency_takeover, remove_emergency_contact, request_emergency_access,
         },
-        password::{add_password, delete_password, update_password},
+        export::{export_vault, restore_vault},
+        folder::{create_folder, delete_folder, update_folder},
+        import::import_vault,
+        organization::{
+            add_organization_member, create_collection, create_organization, delete_collection,
+            remove_organization_member, rename_collection, update_organization_member,
+        },
+        password::{
+            add_password, delete_password, move_passwords, purge_password, restore_password,
+            update_password,
+        },
+        session::{
+            lock_vault, revoke_all_other_sessions, revoke_session, unlock_vault,
+            update_session_policy, verify_session,
+        },
+        share::{revoke_share, share_entry, update_shared_entry},
+        tag::{create_tag, delete_tag, rename_tag},
+        vault_item::{add_vault_item, delete_vault_item, update_vault_item},
     },
     utils::error::{AppError, AppResult},
 };
@@ -29,7 +89,7 @@ impl Mutation {
         &self,
         ctx: &Context<'_>,
         request: UserSignupRequest,
-    ) -> AppResult<GraphqlResponse<UserSignupResponse>> {
+    ) -> AppResult<GraphqlGenericResponse> {
         request
             .validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
@@ -37,6 +97,18 @@ impl Mutation {
         signup(ctx, request).await
     }
 
+    async fn complete_signup(
+        &self,
+        ctx: &Context<'_>,
+        request: CompleteSignupRequest,
+    ) -> AppResult<GraphqlResponse<UserSignupResponse>> {
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        complete_signup(ctx, request).await
+    }
+
     async fn login(
         &self,
         ctx: &Context<'_>,
@@ -50,9 +122,9 @@ impl Mutation {
     }
 
     async fn logout(&self, ctx: &Context<'_>) -> AppResult<GraphqlGenericResponse> {
-        let user_redis_session = session_auth_middleware(ctx)?;
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
 
-        logout(ctx, &user_redis_session).await
+        logout(ctx, &session_state).await
     }
 
     async fn generate_recovery_keys(
@@ -61,9 +133,11 @@ impl Mutation {
     ) -> AppResult<GraphqlResponse<RecoveryKeyResponse>> {
         let user_redis_session = session_auth_middleware(ctx)?;
 
+        step_up_middleware(ctx)?;
+
         let response = generate_recovery_keys(ctx, &user_redis_session).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
@@ -77,11 +151,7 @@ impl Mutation {
             .validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
 
-        let response = recover_account(ctx, request).await;
-
-        increment_session_expire(ctx)?;
-
-        response
+        recover_account(ctx, request).await
     }
 
     async fn change_master_password(
@@ -91,14 +161,183 @@ impl Mutation {
     ) -> AppResult<GraphqlGenericResponse> {
         let user_redis_session = session_auth_middleware(ctx)?;
 
+        step_up_middleware(ctx)?;
+
         let response = change_master_password(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn send_verification_email(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        send_verification_email(ctx, &session_state).await
+    }
+
+    async fn verify_email(
+        &self,
+        ctx: &Context<'_>,
+        request: VerifyEmailRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        verify_email(ctx, request).await
+    }
+
+    async fn change_email(
+        &self,
+        ctx: &Context<'_>,
+        request: ChangeEmailRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = change_email(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
+
+    async fn confirm_email_change(
+        &self,
+        ctx: &Context<'_>,
+        request: EmailChangeTokenRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        confirm_email_change(ctx, request).await
+    }
+
+    async fn cancel_email_change(
+        &self,
+        ctx: &Context<'_>,
+        request: EmailChangeTokenRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        cancel_email_change(ctx, request).await
+    }
+
+    async fn delete_account(
+        &self,
+        ctx: &Context<'_>,
+        request: DeleteAccountRequest,
+    ) -> AppResult<GraphqlResponse<AccountDeletionResponse>> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        delete_account(ctx, &session_state, request).await
+    }
+
+    async fn cancel_account_deletion(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        cancel_account_deletion(ctx, &session_state).await
+    }
     // ********************* AUTH ************************//
 
+    // ********************* SESSION ************************//
+    async fn revoke_session(
+        &self,
+        ctx: &Context<'_>,
+        request: RevokeSessionRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = revoke_session(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn revoke_all_other_sessions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = revoke_all_other_sessions(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn verify_session(
+        &self,
+        ctx: &Context<'_>,
+        request: VerifySessionRequest,
+    ) -> AppResult<GraphqlResponse<SessionVerificationResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = verify_session(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn lock_vault(&self, ctx: &Context<'_>) -> AppResult<GraphqlGenericResponse> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        lock_vault(ctx, &session_state).await
+    }
+
+    async fn unlock_vault(
+        &self,
+        ctx: &Context<'_>,
+        request: UnlockVaultRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = unlock_vault(ctx, &session_state, request).await?;
+
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        Ok(response)
+    }
+
+    async fn update_session_policy(
+        &self,
+        ctx: &Context<'_>,
+        request: UpdateSessionPolicyRequest,
+    ) -> AppResult<GraphqlResponse<SessionPolicyResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = update_session_policy(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* SESSION ************************//
+
     // ********************* PASSWORD ************************//
     async fn add_password(
         &self,
@@ -113,7 +352,7 @@ impl Mutation {
 
         let response = add_password(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
@@ -131,7 +370,7 @@ impl Mutation {
 
         let response = update_password(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
@@ -145,9 +384,591 @@ impl Mutation {
 
         let response = delete_password(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn restore_password(
+        &self,
+        ctx: &Context<'_>,
+        request: RestorePasswordRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = restore_password(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn purge_password(
+        &self,
+        ctx: &Context<'_>,
+        request: PurgePasswordRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = purge_password(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn move_passwords(
+        &self,
+        ctx: &Context<'_>,
+        request: MovePasswordsRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = move_passwords(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
     // ********************* PASSWORD ************************//
+
+    // ********************* VAULT ITEM ************************//
+    async fn add_vault_item(
+        &self,
+        ctx: &Context<'_>,
+        request: AddVaultItemRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = add_vault_item(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn update_vault_item(
+        &self,
+        ctx: &Context<'_>,
+        request: UpdateVaultItemRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = update_vault_item(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn delete_vault_item(
+        &self,
+        ctx: &Context<'_>,
+        request: DeletePasswordRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = delete_vault_item(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* VAULT ITEM ************************//
+
+    // ********************* FOLDER ************************//
+    async fn create_folder(
+        &self,
+        ctx: &Context<'_>,
+        request: CreateFolderRequest,
+    ) -> AppResult<GraphqlResponse<FolderResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = create_folder(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn update_folder(
+        &self,
+        ctx: &Context<'_>,
+        request: UpdateFolderRequest,
+    ) -> AppResult<GraphqlResponse<FolderResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = update_folder(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn delete_folder(
+        &self,
+        ctx: &Context<'_>,
+        request: DeleteFolderRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = delete_folder(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* FOLDER ************************//
+
+    // ********************* TAG ************************//
+    async fn create_tag(
+        &self,
+        ctx: &Context<'_>,
+        request: CreateTagRequest,
+    ) -> AppResult<GraphqlResponse<TagResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = create_tag(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn rename_tag(
+        &self,
+        ctx: &Context<'_>,
+        request: RenameTagRequest,
+    ) -> AppResult<GraphqlResponse<TagResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = rename_tag(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn delete_tag(
+        &self,
+        ctx: &Context<'_>,
+        request: DeleteTagRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = delete_tag(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* TAG ************************//
+
+    // ********************* ATTACHMENT ************************//
+    async fn upload_attachment(
+        &self,
+        ctx: &Context<'_>,
+        request: UploadAttachmentRequest,
+    ) -> AppResult<GraphqlResponse<AttachmentResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = upload_attachment(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn delete_attachment(
+        &self,
+        ctx: &Context<'_>,
+        request: DeleteAttachmentRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = delete_attachment(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* ATTACHMENT ************************//
+
+    // ********************* SHARE ************************//
+    async fn share_entry(
+        &self,
+        ctx: &Context<'_>,
+        request: ShareEntryRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = share_entry(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn revoke_share(
+        &self,
+        ctx: &Context<'_>,
+        request: RevokeShareRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = revoke_share(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn update_shared_entry(
+        &self,
+        ctx: &Context<'_>,
+        request: UpdateSharedEntryRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = update_shared_entry(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* SHARE ************************//
+
+    // ********************* ORGANIZATION ************************//
+    async fn create_organization(
+        &self,
+        ctx: &Context<'_>,
+        request: CreateOrganizationRequest,
+    ) -> AppResult<GraphqlResponse<OrganizationResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = create_organization(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn add_organization_member(
+        &self,
+        ctx: &Context<'_>,
+        request: AddOrganizationMemberRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = add_organization_member(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn update_organization_member(
+        &self,
+        ctx: &Context<'_>,
+        request: UpdateOrganizationMemberRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = update_organization_member(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn remove_organization_member(
+        &self,
+        ctx: &Context<'_>,
+        request: RemoveOrganizationMemberRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = remove_organization_member(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn create_collection(
+        &self,
+        ctx: &Context<'_>,
+        request: CreateCollectionRequest,
+    ) -> AppResult<GraphqlResponse<CollectionResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = create_collection(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn rename_collection(
+        &self,
+        ctx: &Context<'_>,
+        request: RenameCollectionRequest,
+    ) -> AppResult<GraphqlResponse<CollectionResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = rename_collection(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn delete_collection(
+        &self,
+        ctx: &Context<'_>,
+        request: DeleteCollectionRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = delete_collection(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* ORGANIZATION ************************//
+
+    // ********************* EMERGENCY ACCESS ************************//
+    async fn add_emergency_contact(
+        &self,
+        ctx: &Context<'_>,
+        request: AddEmergencyContactRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = add_emergency_contact(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn remove_emergency_contact(
+        &self,
+        ctx: &Context<'_>,
+        request: RemoveEmergencyContactRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = remove_emergency_contact(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn request_emergency_access(
+        &self,
+        ctx: &Context<'_>,
+        request: RequestEmergencyAccessRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        let response = request_emergency_access(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn approve_emergency_access(
+        &self,
+        ctx: &Context<'_>,
+        request: ApproveEmergencyAccessRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = approve_emergency_access(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn decline_emergency_access(
+        &self,
+        ctx: &Context<'_>,
+        request: DeclineEmergencyAccessRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = decline_emergency_access(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn emergency_takeover(
+        &self,
+        ctx: &Context<'_>,
+        request: EmergencyTakeoverRequest,
+    ) -> AppResult<GraphqlGenericResponse> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = emergency_takeover(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* EMERGENCY ACCESS ************************//
+
+    // ********************* IMPORT ************************//
+    async fn import_vault(
+        &self,
+        ctx: &Context<'_>,
+        request: ImportVaultRequest,
+    ) -> AppResult<GraphqlResponse<ImportVaultResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = import_vault(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* IMPORT ************************//
+
+    // ********************* EXPORT ************************//
+    async fn export_vault(
+        &self,
+        ctx: &Context<'_>,
+        request: ExportVaultRequest,
+    ) -> AppResult<GraphqlResponse<ExportVaultResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        verified_email_middleware(&user_redis_session)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        step_up_middleware(ctx)?;
+
+        let response = export_vault(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn restore_vault(
+        &self,
+        ctx: &Context<'_>,
+        request: RestoreVaultRequest,
+    ) -> AppResult<GraphqlResponse<RestoreVaultResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = restore_vault(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* EXPORT ************************//
 }
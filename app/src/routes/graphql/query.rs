@@ -5,14 +5,38 @@ use crate::{
     dtos::response::{GraphqlGenericResponse, GraphqlResponse},
     middlewares::auth::{increment_session_expire, session_auth_middleware},
     models::{
+        custom_field_dtos::{CustomFieldResponse, RevealCustomFieldRequest},
+        emergency_access_dtos::{
+            EmergencyAccessesResponse, EmergencyVaultResponse, GetEmergencyVaultRequest,
+        },
+        folder_dtos::FoldersResponse,
+        organization_dtos::OrganizationsResponse,
         password_dtos::{
-            GetPasswordRequest, GetPasswordsRequest, PasswordResponse, PasswordsPageResponse,
+            ChangesSinceRequest, ChangesSinceResponse, CredentialsForUrlRequest,
+            CredentialsForUrlResponse, DuplicateClustersResponse, GetPasswordRequest,
+            GetPasswordsRequest, PasswordResponse, PasswordsPageResponse,
         },
+        session_dtos::{SessionPolicyResponse, SessionsResponse},
+        share_dtos::SharedEntriesResponse,
+        tag_dtos::TagsResponse,
         user_dtos::CheckRecoveryCodeValidityRequest,
+        vault_item_dtos::{
+            GetVaultItemRequest, GetVaultItemsRequest, VaultItemResponse, VaultItemsResponse,
+        },
     },
     services::{
         auth::check_recovery_code_validity,
-        password::{get_password, get_passwords},
+        custom_field::reveal_custom_field,
+        emergency_access::{get_emergency_accesses, get_emergency_vault},
+        folder::get_folders,
+        organization::get_organizations,
+        password::{
+            changes_since, credentials_for_url, find_duplicates, get_password, get_passwords,
+        },
+        session::{get_session_policy, my_sessions},
+        share::shared_with_me,
+        tag::get_tags,
+        vault_item::{get_vault_item, get_vault_items},
     },
     utils::error::{AppError, AppResult},
 };
@@ -31,12 +55,37 @@ impl Query {
 
         let response = check_recovery_code_validity(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
     // ********************* AUTH ************************//
 
+    // ********************* SESSION ************************//
+    async fn my_sessions(&self, ctx: &Context<'_>) -> AppResult<GraphqlResponse<SessionsResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = my_sessions(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn session_policy(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlResponse<SessionPolicyResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_session_policy(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* SESSION ************************//
+
     // ********************* PASSWORD ************************//
     async fn all_passwords(
         &self,
@@ -51,7 +100,7 @@ impl Query {
 
         let response = get_passwords(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
@@ -69,9 +118,187 @@ impl Query {
 
         let response = get_password(ctx, &user_redis_session, request).await;
 
-        increment_session_expire(ctx)?;
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn credentials_for_url(
+        &self,
+        ctx: &Context<'_>,
+        request: CredentialsForUrlRequest,
+    ) -> AppResult<GraphqlResponse<CredentialsForUrlResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = credentials_for_url(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn changes_since(
+        &self,
+        ctx: &Context<'_>,
+        request: ChangesSinceRequest,
+    ) -> AppResult<GraphqlResponse<ChangesSinceResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let response = changes_since(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn find_duplicates(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlResponse<DuplicateClustersResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = find_duplicates(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
 
         response
     }
     // ********************* PASSWORD ************************//
+
+    // ********************* VAULT ITEM ************************//
+    async fn all_vault_items(
+        &self,
+        ctx: &Context<'_>,
+        request: GetVaultItemsRequest,
+    ) -> AppResult<GraphqlResponse<VaultItemsResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_vault_items(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn get_vault_item(
+        &self,
+        ctx: &Context<'_>,
+        request: GetVaultItemRequest,
+    ) -> AppResult<GraphqlResponse<VaultItemResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_vault_item(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* VAULT ITEM ************************//
+
+    // ********************* FOLDER ************************//
+    async fn all_folders(&self, ctx: &Context<'_>) -> AppResult<GraphqlResponse<FoldersResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_folders(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* FOLDER ************************//
+
+    // ********************* TAG ************************//
+    async fn all_tags(&self, ctx: &Context<'_>) -> AppResult<GraphqlResponse<TagsResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_tags(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* TAG ************************//
+
+    // ********************* CUSTOM FIELD ************************//
+    async fn reveal_custom_field(
+        &self,
+        ctx: &Context<'_>,
+        request: RevealCustomFieldRequest,
+    ) -> AppResult<GraphqlResponse<CustomFieldResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = reveal_custom_field(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* CUSTOM FIELD ************************//
+
+    // ********************* SHARE ************************//
+    async fn shared_with_me(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlResponse<SharedEntriesResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = shared_with_me(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* SHARE ************************//
+
+    // ********************* ORGANIZATION ************************//
+    async fn all_organizations(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlResponse<OrganizationsResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_organizations(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* ORGANIZATION ************************//
+
+    // ********************* EMERGENCY ACCESS ************************//
+    async fn all_emergency_accesses(
+        &self,
+        ctx: &Context<'_>,
+    ) -> AppResult<GraphqlResponse<EmergencyAccessesResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_emergency_accesses(ctx, &user_redis_session).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+
+    async fn emergency_vault(
+        &self,
+        ctx: &Context<'_>,
+        request: GetEmergencyVaultRequest,
+    ) -> AppResult<GraphqlResponse<EmergencyVaultResponse>> {
+        let user_redis_session = session_auth_middleware(ctx)?;
+
+        let response = get_emergency_vault(ctx, &user_redis_session, request).await;
+
+        increment_session_expire(ctx, &user_redis_session)?;
+
+        response
+    }
+    // ********************* EMERGENCY ACCESS ************************//
 }
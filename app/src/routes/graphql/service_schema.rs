@@ -1,18 +1,19 @@
 use std::sync::Arc;
 
-use async_graphql::{EmptySubscription, Schema};
+use async_graphql::Schema;
 
 use crate::dtos::app_state::AppState;
 
 use super::{
     mutation::Mutation,
     query::{self, Query},
+    subscription::Subscription,
 };
 
-pub type ServiceSchema = Schema<Query, Mutation, EmptySubscription>;
+pub type ServiceSchema = Schema<Query, Mutation, Subscription>;
 
 pub fn schema(app_state: Arc<AppState>) -> ServiceSchema {
-    Schema::build(query::Query, Mutation, EmptySubscription)
+    Schema::build(query::Query, Mutation, Subscription)
         .data(app_state.clone())
         .finish()
 }
@@ -0,0 +1,37 @@
+use async_graphql::{Context, Subscription};
+use futures::Stream;
+
+use crate::{
+    middlewares::auth::session_auth_allow_locked_middleware,
+    models::event_dtos::{SessionRevokedEvent, VaultChangedEvent},
+    services::events::{session_revoked, vault_changed},
+    utils::error::AppError,
+};
+
+pub struct Subscription;
+
+#[Subscription]
+impl Subscription {
+    // ********************* EVENTS ************************//
+    /// Changes to the personal vault, made by any session of the user.
+    /// Delivered while the vault is locked too, since no secrets are sent
+    async fn vault_changed(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = VaultChangedEvent> + use<>, AppError> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        vault_changed(ctx, &session_state)
+    }
+
+    /// Fires once when the session of this connection is revoked
+    async fn session_revoked(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<impl Stream<Item = SessionRevokedEvent> + use<>, AppError> {
+        let session_state = session_auth_allow_locked_middleware(ctx)?;
+
+        session_revoked(ctx, &session_state)
+    }
+    // ********************* EVENTS ************************//
+}
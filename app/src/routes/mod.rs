@@ -1,22 +1,42 @@
+mod attachment;
+mod export;
 pub mod graphql;
 mod health;
 
 use std::sync::Arc;
 
-use axum::{Extension, Router, routing::get};
+use axum::{
+    Extension, Router,
+    routing::{get, post},
+};
 pub use graphql::service_schema::schema;
 use health::health;
-use tower_http::trace::TraceLayer;
+use tower_http::limit::RequestBodyLimitLayer;
 
 use crate::{dtos::app_state::AppState, middlewares::trace::tracer};
 
 pub fn init_routes(app_state: Arc<AppState>) -> Router {
     let schema = schema(app_state.clone());
 
+    // room for the rest of a multipart request around the largest upload
+    let body_limit = app_state
+        .env_variables
+        .attachment_max_bytes
+        .max(app_state.env_variables.import_max_bytes) as usize
+        + 1024 * 1024;
+
     tracing_subscriber::fmt::init();
 
     Router::new()
-        .route("/", get(graphql::playground).post(graphql::graphql_handler))
+        .route(
+            "/",
+            get(graphql::playground)
+                .post(graphql::graphql_handler)
+                .layer(RequestBodyLimitLayer::new(body_limit)),
+        )
+        .route("/ws", get(graphql::graphql_ws_handler))
+        .route("/attachments/{id}", get(attachment::download_attachment))
+        .route("/exports/kdbx", post(export::export_kdbx))
         .route("/health", get(health))
         .layer(Extension(schema))
         .layer(tracer())
@@ -0,0 +1,510 @@
+use std::sync::Arc;
+
+use async_graphql::Context;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QuerySelect, Set, TransactionError, TransactionTrait,
+    sea_query::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        graphql_context::GraphQLContext,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        account_dtos::{AccountDeletionResponse, DeleteAccountRequest},
+        attachment, collection, custom_field, deletion_receipt, organization,
+        organization_member::{self, OrganizationRole},
+        password, password_uri, recovery_code, user,
+        user_dtos::SessionState,
+    },
+    services::{
+        attachment::delete_attachment_blobs,
+        crypto::hash_receipt_email,
+        mail::{MailTemplate, send_mail},
+        session::{check_master_password, forget_devices, revoke_user_sessions},
+        throttle::{AuthAction, AuthAttempt, clear_failures},
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Accounts whose grace period has passed
+pub fn is_deletion_due(user: &user::Model, now: DateTime<Utc>) -> bool {
+    user.deletion_scheduled_at
+        .is_some_and(|deletion_scheduled_at| deletion_scheduled_at <= now)
+}
+
+/// What becomes of the organizations of a user once the user is gone
+#[derive(Debug, Default)]
+pub struct OrganizationPlan {
+    /// Organizations without other members, erased with the user
+    pub sole_organization_ids: Vec<Uuid>,
+    /// Another member of each remaining organization, who takes over the user's entries there
+    pub heirs: Vec<(Uuid, Uuid)>,
+}
+
+impl OrganizationPlan {
+    /// Plan one organization from the user's membership and the other members in it
+    pub fn add_organization(
+        &mut self,
+        membership: &organization_member::Model,
+        other_members: &[organization_member::Model],
+    ) -> AppResult<()> {
+        if other_members.is_empty() {
+            self.sole_organization_ids.push(membership.organization_id);
+            return Ok(());
+        }
+
+        let other_owner = other_members
+            .iter()
+            .find(|member| member.role == OrganizationRole::Owner);
+
+        // an organization is never left without an owner
+        let heir = match other_owner {
+            Some(owner) => owner,
+            None if membership.role != OrganizationRole::Owner => &other_members[0],
+            None => {
+                return Err(AppError::Conflict(
+                    "Make another member an owner of every organization you own first".to_string(),
+                ));
+            }
+        };
+
+        self.heirs.push((membership.organization_id, heir.user_id));
+
+        Ok(())
+    }
+}
+
+async fn plan_organizations(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<OrganizationPlan> {
+    let memberships = organization_member::Entity::find()
+        .filter(organization_member::Column::UserId.eq(user_id))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get memberships: {}", e)))?;
+
+    let mut plan = OrganizationPlan::default();
+
+    for membership in memberships {
+        let other_members = organization_member::Entity::find()
+            .filter(organization_member::Column::OrganizationId.eq(membership.organization_id))
+            .filter(organization_member::Column::UserId.ne(user_id))
+            .all(database_connection)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to get members: {}", e)))?;
+
+        plan.add_organization(&membership, &other_members)?;
+    }
+
+    Ok(plan)
+}
+
+async fn find_collection_ids(
+    database_connection: &DatabaseConnection,
+    organization_ids: Vec<Uuid>,
+) -> AppResult<Vec<Uuid>> {
+    let collections = collection::Entity::find()
+        .filter(collection::Column::OrganizationId.is_in(organization_ids))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get collections: {}", e)))?;
+
+    Ok(collections
+        .into_iter()
+        .map(|collection| collection.id)
+        .collect())
+}
+
+async fn find_password_ids(
+    database_connection: &DatabaseConnection,
+    condition: Condition,
+) -> AppResult<Vec<Uuid>> {
+    let password_entries = password::Entity::find()
+        .filter(condition)
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    Ok(password_entries
+        .into_iter()
+        .map(|password_entry| password_entry.id)
+        .collect())
+}
+
+/// Erase a user with everything they own, leaving a receipt behind
+pub async fn erase_account(
+    app_state: &AppState,
+    user: user::Model,
+) -> AppResult<deletion_receipt::Model> {
+    let database_connection = &app_state.database_connection;
+    let user_id = user.id;
+
+    let plan = plan_organizations(database_connection, user_id).await?;
+
+    // entries the user added to shared collections stay with the organization
+    let mut inherited_passwords: Vec<(Uuid, Vec<Uuid>)> = vec![];
+
+    for (organization_id, heir_id) in plan.heirs {
+        let collection_ids =
+            find_collection_ids(database_connection, vec![organization_id]).await?;
+
+        let password_ids = find_password_ids(
+            database_connection,
+            Condition::all()
+                .add(password::Column::UserId.eq(user_id))
+                .add(password::Column::CollectionId.is_in(collection_ids)),
+        )
+        .await?;
+
+        inherited_passwords.push((heir_id, password_ids));
+    }
+
+    let inherited_password_ids: Vec<Uuid> = inherited_passwords
+        .iter()
+        .flat_map(|(_, password_ids)| password_ids.clone())
+        .collect();
+
+    let sole_collection_ids =
+        find_collection_ids(database_connection, plan.sole_organization_ids.clone()).await?;
+
+    let sole_password_ids = find_password_ids(
+        database_connection,
+        Condition::all().add(password::Column::CollectionId.is_in(sole_collection_ids)),
+    )
+    .await?;
+
+    let erased_passwords = Condition::any()
+        .add(
+            Condition::all()
+                .add(password::Column::UserId.eq(user_id))
+                .add(password::Column::Id.is_not_in(inherited_password_ids.clone())),
+        )
+        .add(password::Column::Id.is_in(sole_password_ids.clone()));
+
+    let password_count = password::Entity::find()
+        .filter(erased_passwords)
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count passwords: {}", e)))?;
+
+    let attachments = attachment::Entity::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(attachment::Column::UserId.eq(user_id))
+                        .add(attachment::Column::PasswordId.is_not_in(inherited_password_ids)),
+                )
+                .add(attachment::Column::PasswordId.is_in(sole_password_ids.clone())),
+        )
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get attachments: {}", e)))?;
+
+    let recovery_code_count = recovery_code::Entity::find()
+        .filter(recovery_code::Column::UserId.eq(user_id))
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count recovery codes: {}", e)))?;
+
+    let now = Utc::now();
+    let email = user.email.clone();
+
+    let receipt = deletion_receipt::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        email_hash: Set(hash_receipt_email(
+            &email,
+            &app_state.env_variables.receipt_hash_secret,
+        )?),
+        requested_at: Set(user.deletion_requested_at.unwrap_or(now)),
+        deleted_at: Set(now),
+        password_count: Set(password_count as i64),
+        attachment_count: Set(attachments.len() as i64),
+        recovery_code_count: Set(recovery_code_count as i64),
+        ..Default::default()
+    };
+
+    let attachment_ids: Vec<Uuid> = attachments.iter().map(|attachment| attachment.id).collect();
+    let sole_organization_ids = plan.sole_organization_ids;
+
+    let receipt = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                // the user may have cancelled the deletion since it was found due, the lock holds
+                // off a cancellation until the account is gone
+                let due_user = user::Entity::find_by_id(user_id)
+                    .filter(user::Column::DeletionScheduledAt.is_not_null())
+                    .filter(user::Column::DeletionScheduledAt.lte(now))
+                    .lock_exclusive()
+                    .one(txn)
+                    .await?;
+
+                if due_user.is_none() {
+                    return Ok(None);
+                }
+
+                for (heir_id, password_ids) in inherited_passwords {
+                    password::Entity::update_many()
+                        .col_expr(password::Column::UserId, Expr::value(heir_id))
+                        .filter(password::Column::Id.is_in(password_ids.clone()))
+                        .exec(txn)
+                        .await?;
+
+                    password_uri::Entity::update_many()
+                        .col_expr(password_uri::Column::UserId, Expr::value(heir_id))
+                        .filter(password_uri::Column::PasswordId.is_in(password_ids.clone()))
+                        .exec(txn)
+                        .await?;
+
+                    custom_field::Entity::update_many()
+                        .col_expr(custom_field::Column::UserId, Expr::value(heir_id))
+                        .filter(custom_field::Column::PasswordId.is_in(password_ids.clone()))
+                        .exec(txn)
+                        .await?;
+
+                    attachment::Entity::update_many()
+                        .col_expr(attachment::Column::UserId, Expr::value(heir_id))
+                        .filter(attachment::Column::PasswordId.is_in(password_ids))
+                        .exec(txn)
+                        .await?;
+                }
+
+                attachment::Entity::delete_many()
+                    .filter(attachment::Column::Id.is_in(attachment_ids))
+                    .exec(txn)
+                    .await?;
+
+                // collections only go once their entries are gone
+                password::Entity::delete_many()
+                    .filter(password::Column::Id.is_in(sole_password_ids))
+                    .exec(txn)
+                    .await?;
+
+                organization::Entity::delete_many()
+                    .filter(organization::Column::Id.is_in(sole_organization_ids))
+                    .exec(txn)
+                    .await?;
+
+                password::Entity::delete_many()
+                    .filter(password::Column::UserId.eq(user_id))
+                    .exec(txn)
+                    .await?;
+
+                recovery_code::Entity::delete_many()
+                    .filter(recovery_code::Column::UserId.eq(user_id))
+                    .exec(txn)
+                    .await?;
+
+                // folders, tags, tombstones, shares, memberships and emergency access cascade
+                user::Entity::delete_by_id(user_id).exec(txn).await?;
+
+                receipt.insert(txn).await.map(Some)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to erase account: {}", e))
+        })?
+        .ok_or(AppError::Conflict(
+            "Account deletion was cancelled".to_string(),
+        ))?;
+
+    delete_attachment_blobs(app_state.blob_store.as_ref(), &attachments).await?;
+
+    revoke_user_sessions(app_state, user_id, None)?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    forget_devices(&mut redis_connection, user_id)
+        .map_err(|e| AppError::Internal(format!("Failed to forget devices: {}", e)))?;
+
+    clear_failures(&mut redis_connection, AuthAction::Login, &email)?;
+    clear_failures(&mut redis_connection, AuthAction::Recovery, &email)?;
+    clear_failures(
+        &mut redis_connection,
+        AuthAction::MasterPassword,
+        &user_id.to_string(),
+    )?;
+
+    send_mail(
+        app_state,
+        &email,
+        MailTemplate::AccountDeleted {
+            receipt_id: receipt.id,
+            deleted_at: receipt.deleted_at,
+        },
+    );
+
+    Ok(receipt)
+}
+
+/// Erase the accounts whose grace period has passed, one failing does not stop the others
+pub async fn delete_due_accounts(app_state: &AppState) -> AppResult<u64> {
+    let now = Utc::now();
+
+    let users = user::Entity::find()
+        .filter(user::Column::DeletionScheduledAt.lte(now))
+        .all(app_state.database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get users: {}", e)))?;
+
+    let mut deleted = 0;
+
+    for user in users {
+        if !is_deletion_due(&user, now) {
+            continue;
+        }
+
+        let user_id = user.id;
+
+        match erase_account(app_state, user).await {
+            Ok(receipt) => {
+                tracing::info!("Erased account {}, receipt {}", user_id, receipt.id);
+                deleted += 1;
+            }
+            Err(AppError::Conflict(_)) => {
+                tracing::info!("Account {} was restored before it was erased", user_id)
+            }
+            Err(e) => tracing::error!("Failed to erase account {}: {}", user_id, e),
+        }
+    }
+
+    Ok(deleted)
+}
+
+fn current_session_token<'a>(ctx: &'a Context<'_>) -> Option<&'a str> {
+    ctx.data::<Arc<GraphQLContext>>()
+        .ok()
+        .and_then(|gql_ctx| gql_ctx.session_token.as_deref())
+}
+
+async fn find_user(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<user::Model> {
+    user::Entity::find_by_id(user_id)
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find user: {}", e)))?
+        .ok_or(AppError::NotFound("User not found".to_string()))
+}
+
+/// Schedule the account for erasure once the grace period passes, until then it can be cancelled
+pub async fn delete_account(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+    request: DeleteAccountRequest,
+) -> AppResult<GraphqlResponse<AccountDeletionResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+
+    let user = find_user(database_connection, session_state.user_id()).await?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a locked session cannot step up, the master password is checked here instead
+    let attempt = AuthAttempt::new(ctx, AuthAction::MasterPassword, &user.id.to_string());
+    check_master_password(
+        &mut redis_connection,
+        &app_state.env_variables,
+        &attempt,
+        &request.master_password,
+        &user.master_password_hash,
+    )?;
+    drop(redis_connection);
+
+    if user.deletion_scheduled_at.is_some() {
+        return Err(AppError::Conflict(
+            "Account deletion is already scheduled".to_string(),
+        ));
+    }
+
+    // fail now rather than when the grace period is over
+    plan_organizations(database_connection, user.id).await?;
+
+    let now = Utc::now();
+    let deletion_scheduled_at =
+        now + Duration::days(app_state.env_variables.account_deletion_grace_days);
+
+    let user_id = user.id;
+    let email = user.email.clone();
+
+    let mut user_model: user::ActiveModel = user.into();
+    user_model.deletion_requested_at = Set(Some(now));
+    user_model.deletion_scheduled_at = Set(Some(deletion_scheduled_at));
+    user_model.updated_at = Set(now);
+
+    user_model
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to schedule deletion: {}", e)))?;
+
+    revoke_user_sessions(app_state, user_id, current_session_token(ctx))?;
+
+    send_mail(
+        app_state,
+        &email,
+        MailTemplate::AccountDeletionScheduled {
+            deletion_scheduled_at,
+        },
+    );
+
+    Ok(GraphqlResponse::<AccountDeletionResponse> {
+        success: true,
+        message: "Account deletion scheduled".to_string(),
+        data: AccountDeletionResponse {
+            deletion_scheduled_at,
+        },
+    })
+}
+
+pub async fn cancel_account_deletion(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+
+    let user = find_user(database_connection, session_state.user_id()).await?;
+
+    if user.deletion_scheduled_at.is_none() {
+        return Err(AppError::Conflict(
+            "Account deletion is not scheduled".to_string(),
+        ));
+    }
+
+    let mut user_model: user::ActiveModel = user.into();
+    user_model.deletion_requested_at = Set(None);
+    user_model.deletion_scheduled_at = Set(None);
+    user_model.updated_at = Set(Utc::now());
+
+    user_model
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to cancel deletion: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Account deletion cancelled".to_string(),
+    })
+}
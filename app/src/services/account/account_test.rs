@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        models::{
+            organization_member::{self, OrganizationRole},
+            user,
+        },
+        services::account::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    fn member(organization_id: Uuid, role: OrganizationRole) -> organization_member::Model {
+        organization_member::Model {
+            id: Uuid::new_v4(),
+            organization_id,
+            user_id: Uuid::new_v4(),
+            role,
+            wrapped_organization_key: String::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn user(deletion_scheduled_in_days: Option<i64>) -> user::Model {
+        user::Model {
+            id: Uuid::new_v4(),
+            email: "user@example.com".to_string(),
+            master_password_hash: String::new(),
+            encrypted_dek: String::new(),
+            public_key: None,
+            encrypted_private_key: None,
+            revision: 0,
+            session_idle_minutes: None,
+            session_max_lifetime_minutes: None,
+            email_verified_at: None,
+            deletion_requested_at: None,
+            deletion_scheduled_at: deletion_scheduled_in_days
+                .map(|days| Utc::now() + Duration::days(days)),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_deletion_due() -> AppResult<()> {
+        let now = Utc::now();
+
+        if !is_deletion_due(&user(Some(-1)), now) {
+            return Err(AppError::Internal(
+                "Account past its grace period should be deleted".to_string(),
+            ));
+        }
+
+        if is_deletion_due(&user(Some(1)), now) {
+            return Err(AppError::Internal(
+                "Account within its grace period should not be deleted".to_string(),
+            ));
+        }
+
+        if is_deletion_due(&user(None), now) {
+            return Err(AppError::Internal(
+                "Account without a scheduled deletion should not be deleted".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_organization_plan() -> AppResult<()> {
+        let mut plan = OrganizationPlan::default();
+
+        // alone in an organization, it goes with the user
+        let sole_organization_id = Uuid::new_v4();
+        plan.add_organization(&member(sole_organization_id, OrganizationRole::Owner), &[])?;
+
+        // another owner inherits over other members
+        let owned_organization_id = Uuid::new_v4();
+        let other_owner = member(owned_organization_id, OrganizationRole::Owner);
+        plan.add_organization(
+            &member(owned_organization_id, OrganizationRole::Owner),
+            &[
+                member(owned_organization_id, OrganizationRole::Member),
+                other_owner.clone(),
+            ],
+        )?;
+
+        // a plain member leaves their entries to whoever is left
+        let joined_organization_id = Uuid::new_v4();
+        let other_member = member(joined_organization_id, OrganizationRole::Member);
+        plan.add_organization(
+            &member(joined_organization_id, OrganizationRole::Member),
+            std::slice::from_ref(&other_member),
+        )?;
+
+        if plan.sole_organization_ids != vec![sole_organization_id] {
+            return Err(AppError::Internal(
+                "Organization without other members was not erased".to_string(),
+            ));
+        }
+
+        if plan.heirs
+            != vec![
+                (owned_organization_id, other_owner.user_id),
+                (joined_organization_id, other_member.user_id),
+            ]
+        {
+            return Err(AppError::Internal(
+                "Organizations with other members were not inherited".to_string(),
+            ));
+        }
+
+        // the last owner has to hand the organization over first
+        let orphaned_organization_id = Uuid::new_v4();
+
+        if !matches!(
+            plan.add_organization(
+                &member(orphaned_organization_id, OrganizationRole::Owner),
+                &[member(orphaned_organization_id, OrganizationRole::Member)],
+            ),
+            Err(AppError::Conflict(_))
+        ) {
+            return Err(AppError::Internal(
+                "Organization was left without an owner".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod account;
+mod account_test;
+
+pub use account::*;
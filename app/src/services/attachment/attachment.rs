@@ -0,0 +1,478 @@
+use std::{collections::HashMap, io::Read, sync::Arc};
+
+use async_graphql::Context;
+use base64::{Engine as _, engine::general_purpose};
+use futures::{
+    StreamExt,
+    stream::{self, BoxStream},
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder,
+    QuerySelect, Set, sea_query::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        attachment,
+        attachment_dtos::{AttachmentResponse, DeleteAttachmentRequest, UploadAttachmentRequest},
+        password,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        attachment::BlobStore,
+        crypto::{
+            ENCRYPTED_FILE_CHUNK_SIZE, FILE_CHUNK_SIZE, FILE_HEADER_LENGTH, decrypt_file_chunk,
+            decrypt_password, encrypt_file, encrypt_password, file_chunk_count, generate_dek,
+            unwrap_file_key, wrap_file_key,
+        },
+        organization::OrganizationAccess,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// A decrypted attachment, streamed chunk by chunk from the blob store
+pub struct AttachmentDownload {
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub size: u64,
+    pub stream: BoxStream<'static, AppResult<Vec<u8>>>,
+}
+
+pub fn to_attachment_response(
+    attachment: &attachment::Model,
+    dek: &[u8; 32],
+) -> AppResult<AttachmentResponse> {
+    let client_file_key = if attachment.client_encrypted {
+        Some(general_purpose::STANDARD.encode(unwrap_file_key(&attachment.wrapped_file_key, dek)?))
+    } else {
+        None
+    };
+
+    Ok(AttachmentResponse {
+        id: attachment.id,
+        file_name: decrypt_password(&attachment.encrypted_file_name, dek)?,
+        content_type: attachment
+            .encrypted_content_type
+            .as_ref()
+            .map(|content_type| decrypt_password(content_type, dek))
+            .transpose()?,
+        size: attachment.size,
+        client_encrypted: attachment.client_encrypted,
+        client_file_key,
+        download_url: format!("/attachments/{}", attachment.id),
+        created_at: attachment.created_at,
+    })
+}
+
+pub fn to_attachment_responses(
+    attachments: Vec<attachment::Model>,
+    dek: &[u8; 32],
+) -> AppResult<Vec<AttachmentResponse>> {
+    attachments
+        .iter()
+        .map(|attachment| to_attachment_response(attachment, dek))
+        .collect()
+}
+
+/// Re-encrypt the metadata and file key of an attachment, the file itself keeps its key
+pub fn rekey_attachment(
+    attachment: attachment::Model,
+    old_key: &[u8; 32],
+    new_key: &[u8; 32],
+) -> AppResult<attachment::ActiveModel> {
+    let encrypted_file_name = encrypt_password(
+        &decrypt_password(&attachment.encrypted_file_name, old_key)?,
+        new_key,
+    )?;
+    let encrypted_content_type = attachment
+        .encrypted_content_type
+        .as_ref()
+        .map(|content_type| encrypt_password(&decrypt_password(content_type, old_key)?, new_key))
+        .transpose()?;
+    let wrapped_file_key = wrap_file_key(
+        &unwrap_file_key(&attachment.wrapped_file_key, old_key)?,
+        new_key,
+    )?;
+
+    let mut rekeyed_attachment: attachment::ActiveModel = attachment.into();
+    rekeyed_attachment.encrypted_file_name = Set(encrypted_file_name);
+    rekeyed_attachment.encrypted_content_type = Set(encrypted_content_type);
+    rekeyed_attachment.wrapped_file_key = Set(wrapped_file_key);
+
+    Ok(rekeyed_attachment)
+}
+
+pub async fn find_attachments(
+    database_connection: &DatabaseConnection,
+    password_ids: Vec<Uuid>,
+) -> AppResult<HashMap<Uuid, Vec<attachment::Model>>> {
+    let attachments = attachment::Entity::find()
+        .filter(attachment::Column::PasswordId.is_in(password_ids))
+        .order_by(attachment::Column::CreatedAt, Order::Asc)
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get attachments: {}", e)))?;
+
+    let mut attachments_by_password: HashMap<Uuid, Vec<attachment::Model>> = HashMap::new();
+
+    for attachment in attachments {
+        attachments_by_password
+            .entry(attachment.password_id)
+            .or_default()
+            .push(attachment);
+    }
+
+    Ok(attachments_by_password)
+}
+
+/// Attachments of an entry, to remove their blobs once the entry is deleted
+pub async fn find_entry_attachments(
+    database_connection: &DatabaseConnection,
+    password_id: Uuid,
+) -> AppResult<Vec<attachment::Model>> {
+    attachment::Entity::find()
+        .filter(attachment::Column::PasswordId.eq(password_id))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get attachments: {}", e)))
+}
+
+/// An attachment together with its entry, if the entry is readable by the user
+async fn find_accessible_attachment(
+    database_connection: &DatabaseConnection,
+    access: &OrganizationAccess,
+    id: Uuid,
+) -> AppResult<(attachment::Model, password::Model)> {
+    attachment::Entity::find()
+        .filter(attachment::Column::Id.eq(id))
+        .find_also_related(password::Entity)
+        .filter(access.entries_condition())
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find attachment: {}", e)))?
+        .and_then(|(attachment, password_entry)| {
+            password_entry.map(|password_entry| (attachment, password_entry))
+        })
+        .ok_or(AppError::NotFound("Attachment not found".to_string()))
+}
+
+pub async fn delete_attachment_blobs(
+    blob_store: &dyn BlobStore,
+    attachments: &[attachment::Model],
+) -> AppResult<()> {
+    for attachment in attachments {
+        if attachment.storage_backend == blob_store.backend() {
+            blob_store.delete(&attachment.storage_key).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn used_attachment_bytes(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<u64> {
+    let used_bytes = attachment::Entity::find()
+        .select_only()
+        .column_as(
+            Expr::cust(r#"COALESCE(SUM("size"), 0)::bigint"#),
+            "used_bytes",
+        )
+        .filter(attachment::Column::UserId.eq(user_id))
+        .into_tuple::<i64>()
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get attachment usage: {}", e)))?
+        .unwrap_or(0);
+
+    Ok(used_bytes as u64)
+}
+
+pub fn decode_client_file_key(client_file_key: &str) -> AppResult<Vec<u8>> {
+    general_purpose::STANDARD
+        .decode(client_file_key)
+        .ok()
+        .filter(|file_key| file_key.len() == 32)
+        .ok_or(AppError::Validation(
+            "Client file key must be 32 bytes encoded as base64".to_string(),
+        ))
+}
+
+pub async fn upload_attachment(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UploadAttachmentRequest,
+) -> AppResult<GraphqlResponse<AttachmentResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let env_variables = &app_state.env_variables;
+    let blob_store = &app_state.blob_store;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let password_entry = password::Entity::find()
+        .filter(access.entries_condition())
+        .filter(password::Column::Id.eq(request.password_id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+        .ok_or(AppError::NotFound("Password not found".to_string()))?;
+
+    access.require_entry_edit(&password_entry)?;
+
+    // attachments of collection entries are readable by the whole organization
+    let attachment_key = access.attachment_key(&password_entry, &dek_u8_32)?;
+
+    let upload = request
+        .file
+        .value(ctx)
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    let size = upload
+        .size()
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    if size > env_variables.attachment_max_bytes {
+        return Err(AppError::Validation(format!(
+            "Attachment is larger than {} bytes",
+            env_variables.attachment_max_bytes
+        )));
+    }
+
+    if used_attachment_bytes(database_connection, user_id).await? + size
+        > env_variables.attachment_quota_bytes
+    {
+        return Err(AppError::Validation(
+            "Attachment storage quota exceeded".to_string(),
+        ));
+    }
+
+    let encrypted_file_name = encrypt_password(&upload.filename, &attachment_key)?;
+    let encrypted_content_type = upload
+        .content_type
+        .as_ref()
+        .map(|content_type| encrypt_password(content_type, &attachment_key))
+        .transpose()?;
+
+    // the upload is spooled to a temporary file, which only has a blocking reader
+    let data = tokio::task::spawn_blocking(move || {
+        let mut data = vec![];
+        upload.into_read().read_to_end(&mut data).map(|_| data)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?
+    .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+
+    let size = data.len() as i64;
+    let client_encrypted = request.client_file_key.is_some();
+
+    let (blob, file_key) = match &request.client_file_key {
+        Some(client_file_key) => (data, decode_client_file_key(client_file_key)?),
+        None => {
+            let file_key = generate_dek();
+            (encrypt_file(&data, &file_key)?, file_key.to_vec())
+        }
+    };
+
+    let wrapped_file_key = wrap_file_key(&file_key, &attachment_key)?;
+
+    let storage_key = blob_store.put(blob).await?;
+
+    let attachment_model = attachment::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        password_id: Set(request.password_id),
+        user_id: Set(user_id),
+        encrypted_file_name: Set(encrypted_file_name),
+        encrypted_content_type: Set(encrypted_content_type),
+        size: Set(size),
+        client_encrypted: Set(client_encrypted),
+        wrapped_file_key: Set(wrapped_file_key),
+        storage_backend: Set(blob_store.backend()),
+        storage_key: Set(storage_key.to_string()),
+        ..Default::default()
+    };
+
+    let attachment = match attachment_model.insert(database_connection.as_ref()).await {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            // nothing references the blob without its row
+            blob_store.delete(&storage_key).await?;
+
+            return Err(AppError::Internal(format!(
+                "Failed to save attachment: {}",
+                e
+            )));
+        }
+    };
+
+    Ok(GraphqlResponse::<AttachmentResponse> {
+        success: true,
+        message: "Attachment uploaded successfully".to_string(),
+        data: to_attachment_response(&attachment, &attachment_key)?,
+    })
+}
+
+pub async fn delete_attachment(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeleteAttachmentRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let (attachment, password_entry) =
+        find_accessible_attachment(database_connection, &access, request.id).await?;
+
+    access.require_entry_edit(&password_entry)?;
+
+    attachment::Entity::delete_by_id(attachment.id)
+        .exec(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to delete attachment: {}", e)))?;
+
+    delete_attachment_blobs(app_state.blob_store.as_ref(), &[attachment]).await?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Attachment deleted successfully".to_string(),
+    })
+}
+
+/// Stream of the decrypted chunks of an attachment, client encrypted files as uploaded
+pub async fn attachment_stream(
+    blob_store: &Arc<dyn BlobStore>,
+    attachment: &attachment::Model,
+    attachment_key: &[u8; 32],
+) -> AppResult<BoxStream<'static, AppResult<Vec<u8>>>> {
+    if attachment.storage_backend != blob_store.backend() {
+        return Err(AppError::Internal(
+            "Attachment is stored in another backend".to_string(),
+        ));
+    }
+
+    let blob_store = blob_store.clone();
+    let storage_key = attachment.storage_key.to_string();
+    let size = attachment.size as u64;
+
+    // client encrypted files are passed through as they were uploaded
+    Ok(if attachment.client_encrypted {
+        stream::try_unfold(0u64, move |offset| {
+            let blob_store = blob_store.clone();
+            let storage_key = storage_key.to_string();
+
+            async move {
+                if offset >= size {
+                    return Ok(None);
+                }
+
+                let chunk = blob_store
+                    .read(&storage_key, offset, FILE_CHUNK_SIZE)
+                    .await?;
+
+                if chunk.is_empty() {
+                    return Err(AppError::Internal("Attachment is truncated".to_string()));
+                }
+
+                let next_offset = offset + chunk.len() as u64;
+
+                Ok(Some((chunk, next_offset)))
+            }
+        })
+        .boxed()
+    } else {
+        let file_key = unwrap_file_key(&attachment.wrapped_file_key, attachment_key)?;
+        let nonce_prefix = blob_store.read(&storage_key, 0, FILE_HEADER_LENGTH).await?;
+        let chunk_count = file_chunk_count(size);
+
+        stream::try_unfold(0u64, move |index| {
+            let blob_store = blob_store.clone();
+            let storage_key = storage_key.to_string();
+            let file_key = file_key.clone();
+            let nonce_prefix = nonce_prefix.clone();
+
+            async move {
+                if index >= chunk_count {
+                    return Ok(None);
+                }
+
+                let offset = FILE_HEADER_LENGTH as u64 + index * ENCRYPTED_FILE_CHUNK_SIZE as u64;
+                let encrypted_chunk = blob_store
+                    .read(&storage_key, offset, ENCRYPTED_FILE_CHUNK_SIZE)
+                    .await?;
+
+                let chunk = decrypt_file_chunk(
+                    &encrypted_chunk,
+                    &nonce_prefix,
+                    index,
+                    index + 1 == chunk_count,
+                    &file_key,
+                )?;
+
+                Ok(Some((chunk, index + 1)))
+            }
+        })
+        .boxed()
+    })
+}
+
+pub async fn download_attachment(
+    app_state: &AppState,
+    user_redis_session: &UserRedisSession,
+    id: Uuid,
+) -> AppResult<AttachmentDownload> {
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let (attachment, password_entry) =
+        find_accessible_attachment(database_connection, &access, id).await?;
+
+    let attachment_key = access.attachment_key(&password_entry, &dek_u8_32)?;
+    let stream = attachment_stream(&app_state.blob_store, &attachment, &attachment_key).await?;
+
+    Ok(AttachmentDownload {
+        file_name: decrypt_password(&attachment.encrypted_file_name, &attachment_key)?,
+        content_type: attachment
+            .encrypted_content_type
+            .as_ref()
+            .map(|content_type| decrypt_password(content_type, &attachment_key))
+            .transpose()?,
+        size: attachment.size as u64,
+        stream,
+    })
+}
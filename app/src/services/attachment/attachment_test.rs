@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod test {
+    use crate::{
+        services::attachment::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    #[tokio::test]
+    async fn test_local_blob_store() -> AppResult<()> {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let blob_store = LocalBlobStore::new(&root);
+
+        let storage_key = blob_store.put(b"recovery codes".to_vec()).await?;
+
+        if blob_store.read(&storage_key, 9, 100).await? != b"codes" {
+            return Err(AppError::Internal("Blob range mismatch".to_string()));
+        }
+
+        if blob_store.read("../../etc/passwd", 0, 100).await.is_ok() {
+            return Err(AppError::Internal(
+                "Storage key escaped the blob directory".to_string(),
+            ));
+        }
+
+        blob_store.delete(&storage_key).await?;
+
+        if blob_store.read(&storage_key, 0, 100).await.is_ok() {
+            return Err(AppError::Internal("Blob was not deleted".to_string()));
+        }
+
+        let _ = std::fs::remove_dir_all(root);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+use uuid::Uuid;
+
+use crate::{
+    models::attachment::StorageBackend,
+    utils::error::{AppError, AppResult},
+};
+
+/// Storage for attachment blobs, which are already encrypted when they get here
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    fn backend(&self) -> StorageBackend;
+
+    /// Store a blob and return the key to read it back with
+    async fn put(&self, data: Vec<u8>) -> AppResult<String>;
+
+    /// Read up to `length` bytes starting at `offset`, less at the end of the blob
+    async fn read(&self, storage_key: &str, offset: u64, length: usize) -> AppResult<Vec<u8>>;
+
+    async fn delete(&self, storage_key: &str) -> AppResult<()>;
+}
+
+/// Blobs as files in a directory, named by a random uuid
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, storage_key: &str) -> AppResult<PathBuf> {
+        // only uuids are handed out, anything else could escape the root
+        let file_name = Uuid::parse_str(storage_key)
+            .map_err(|_| AppError::Internal("Invalid storage key".to_string()))?;
+
+        Ok(self.root.join(file_name.to_string()))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Local
+    }
+
+    async fn put(&self, data: Vec<u8>) -> AppResult<String> {
+        fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create blob directory: {}", e)))?;
+
+        let storage_key = Uuid::new_v4().to_string();
+
+        fs::write(self.path(&storage_key)?, data)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to write blob: {}", e)))?;
+
+        Ok(storage_key)
+    }
+
+    async fn read(&self, storage_key: &str, offset: u64, length: usize) -> AppResult<Vec<u8>> {
+        let mut file = fs::File::open(self.path(storage_key)?)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to open blob: {}", e)))?;
+
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read blob: {}", e)))?;
+
+        let mut data = Vec::with_capacity(length);
+
+        file.take(length as u64)
+            .read_to_end(&mut data)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read blob: {}", e)))?;
+
+        Ok(data)
+    }
+
+    async fn delete(&self, storage_key: &str) -> AppResult<()> {
+        match fs::remove_file(self.path(storage_key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(AppError::Internal(format!("Failed to delete blob: {}", e)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Blobs as Postgres large objects, keyed by their oid
+pub struct PostgresBlobStore {
+    database_connection: Arc<DatabaseConnection>,
+}
+
+impl PostgresBlobStore {
+    pub fn new(database_connection: Arc<DatabaseConnection>) -> Self {
+        Self {
+            database_connection,
+        }
+    }
+
+    fn oid(storage_key: &str) -> AppResult<i64> {
+        storage_key
+            .parse::<i64>()
+            .map_err(|_| AppError::Internal("Invalid storage key".to_string()))
+    }
+}
+
+#[async_trait]
+impl BlobStore for PostgresBlobStore {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Postgres
+    }
+
+    async fn put(&self, data: Vec<u8>) -> AppResult<String> {
+        let row = self
+            .database_connection
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT lo_from_bytea(0, $1)::bigint AS "oid""#,
+                [data.into()],
+            ))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to write blob: {}", e)))?
+            .ok_or(AppError::Internal("Failed to write blob".to_string()))?;
+
+        let oid = row
+            .try_get::<i64>("", "oid")
+            .map_err(|e| AppError::Internal(format!("Failed to write blob: {}", e)))?;
+
+        Ok(oid.to_string())
+    }
+
+    async fn read(&self, storage_key: &str, offset: u64, length: usize) -> AppResult<Vec<u8>> {
+        let row = self
+            .database_connection
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT lo_get($1::oid, $2, $3) AS "data""#,
+                [
+                    Self::oid(storage_key)?.into(),
+                    (offset as i64).into(),
+                    (length as i32).into(),
+                ],
+            ))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read blob: {}", e)))?
+            .ok_or(AppError::Internal("Failed to read blob".to_string()))?;
+
+        row.try_get::<Vec<u8>>("", "data")
+            .map_err(|e| AppError::Internal(format!("Failed to read blob: {}", e)))
+    }
+
+    async fn delete(&self, storage_key: &str) -> AppResult<()> {
+        self.database_connection
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT lo_unlink($1::oid)"#,
+                [Self::oid(storage_key)?.into()],
+            ))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to delete blob: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+#[allow(clippy::module_inception)]
+pub mod attachment;
+mod attachment_test;
+pub mod blob_store;
+
+pub use attachment::*;
+pub use blob_store::*;
@@ -3,11 +3,10 @@ use std::sync::Arc;
 use async_graphql::Context;
 use axum::http::header;
 use chrono::{Duration, Utc};
-use r2d2::Pool;
-use redis::{Client, Commands};
+use redis::Commands;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, Set, TransactionError,
-    TransactionTrait,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    TransactionError, TransactionTrait,
 };
 use serde_json::to_string;
 use uuid::Uuid;
@@ -22,12 +21,27 @@ use crate::{
     models::{
         recovery_code, user,
         user_dtos::{
-            ChangeMasterPasswordRequest, CheckRecoveryCodeValidityRequest, RecoveryAccountRequest,
-            RecoveryKeyResponse, UserLoginRequest, UserRedisSession, UserSignupRequest,
-            UserSignupResponse,
+            ChangeEmailRequest, ChangeMasterPasswordRequest, CheckRecoveryCodeValidityRequest,
+            CompleteSignupRequest, EmailChangeTokenRequest, PendingEmailChange,
+            RecoveryAccountRequest, RecoveryKeyResponse, RedisSession, SessionState,
+            UserLoginRequest, UserRedisSession, UserSignupRequest, UserSignupResponse,
+            VerifyEmailRequest,
+        },
+    },
+    services::{
+        crypto::{self, decrypt_dek, verify_master_password},
+        mail::{MailTemplate, send_mail},
+        session::{
+            SessionPolicy, client_ip, index_session, remember_device, revoke_session_tokens,
+            revoke_user_sessions, session_policy, session_ttl_seconds, store_session_dek,
+            update_user_sessions, user_agent_from_headers,
+        },
+        tag::rebuild_stale_tag_indexes,
+        throttle::{
+            AuthAction, AuthAttempt, clear_failures, record_failure, release_attempt,
+            reserve_attempt,
         },
     },
-    services::crypto::{self, decrypt_dek, verify_master_password},
     utils::error::{AppError, AppResult},
 };
 
@@ -41,15 +55,15 @@ fn generate_recovery_keys_for_dek(
     let mut recovery_code_entities: Vec<recovery_code::ActiveModel> = vec![];
 
     for recovery_code in recovery_keys.iter() {
-        let hash = crypto::hash_recovery_code(&recovery_code);
+        let hash = crypto::hash_recovery_code(recovery_code);
 
-        let recovery_kek = crypto::derive_kek(&recovery_code)?;
+        let recovery_kek = crypto::derive_kek(recovery_code)?;
 
         let encrypted_encrypted_dek = crypto::encrypt_dek(dek, &recovery_kek).unwrap();
 
         let recovery_code_entity = recovery_code::ActiveModel {
             id: Set(Uuid::new_v4()),
-            user_id: Set(user_id.clone()),
+            user_id: Set(*user_id),
             code_hash: Set(hash),
             encrypted_dek: Set(encrypted_encrypted_dek),
             ..Default::default()
@@ -61,31 +75,93 @@ fn generate_recovery_keys_for_dek(
     Ok((recovery_code_entities, recovery_keys))
 }
 
+/// Token of the session making the request, if any
+fn current_session_token<'a>(ctx: &'a Context<'_>) -> Option<&'a str> {
+    ctx.data::<Arc<GraphQLContext>>()
+        .ok()
+        .and_then(|gql_ctx| gql_ctx.session_token.as_deref())
+}
+
+/// Start a session, alerting the user by mail when it comes from a device they never used
 fn generate_and_save_session(
-    user_redis_session: UserRedisSession,
-    redis_pool_manager: &Arc<Pool<Client>>,
-    env_variables: Arc<Env>,
+    mut redis_session: RedisSession,
+    dek: &[u8],
+    app_state: &AppState,
+    policy: SessionPolicy,
     ctx: &Context<'_>,
 ) -> AppResult<()> {
+    let env_variables = &app_state.env_variables;
     let session_token = crypto::generate_session_token();
-    let expires_at = Utc::now() + Duration::minutes(env_variables.session_expire_minutes);
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(policy.max_lifetime_minutes);
+    let expire_seconds = session_ttl_seconds(policy.idle_minutes, expires_at, now);
+    let auto_lock_seconds =
+        session_ttl_seconds(env_variables.vault_auto_lock_minutes, expires_at, now);
 
-    let user_redis_session_str =
-        to_string(&user_redis_session).map_err(|e| AppError::Internal(e.to_string()))?;
+    let headers = ctx
+        .data::<Arc<GraphQLContext>>()
+        .ok()
+        .and_then(|gql_ctx| gql_ctx.headers.as_ref());
+
+    redis_session.created_at = Some(now);
+    redis_session.expires_at = Some(expires_at);
+    redis_session.idle_minutes = Some(policy.idle_minutes);
+    redis_session.user_agent = headers.and_then(user_agent_from_headers);
+    redis_session.ip_address = ctx
+        .data::<Arc<GraphQLContext>>()
+        .ok()
+        .and_then(|gql_ctx| client_ip(gql_ctx, &env_variables.trusted_proxies));
+    let user_id = redis_session.id;
 
-    let mut redis_connection = redis_pool_manager
+    let redis_session_str =
+        to_string(&redis_session).map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
         .get()
         .map_err(|e| AppError::Database(e.to_string()))?;
 
     redis_connection
-        .set_ex::<String, String, ()>(
-            session_token.to_string(),
-            user_redis_session_str,
-            (env_variables.session_expire_minutes as u64) * 60,
-        )
+        .set_ex::<String, String, ()>(session_token.to_string(), redis_session_str, expire_seconds)
         .map_err(|e| AppError::Database(e.to_string()))
         .unwrap();
 
+    // the vault starts unlocked
+    store_session_dek(
+        &mut redis_connection,
+        &session_token,
+        dek,
+        auto_lock_seconds,
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    index_session(
+        &mut redis_connection,
+        user_id,
+        &session_token,
+        expire_seconds,
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let new_device = remember_device(
+        &mut redis_connection,
+        user_id,
+        redis_session.user_agent.as_deref(),
+    )
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if new_device {
+        send_mail(
+            app_state,
+            &redis_session.email,
+            MailTemplate::NewDeviceLogin {
+                user_agent: redis_session.user_agent,
+                ip_address: redis_session.ip_address,
+                at: now,
+            },
+        );
+    }
+
     ctx.insert_http_header(
         header::SET_COOKIE,
         format!(
@@ -98,9 +174,81 @@ fn generate_and_save_session(
     Ok(())
 }
 
+fn signup_token_key(token: &str) -> String {
+    format!("signup_token:{}", crypto::hash_recovery_code(token))
+}
+
+fn email_verification_token_key(token: &str) -> String {
+    format!(
+        "email_verification_token:{}",
+        crypto::hash_recovery_code(token)
+    )
+}
+
+fn email_change_confirm_key(token: &str) -> String {
+    format!("email_change_confirm:{}", crypto::hash_recovery_code(token))
+}
+
+fn email_change_cancel_key(token: &str) -> String {
+    format!("email_change_cancel:{}", crypto::hash_recovery_code(token))
+}
+
+/// Hash of the confirmation token of the one email change of a user still in effect
+fn pending_email_change_key(user_id: Uuid) -> String {
+    format!("email_change:{}", user_id)
+}
+
+/// Start a signup, the account is only created once the owner of the email follows the link.
+/// The response is the same whether or not the email already has an account
 pub async fn signup(
     ctx: &Context<'_>,
     request: UserSignupRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let db_connection = &app_state.database_connection;
+    let env_variables = &app_state.env_variables;
+
+    let email = request.email;
+    let token = crypto::generate_session_token();
+
+    let user = user::Entity::find()
+        .filter(user::Column::Email.eq(&email))
+        .one(db_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if user.is_none() {
+        let mut redis_connection = app_state.redis_pool_manager.get().map_err(|_| {
+            AppError::Internal("Failed to get redis connection from pool".to_string())
+        })?;
+
+        redis_connection
+            .set_ex::<String, &str, ()>(
+                signup_token_key(&token),
+                &email,
+                env_variables.signup_token_minutes * 60,
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        send_mail(
+            app_state,
+            &email,
+            MailTemplate::SignupVerification { token },
+        );
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Check your email to finish signing up".to_string(),
+    })
+}
+
+pub async fn complete_signup(
+    ctx: &Context<'_>,
+    request: CompleteSignupRequest,
 ) -> AppResult<GraphqlResponse<UserSignupResponse>> {
     let app_state = ctx
         .data::<Arc<AppState>>()
@@ -110,7 +258,20 @@ pub async fn signup(
     let redis_pool_manager = &app_state.redis_pool_manager;
     let env_variables = &app_state.env_variables;
 
-    let email = request.email;
+    let invalid_token = || AppError::Authentication("Invalid or expired signup link".to_string());
+
+    let mut redis_connection = redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a signup link works once
+    let email = redis_connection
+        .get_del::<String, Option<String>>(signup_token_key(&request.token))
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(invalid_token)?;
+
+    drop(redis_connection);
+
     let master_password = request.master_password;
 
     let user = user::Entity::find()
@@ -119,14 +280,16 @@ pub async fn signup(
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+    // another link for the same email was followed first
     if user.is_some() {
-        return Err(AppError::Conflict("User Already Exists".to_string()));
+        return Err(invalid_token());
     }
 
     let kek = crypto::derive_kek(&master_password)?;
     let dek = crypto::generate_dek();
 
     let encrypted_dek = crypto::encrypt_dek(&dek, &kek)?;
+    let (public_key, encrypted_private_key) = crypto::generate_key_pair(&dek)?;
 
     let user_id = Uuid::new_v4();
     let master_password_hash = crypto::hash_master_password(&master_password)?;
@@ -136,6 +299,10 @@ pub async fn signup(
         email: Set(email.clone()),
         master_password_hash: Set(master_password_hash),
         encrypted_dek: Set(encrypted_dek),
+        public_key: Set(Some(public_key)),
+        encrypted_private_key: Set(Some(encrypted_private_key)),
+        // following the signup link proved the email
+        email_verified_at: Set(Some(Utc::now())),
         ..Default::default()
     };
 
@@ -158,24 +325,26 @@ pub async fn signup(
         .map_err(|e: TransactionError<DbErr>| AppError::Database(e.to_string()))?;
 
     generate_and_save_session(
-        UserRedisSession {
+        RedisSession {
             id: user_id,
-            dek: dek.to_vec(),
             email,
+            email_verified: true,
+            ..Default::default()
         },
-        redis_pool_manager,
-        env_variables.clone(),
+        &dek,
+        app_state,
+        session_policy(env_variables, None, None),
         ctx,
     )?;
 
-    return Ok(GraphqlResponse::<UserSignupResponse> {
+    Ok(GraphqlResponse::<UserSignupResponse> {
         success: true,
         message: "Signup Successful".to_string(),
         data: UserSignupResponse {
             recovery_keys,
             id: user_id,
         },
-    });
+    })
 }
 
 pub async fn login(
@@ -187,57 +356,109 @@ pub async fn login(
         .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
 
     let db_connection = &app_state.database_connection;
-    let redis_pool_manager = &app_state.redis_pool_manager;
     let env_variables = &app_state.env_variables;
 
     let email = request.email;
 
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let attempt = AuthAttempt::new(ctx, AuthAction::Login, &email);
+    reserve_attempt(&mut redis_connection, env_variables, &attempt)?;
+
     let user = user::Entity::find()
         .filter(user::Column::Email.eq(&email))
         .one(db_connection.as_ref())
         .await
-        .map_err(|e| AppError::Database(e.to_string()))?
-        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+        .map_err(|e| AppError::Database(e.to_string()))?;
 
-    let user_master_password_hash = user.master_password_hash;
     let request_master_password = request.master_password;
 
-    if !crypto::verify_master_password(&request_master_password, &user_master_password_hash)? {
-        return Err(AppError::Authorization(
-            "Invalid Master Password".to_string(),
+    // unknown emails take as long and fail the same way as wrong master passwords
+    let verified = match &user {
+        Some(user) => {
+            crypto::verify_master_password(&request_master_password, &user.master_password_hash)?
+        }
+        None => {
+            crypto::dummy_verify_master_password(&request_master_password);
+            false
+        }
+    };
+
+    let (Some(user), true) = (user, verified) else {
+        record_failure(&mut redis_connection, env_variables, &attempt)?;
+        return Err(AppError::Authentication(
+            "Invalid email or master password".to_string(),
         ));
-    }
+    };
+
+    release_attempt(&mut redis_connection, env_variables, &attempt)?;
+    clear_failures(&mut redis_connection, AuthAction::Login, &email)?;
+    drop(redis_connection);
 
     let kek = crypto::derive_kek(&request_master_password)?;
     let dek = crypto::decrypt_dek(&user.encrypted_dek, &kek)?;
 
+    let user_id = user.id;
+    let user_email = user.email.clone();
+    let email_verified = user.email_verified_at.is_some();
+    let policy = session_policy(
+        env_variables,
+        user.session_idle_minutes,
+        user.session_max_lifetime_minutes,
+    );
+
+    let dek_u8_32: [u8; 32] = dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    rebuild_stale_tag_indexes(db_connection, user_id, &dek_u8_32).await?;
+
+    // users from before sharing get their keypair once their DEK is available
+    if user.public_key.is_none() {
+        let (public_key, encrypted_private_key) = crypto::generate_key_pair(&dek)?;
+
+        let mut user_model: user::ActiveModel = user.into();
+        user_model.public_key = Set(Some(public_key));
+        user_model.encrypted_private_key = Set(Some(encrypted_private_key));
+        user_model.updated_at = Set(Utc::now());
+
+        user_model
+            .update(db_connection.as_ref())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
     generate_and_save_session(
-        UserRedisSession {
-            id: user.id,
-            dek,
-            email: user.email,
+        RedisSession {
+            id: user_id,
+            email: user_email,
+            email_verified,
+            ..Default::default()
         },
-        redis_pool_manager,
-        env_variables.clone(),
+        &dek,
+        app_state,
+        policy,
         ctx,
     )?;
 
-    return Ok(GraphqlGenericResponse {
+    Ok(GraphqlGenericResponse {
         success: true,
         message: "Login Successful".to_string(),
-    });
+    })
 }
 
-pub async fn logout(ctx: &Context<'_>, _: &UserRedisSession) -> AppResult<GraphqlGenericResponse> {
+pub async fn logout(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+) -> AppResult<GraphqlGenericResponse> {
     let app_state = ctx
         .data::<Arc<AppState>>()
         .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
 
-    let mut redis_connection = app_state
-        .redis_pool_manager
-        .get()
-        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
-
     let gql_ctx = ctx
         .data::<Arc<GraphQLContext>>()
         .map_err(|_| AppError::Internal("GraphQL Context is not passed".to_string()))?;
@@ -249,9 +470,12 @@ pub async fn logout(ctx: &Context<'_>, _: &UserRedisSession) -> AppResult<Graphq
             "Session token is missing".to_string(),
         ))?;
 
-    redis_connection
-        .del::<String, ()>(session_token.to_string())
-        .map_err(|_| AppError::Authorization("Session token is invalid or expired".to_string()))?;
+    // other connections of the session, like subscriptions, end with it
+    revoke_session_tokens(
+        app_state,
+        session_state.user_id(),
+        &[session_token.to_string()],
+    )?;
 
     ctx.insert_http_header(
         header::SET_COOKIE,
@@ -280,20 +504,34 @@ pub async fn check_recovery_code_validity(
 
     let recovery_code = request.recovery_code;
 
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let attempt = AuthAttempt::new(ctx, AuthAction::Recovery, &user_redis_session.email);
+    reserve_attempt(&mut redis_connection, &app_state.env_variables, &attempt)?;
+
     let recovery_code_hash = crypto::hash_recovery_code(&recovery_code);
 
-    let recovery_code_entity = recovery_code::Entity::find()
+    let Some(recovery_code_entity) = recovery_code::Entity::find()
         .filter(recovery_code::Column::CodeHash.eq(recovery_code_hash))
         .filter(recovery_code::Column::UserId.eq(user_id))
         .one(db_connection.as_ref())
         .await
         .map_err(|e| AppError::Database(e.to_string()))?
-        .ok_or(AppError::NotFound("Recovery Code Not Found".to_string()))?;
+    else {
+        record_failure(&mut redis_connection, &app_state.env_variables, &attempt)?;
+        return Err(AppError::NotFound("Recovery Code Not Found".to_string()));
+    };
 
     if recovery_code_entity.used {
+        record_failure(&mut redis_connection, &app_state.env_variables, &attempt)?;
         return Err(AppError::Conflict("Recovery Code Already Used".to_string()));
     }
 
+    release_attempt(&mut redis_connection, &app_state.env_variables, &attempt)?;
+
     Ok(GraphqlGenericResponse {
         success: true,
         message: "Recovery Code is Valid".to_string(),
@@ -324,7 +562,7 @@ pub async fn generate_recovery_keys(
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
-    if recovery_code_entities.len() != 0 {
+    if !recovery_code_entities.is_empty() {
         return Err(AppError::Conflict(
             "Recovery Keys Already Generated".to_string(),
         ));
@@ -357,27 +595,42 @@ pub async fn recover_account(
 
     let email = request.email;
 
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let attempt = AuthAttempt::new(ctx, AuthAction::Recovery, &email);
+    reserve_attempt(&mut redis_connection, &app_state.env_variables, &attempt)?;
+
     let user = user::Entity::find()
         .filter(user::Column::Email.eq(&email))
         .one(db_connection.as_ref())
         .await
-        .map_err(|e| AppError::Database(e.to_string()))?
-        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+        .map_err(|e| AppError::Database(e.to_string()))?;
 
     let recovery_code = request.recovery_code;
     let recovery_code_hash = crypto::hash_recovery_code(&recovery_code);
 
-    let recovery_code_entity = recovery_code::Entity::find()
-        .filter(recovery_code::Column::CodeHash.eq(recovery_code_hash))
-        .filter(recovery_code::Column::UserId.eq(user.id))
-        .one(db_connection.as_ref())
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?
-        .ok_or(AppError::NotFound("Recovery Code Not Found".to_string()))?;
+    let recovery_code_entity = match &user {
+        Some(user) => recovery_code::Entity::find()
+            .filter(recovery_code::Column::CodeHash.eq(recovery_code_hash))
+            .filter(recovery_code::Column::UserId.eq(user.id))
+            .filter(recovery_code::Column::Used.eq(false))
+            .one(db_connection.as_ref())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?,
+        None => None,
+    };
 
-    if recovery_code_entity.used {
-        return Err(AppError::Conflict("Recovery Code Already Used".to_string()));
-    }
+    // unknown emails, unknown codes and used codes cost the same and fail the same way
+    let Some((user, recovery_code_entity)) = user.zip(recovery_code_entity) else {
+        crypto::dummy_verify_master_password(&recovery_code);
+        record_failure(&mut redis_connection, &app_state.env_variables, &attempt)?;
+        return Err(AppError::Authentication(
+            "Invalid email or recovery code".to_string(),
+        ));
+    };
 
     let new_master_password = request.new_master_password;
     let new_kek = crypto::derive_kek(&new_master_password)?;
@@ -388,6 +641,9 @@ pub async fn recover_account(
 
     let encrypted_dek = crypto::encrypt_dek(&dek, &new_kek)?;
 
+    let user_id = user.id;
+    let user_email = user.email.clone();
+
     let mut user_model: user::ActiveModel = user.into();
     user_model.master_password_hash = Set(new_master_password_hash);
     user_model.encrypted_dek = Set(encrypted_dek);
@@ -408,6 +664,20 @@ pub async fn recover_account(
         .await
         .map_err(|e: TransactionError<DbErr>| AppError::Database(e.to_string()))?;
 
+    // whoever knew the old master password is signed out, recovery runs without a session
+    revoke_user_sessions(app_state, user_id, None)?;
+
+    // a recovery code lifts a login lockout, the new master password works right away
+    release_attempt(&mut redis_connection, &app_state.env_variables, &attempt)?;
+    clear_failures(&mut redis_connection, AuthAction::Recovery, &email)?;
+    clear_failures(&mut redis_connection, AuthAction::Login, &email)?;
+
+    send_mail(
+        app_state,
+        &user_email,
+        MailTemplate::RecoveryCodeUsed { at: Utc::now() },
+    );
+
     Ok(GraphqlGenericResponse {
         success: true,
         message: "Account Recovered Successfully".to_string(),
@@ -454,6 +724,8 @@ pub async fn change_master_password(
     let dek = crypto::decrypt_dek(encrypted_dek, &old_kek)?;
     let encrypted_dek = crypto::encrypt_dek(&dek, &new_kek)?;
 
+    let user_email = user.email.clone();
+
     let mut user_model: user::ActiveModel = user.into();
     user_model.master_password_hash = Set(new_master_password_hash);
     user_model.encrypted_dek = Set(encrypted_dek);
@@ -464,8 +736,334 @@ pub async fn change_master_password(
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+    revoke_user_sessions(app_state, user_id, current_session_token(ctx))?;
+
+    send_mail(
+        app_state,
+        &user_email,
+        MailTemplate::MasterPasswordChanged { at: Utc::now() },
+    );
+
     Ok(GraphqlGenericResponse {
         success: true,
         message: "Master Password Changed Successfully".to_string(),
     })
 }
+
+/// Mail a verification link to the email of an account from before signup links
+pub async fn send_verification_email(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user = user::Entity::find_by_id(session_state.user_id())
+        .one(app_state.database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User not found".to_string()))?;
+
+    if user.email_verified_at.is_some() {
+        return Err(AppError::Conflict("Email is already verified".to_string()));
+    }
+
+    let token = crypto::generate_session_token();
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    redis_connection
+        .set_ex::<String, String, ()>(
+            email_verification_token_key(&token),
+            user.id.to_string(),
+            app_state.env_variables.signup_token_minutes * 60,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    send_mail(
+        app_state,
+        &user.email,
+        MailTemplate::EmailVerification { token },
+    );
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Check your email to verify it".to_string(),
+    })
+}
+
+pub async fn verify_email(
+    ctx: &Context<'_>,
+    request: VerifyEmailRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let db_connection = &app_state.database_connection;
+
+    let invalid_token =
+        || AppError::Authentication("Invalid or expired verification link".to_string());
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a verification link works once
+    let user_id = redis_connection
+        .get_del::<String, Option<String>>(email_verification_token_key(&request.token))
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .and_then(|user_id| Uuid::parse_str(&user_id).ok())
+        .ok_or_else(invalid_token)?;
+
+    drop(redis_connection);
+
+    let user = user::Entity::find_by_id(user_id)
+        .one(db_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(invalid_token)?;
+
+    if user.email_verified_at.is_none() {
+        let mut user_model: user::ActiveModel = user.into();
+        user_model.email_verified_at = Set(Some(Utc::now()));
+        user_model.updated_at = Set(Utc::now());
+
+        user_model
+            .update(db_connection.as_ref())
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+
+    // signed in sessions lose their restrictions right away
+    update_user_sessions(app_state, user_id, |redis_session| {
+        redis_session.email_verified = true;
+    })?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Email Verified Successfully".to_string(),
+    })
+}
+
+async fn is_email_taken(db_connection: &DatabaseConnection, email: &str) -> AppResult<bool> {
+    let user = user::Entity::find()
+        .filter(user::Column::Email.eq(email))
+        .one(db_connection)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(user.is_some())
+}
+
+async fn ensure_email_available(db_connection: &DatabaseConnection, email: &str) -> AppResult<()> {
+    if is_email_taken(db_connection, email).await? {
+        return Err(AppError::Conflict("Email is already in use".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Start moving an account to a new email, it only changes once the new address is confirmed.
+/// The old address is told and can cancel
+pub async fn change_email(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ChangeEmailRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let new_email = request.new_email;
+
+    if new_email == user_redis_session.email {
+        return Err(AppError::Validation(
+            "New email is the current email".to_string(),
+        ));
+    }
+
+    let success = GraphqlGenericResponse {
+        success: true,
+        message: "Check your new email to confirm the change".to_string(),
+    };
+
+    let is_taken = is_email_taken(&app_state.database_connection, &new_email).await?;
+
+    // the same links are stored either way, so the answer takes as long whether it is taken
+    let user_id = user_redis_session.id;
+    let confirm_token = crypto::generate_session_token();
+    let cancel_token = crypto::generate_session_token();
+    let expire_seconds = app_state.env_variables.signup_token_minutes * 60;
+
+    let pending_email_change = to_string(&PendingEmailChange {
+        user_id,
+        new_email: new_email.clone(),
+    })
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a newer change replaces the links of an older one
+    redis_connection
+        .set_ex::<String, String, ()>(
+            pending_email_change_key(user_id),
+            crypto::hash_recovery_code(&confirm_token),
+            expire_seconds,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    redis_connection
+        .set_ex::<String, String, ()>(
+            email_change_confirm_key(&confirm_token),
+            pending_email_change,
+            expire_seconds,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    redis_connection
+        .set_ex::<String, String, ()>(
+            email_change_cancel_key(&cancel_token),
+            user_id.to_string(),
+            expire_seconds,
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    // whether an address has an account is only told to that address, a confirmation link
+    // for it would fail anyway once the address is checked again
+    if is_taken {
+        send_mail(app_state, &new_email, MailTemplate::EmailAlreadyInUse);
+    } else {
+        send_mail(
+            app_state,
+            &new_email,
+            MailTemplate::EmailChangeConfirmation {
+                token: confirm_token,
+            },
+        );
+    }
+
+    send_mail(
+        app_state,
+        &user_redis_session.email,
+        MailTemplate::EmailChangeRequested {
+            new_email,
+            token: cancel_token,
+        },
+    );
+
+    Ok(success)
+}
+
+pub async fn confirm_email_change(
+    ctx: &Context<'_>,
+    request: EmailChangeTokenRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let db_connection = &app_state.database_connection;
+
+    let invalid_token = || AppError::Authentication("Invalid or expired email link".to_string());
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a confirmation link works once
+    let pending_email_change = redis_connection
+        .get_del::<String, Option<String>>(email_change_confirm_key(&request.token))
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .and_then(|pending| serde_json::from_str::<PendingEmailChange>(&pending).ok())
+        .ok_or_else(invalid_token)?;
+
+    let user_id = pending_email_change.user_id;
+    let new_email = pending_email_change.new_email;
+
+    // cancelled, or replaced by a newer change
+    let current_token_hash = redis_connection
+        .get::<String, Option<String>>(pending_email_change_key(user_id))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    if current_token_hash != Some(crypto::hash_recovery_code(&request.token)) {
+        return Err(invalid_token());
+    }
+
+    redis_connection
+        .del::<String, ()>(pending_email_change_key(user_id))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    drop(redis_connection);
+
+    // another account may have taken the email in the meantime
+    ensure_email_available(db_connection, &new_email).await?;
+
+    let user = user::Entity::find_by_id(user_id)
+        .one(db_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(invalid_token)?;
+
+    let mut user_model: user::ActiveModel = user.into();
+    user_model.email = Set(new_email.clone());
+    // following the link proved the new email
+    user_model.email_verified_at = Set(Some(Utc::now()));
+    user_model.updated_at = Set(Utc::now());
+
+    user_model
+        .update(db_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    update_user_sessions(app_state, user_id, |redis_session| {
+        redis_session.email = new_email.clone();
+        redis_session.email_verified = true;
+    })?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Email Changed Successfully".to_string(),
+    })
+}
+
+pub async fn cancel_email_change(
+    ctx: &Context<'_>,
+    request: EmailChangeTokenRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let user_id = redis_connection
+        .get_del::<String, Option<String>>(email_change_cancel_key(&request.token))
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .and_then(|user_id| Uuid::parse_str(&user_id).ok())
+        .ok_or(AppError::Authentication(
+            "Invalid or expired email link".to_string(),
+        ))?;
+
+    // the confirmation link sent to the new address stops working
+    redis_connection
+        .del::<String, ()>(pending_email_change_key(user_id))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Email Change Cancelled".to_string(),
+    })
+}
@@ -0,0 +1,231 @@
+#[cfg(test)]
+mod test {
+    use serde_json::{Value, json};
+
+    use crate::{
+        configs::env::Env,
+        services::mail::mask_email,
+        utils::{
+            error::{AppError, AppResult},
+            test_app::{TestApp, session_token, unique_email},
+        },
+    };
+
+    const MASTER_PASSWORD: &str = "correct horse battery staple";
+
+    const LOGIN: &str = "mutation($email: String!, $masterPassword: String!) {
+        login(request: { email: $email, masterPassword: $masterPassword }) { success message }
+    }";
+
+    async fn login(app: &TestApp, email: &str, master_password: &str) -> AppResult<Value> {
+        app.execute(
+            None,
+            LOGIN,
+            json!({ "email": email, "masterPassword": master_password }),
+        )
+        .await
+    }
+
+    async fn signup(app: &TestApp, email: &str) -> AppResult<Value> {
+        app.execute(
+            None,
+            "mutation($email: String!) { signup(request: { email: $email }) { success message } }",
+            json!({ "email": email }),
+        )
+        .await
+    }
+
+    async fn change_email(app: &TestApp, session: &str, new_email: &str) -> AppResult<Value> {
+        app.execute(
+            Some(session),
+            "mutation($newEmail: String!) {
+                changeEmail(request: { newEmail: $newEmail }) { success message }
+            }",
+            json!({ "newEmail": new_email }),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_login_error_is_uniform() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let email = unique_email();
+        app.signup(&email, MASTER_PASSWORD).await?;
+
+        let unknown_email = login(&app, &unique_email(), MASTER_PASSWORD).await;
+        let wrong_password = login(&app, &email, "not the master password").await;
+
+        match (&unknown_email, &wrong_password) {
+            (Err(AppError::Authentication(unknown)), Err(AppError::Authentication(wrong)))
+                if unknown == wrong => {}
+            _ => {
+                return Err(AppError::Internal(format!(
+                    "Unknown email and wrong password should fail alike: {:?} / {:?}",
+                    unknown_email, wrong_password
+                )));
+            }
+        }
+
+        let response = app
+            .request(
+                None,
+                LOGIN,
+                json!({ "email": email, "masterPassword": MASTER_PASSWORD }),
+            )
+            .await;
+        if !response.errors.is_empty() || session_token(&response).is_none() {
+            return Err(AppError::Internal(format!(
+                "Login should start a session: {:?}",
+                response.errors
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_login_throttling() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let email = unique_email();
+        app.signup(&email, MASTER_PASSWORD).await?;
+
+        for _ in 0..3 {
+            match login(&app, &email, "not the master password").await {
+                Err(AppError::Authentication(_)) => {}
+                other => {
+                    return Err(AppError::Internal(format!(
+                        "Wrong password should fail: {:?}",
+                        other
+                    )));
+                }
+            }
+        }
+
+        // even the right master password waits out the backoff
+        match login(&app, &email, MASTER_PASSWORD).await {
+            Err(AppError::TooManyRequests {
+                retry_after_seconds,
+                ..
+            }) if retry_after_seconds > 0 => {}
+            other => {
+                return Err(AppError::Internal(format!(
+                    "Repeated failures should be throttled: {:?}",
+                    other
+                )));
+            }
+        }
+
+        // unknown emails are throttled the same way
+        let unknown = unique_email();
+        for _ in 0..3 {
+            let _ = login(&app, &unknown, MASTER_PASSWORD).await;
+        }
+        if !matches!(
+            login(&app, &unknown, MASTER_PASSWORD).await,
+            Err(AppError::TooManyRequests { .. })
+        ) {
+            return Err(AppError::Internal(
+                "Unknown emails should be throttled like known ones".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_signup_answers_the_same_for_taken_emails() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let taken = unique_email();
+        app.signup(&taken, MASTER_PASSWORD).await?;
+
+        let taken_response = signup(&app, &taken).await?;
+        let free_response = signup(&app, &unique_email()).await?;
+
+        if taken_response != free_response {
+            return Err(AppError::Internal(format!(
+                "Signup should not tell whether an email is taken: {} / {}",
+                taken_response, free_response
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_change_email_answers_the_same_for_taken_emails() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let taken = unique_email();
+        app.signup(&taken, MASTER_PASSWORD).await?;
+
+        let email = unique_email();
+        let session = app.signup(&email, MASTER_PASSWORD).await?;
+
+        if change_email(&app, &session, &unique_email()).await.is_ok() {
+            return Err(AppError::Internal(
+                "Changing the email should need a verified session".to_string(),
+            ));
+        }
+
+        app.execute(
+            Some(&session),
+            "mutation($masterPassword: String!) {
+                verifySession(request: { masterPassword: $masterPassword }) { success }
+            }",
+            json!({ "masterPassword": MASTER_PASSWORD }),
+        )
+        .await?;
+
+        let free = unique_email();
+        let taken_response = change_email(&app, &session, &taken).await?;
+        let free_response = change_email(&app, &session, &free).await?;
+
+        if taken_response != free_response {
+            return Err(AppError::Internal(format!(
+                "Changing the email should not tell whether it is taken: {} / {}",
+                taken_response, free_response
+            )));
+        }
+
+        // the signup mail and one notice per change
+        let notices = app.mails_to(&email, 3).await;
+        let masked = [mask_email(&taken), mask_email(&free)];
+        if notices.len() != 3
+            || masked.iter().any(|masked| {
+                !notices
+                    .iter()
+                    .any(|notice| notice.body.contains(masked.as_str()))
+            })
+            || notices
+                .iter()
+                .any(|notice| notice.body.contains(&taken) || notice.body.contains(&free))
+        {
+            return Err(AppError::Internal(format!(
+                "Old address should get a masked notice for both changes: {:?}",
+                notices
+            )));
+        }
+
+        let taken_mails = app.mails_to(&taken, 2).await;
+        let free_mails = app.mails_to(&free, 1).await;
+        if taken_mails.len() != 2
+            || taken_mails[1].body.contains("token=")
+            || free_mails.len() != 1
+            || !free_mails[0].body.contains("token=")
+        {
+            return Err(AppError::Internal(format!(
+                "Only a free address should get a confirmation link: {:?} / {:?}",
+                taken_mails, free_mails
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::LazyLock;
+
 use aes_gcm::{
     aead::{Aead, OsRng},
     AeadCore, Aes256Gcm, Key, KeyInit, Nonce,
@@ -6,13 +8,31 @@ use argon2::{
     password_hash::{rand_core::RngCore, SaltString},
     Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
 };
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
 use rand::Rng;
 use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey, StaticSecret};
 
 use crate::utils::error::{AppError, AppResult};
 use base64::{engine::general_purpose, Engine as _};
 // Constants for encryption
 const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const PUBLIC_KEY_LENGTH: usize = 32;
+
+/// Plaintext bytes per encrypted file chunk
+pub const FILE_CHUNK_SIZE: usize = 64 * 1024;
+/// Random nonce prefix written at the start of an encrypted file
+pub const FILE_HEADER_LENGTH: usize = 7;
+pub const ENCRYPTED_FILE_CHUNK_SIZE: usize = FILE_CHUNK_SIZE + TAG_LENGTH;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Hash of a random password, checked when there is no user so that unknown emails cost as much
+static DUMMY_MASTER_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    hash_master_password(&generate_recovery_key()).expect("dummy master password hashes")
+});
 
 /// Generate a random encryption key (DEK)
 pub fn generate_dek() -> [u8; 32] {
@@ -40,8 +60,8 @@ pub fn hash_master_password(password: &str) -> AppResult<String> {
 
 /// Verify the master password against its hash
 pub fn verify_master_password(password: &str, password_hash: &str) -> AppResult<bool> {
-    let parsed_hash = PasswordHash::new(&password_hash)
-        .map_err(|e| AppError::Crypto(format!("Invalid password hash: {}", e.to_string())))?;
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|e| AppError::Crypto(format!("Invalid password hash: {}", e)))?;
 
     let argon2_instance = Argon2::default();
     let result = argon2_instance
@@ -51,6 +71,11 @@ pub fn verify_master_password(password: &str, password_hash: &str) -> AppResult<
     Ok(result)
 }
 
+/// Spend the time of a master password check on a hash no password matches
+pub fn dummy_verify_master_password(password: &str) {
+    let _ = verify_master_password(password, &DUMMY_MASTER_PASSWORD_HASH);
+}
+
 /// Derive a key encryption key (KEK) from the master password
 pub fn derive_kek(master_password: &str) -> AppResult<Key<Aes256Gcm>> {
     let result = Sha256::digest(master_password.as_bytes());
@@ -151,6 +176,12 @@ pub fn hash_recovery_code(code: &str) -> String {
     format!("{:x}", hash)
 }
 
+/// Hash a session token, to refer to a session without its token
+pub fn hash_session_token(session_token: &str) -> String {
+    let hash = Sha256::digest(session_token.as_bytes());
+    format!("{:x}", hash)
+}
+
 /// Generate a secure token for sessions
 pub fn generate_session_token() -> String {
     format!(
@@ -164,3 +195,243 @@ pub fn generate_session_token() -> String {
 pub fn generate_recovery_keys(count: i32) -> Vec<String> {
     (0..count).map(|_| generate_recovery_key()).collect()
 }
+
+// derived from DEK so that DEK itself only ever encrypts
+fn blind_index_key(dek: &[u8]) -> AppResult<[u8; 32]> {
+    let hkdf = Hkdf::<Sha256>::new(None, dek);
+
+    let mut key = [0u8; 32];
+    hkdf.expand(b"password-vault blind-index", &mut key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    Ok(key)
+}
+
+/// Keyed hash of a value with a subkey of DEK, comparable without decrypting it
+pub fn blind_index(value: &str, dek: &[u8]) -> AppResult<String> {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(&blind_index_key(dek)?)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    mac.update(value.trim().to_lowercase().as_bytes());
+
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// Keyed hash of an email kept after its account is erased, the server secret keeps it from
+/// being matched against a list of addresses
+pub fn hash_receipt_email(email: &str, secret: &str) -> AppResult<String> {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(secret.as_bytes())
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    mac.update(email.trim().to_lowercase().as_bytes());
+
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+/// Wrap a per-file key with DEK
+pub fn wrap_file_key(file_key: &[u8], dek: &[u8]) -> AppResult<String> {
+    encrypt_dek(file_key, Key::<Aes256Gcm>::from_slice(dek))
+}
+
+/// Unwrap a per-file key with DEK
+pub fn unwrap_file_key(wrapped_file_key: &str, dek: &[u8]) -> AppResult<Vec<u8>> {
+    decrypt_dek(wrapped_file_key, Key::<Aes256Gcm>::from_slice(dek))
+}
+
+/// Number of chunks a file of the given plaintext size is encrypted in
+pub fn file_chunk_count(size: u64) -> u64 {
+    size.div_ceil(FILE_CHUNK_SIZE as u64).max(1)
+}
+
+// nonce prefix, big endian chunk counter and a last chunk flag, so chunks
+// cannot be reordered, dropped or appended without failing to decrypt
+fn file_chunk_nonce(nonce_prefix: &[u8], index: u64, last: bool) -> AppResult<[u8; NONCE_LENGTH]> {
+    if nonce_prefix.len() != FILE_HEADER_LENGTH {
+        return Err(AppError::Crypto("Invalid file nonce prefix".to_string()));
+    }
+
+    let index =
+        u32::try_from(index).map_err(|_| AppError::Crypto("File is too large".to_string()))?;
+
+    let mut nonce = [0u8; NONCE_LENGTH];
+    nonce[..FILE_HEADER_LENGTH].copy_from_slice(nonce_prefix);
+    nonce[FILE_HEADER_LENGTH..NONCE_LENGTH - 1].copy_from_slice(&index.to_be_bytes());
+    nonce[NONCE_LENGTH - 1] = last as u8;
+
+    Ok(nonce)
+}
+
+/// Encrypt a file with its own key, chunk by chunk so it can be decrypted as a stream
+pub fn encrypt_file(data: &[u8], file_key: &[u8]) -> AppResult<Vec<u8>> {
+    let key = Key::<Aes256Gcm>::from_slice(file_key);
+    let cipher = Aes256Gcm::new(key);
+
+    let mut nonce_prefix = [0u8; FILE_HEADER_LENGTH];
+    OsRng.fill_bytes(&mut nonce_prefix);
+
+    let chunk_count = file_chunk_count(data.len() as u64);
+
+    let mut encrypted =
+        Vec::with_capacity(FILE_HEADER_LENGTH + data.len() + chunk_count as usize * TAG_LENGTH);
+    encrypted.extend_from_slice(&nonce_prefix);
+
+    for index in 0..chunk_count {
+        let start = index as usize * FILE_CHUNK_SIZE;
+        let end = (start + FILE_CHUNK_SIZE).min(data.len());
+        let nonce = file_chunk_nonce(&nonce_prefix, index, index + 1 == chunk_count)?;
+
+        let cipher_text = cipher
+            .encrypt(Nonce::from_slice(&nonce), &data[start..end])
+            .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+        encrypted.extend_from_slice(&cipher_text);
+    }
+
+    Ok(encrypted)
+}
+
+/// Decrypt one chunk of a file encrypted with encrypt_file
+pub fn decrypt_file_chunk(
+    encrypted_chunk: &[u8],
+    nonce_prefix: &[u8],
+    index: u64,
+    last: bool,
+    file_key: &[u8],
+) -> AppResult<Vec<u8>> {
+    let key = Key::<Aes256Gcm>::from_slice(file_key);
+    let cipher = Aes256Gcm::new(key);
+    let nonce = file_chunk_nonce(nonce_prefix, index, last)?;
+
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), encrypted_chunk)
+        .map_err(|e| AppError::Crypto(e.to_string()))
+}
+
+/// Wrap a per-entry item key with DEK
+pub fn wrap_item_key(item_key: &[u8], dek: &[u8]) -> AppResult<String> {
+    encrypt_dek(item_key, Key::<Aes256Gcm>::from_slice(dek))
+}
+
+/// Unwrap a per-entry item key with DEK
+pub fn unwrap_item_key(wrapped_item_key: &str, dek: &[u8]) -> AppResult<Vec<u8>> {
+    decrypt_dek(wrapped_item_key, Key::<Aes256Gcm>::from_slice(dek))
+}
+
+/// Generate an X25519 keypair for sharing, the private key wrapped with DEK
+pub fn generate_key_pair(dek: &[u8]) -> AppResult<(String, String)> {
+    let private_key = StaticSecret::from(generate_dek());
+    let public_key = PublicKey::from(&private_key);
+
+    let encrypted_private_key =
+        encrypt_dek(private_key.as_bytes(), Key::<Aes256Gcm>::from_slice(dek))?;
+
+    Ok((
+        general_purpose::STANDARD.encode(public_key.as_bytes()),
+        encrypted_private_key,
+    ))
+}
+
+fn decode_public_key(public_key: &[u8]) -> AppResult<PublicKey> {
+    let public_key: [u8; PUBLIC_KEY_LENGTH] = public_key
+        .try_into()
+        .map_err(|_| AppError::Crypto("Invalid public key".to_string()))?;
+
+    Ok(PublicKey::from(public_key))
+}
+
+// derived from the X25519 shared secret and bound to both public keys
+fn sealing_key(
+    shared_secret: &[u8],
+    ephemeral_public_key: &PublicKey,
+    recipient_public_key: &PublicKey,
+) -> AppResult<Key<Aes256Gcm>> {
+    let salt = [
+        ephemeral_public_key.as_bytes().as_slice(),
+        recipient_public_key.as_bytes().as_slice(),
+    ]
+    .concat();
+    let hkdf = Hkdf::<Sha256>::new(Some(&salt), shared_secret);
+
+    let mut key = [0u8; 32];
+    hkdf.expand(b"item-key", &mut key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    Ok(*Key::<Aes256Gcm>::from_slice(&key))
+}
+
+/// Seal an item key to a recipient's public key with an ephemeral X25519 key
+pub fn seal_item_key(item_key: &[u8], recipient_public_key: &str) -> AppResult<String> {
+    let recipient_public_key = decode_public_key(
+        &general_purpose::STANDARD
+            .decode(recipient_public_key)
+            .map_err(|e| AppError::Crypto(e.to_string()))?,
+    )?;
+
+    let ephemeral_private_key = StaticSecret::from(generate_dek());
+    let ephemeral_public_key = PublicKey::from(&ephemeral_private_key);
+
+    let shared_secret = ephemeral_private_key.diffie_hellman(&recipient_public_key);
+
+    if !shared_secret.was_contributory() {
+        return Err(AppError::Crypto("Invalid public key".to_string()));
+    }
+
+    let key = sealing_key(
+        shared_secret.as_bytes(),
+        &ephemeral_public_key,
+        &recipient_public_key,
+    )?;
+
+    let cipher = Aes256Gcm::new(&key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let cipher_text = cipher
+        .encrypt(&nonce, item_key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    // Combine ephemeral public key, nonce and cipher_text and encode
+    let mut sealed = Vec::with_capacity(PUBLIC_KEY_LENGTH + NONCE_LENGTH + cipher_text.len());
+    sealed.extend_from_slice(ephemeral_public_key.as_bytes());
+    sealed.extend_from_slice(nonce.as_slice());
+    sealed.extend_from_slice(&cipher_text);
+
+    Ok(general_purpose::STANDARD.encode(sealed))
+}
+
+/// Open an item key sealed to the user's public key
+pub fn open_item_key(
+    sealed_item_key: &str,
+    encrypted_private_key: &str,
+    dek: &[u8],
+) -> AppResult<Vec<u8>> {
+    let sealed = general_purpose::STANDARD
+        .decode(sealed_item_key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    if sealed.len() < PUBLIC_KEY_LENGTH + NONCE_LENGTH {
+        return Err(AppError::Crypto("Invalid sealed item key".to_string()));
+    }
+
+    let private_key: [u8; 32] =
+        decrypt_dek(encrypted_private_key, Key::<Aes256Gcm>::from_slice(dek))?
+            .try_into()
+            .map_err(|_| AppError::Crypto("Invalid private key".to_string()))?;
+    let private_key = StaticSecret::from(private_key);
+
+    let ephemeral_public_key = decode_public_key(&sealed[..PUBLIC_KEY_LENGTH])?;
+    let nonce = Nonce::from_slice(&sealed[PUBLIC_KEY_LENGTH..PUBLIC_KEY_LENGTH + NONCE_LENGTH]);
+    let cipher_text = &sealed[PUBLIC_KEY_LENGTH + NONCE_LENGTH..];
+
+    let shared_secret = private_key.diffie_hellman(&ephemeral_public_key);
+    let key = sealing_key(
+        shared_secret.as_bytes(),
+        &ephemeral_public_key,
+        &PublicKey::from(&private_key),
+    )?;
+
+    let cipher = Aes256Gcm::new(&key);
+
+    cipher
+        .decrypt(nonce, cipher_text)
+        .map_err(|e| AppError::Crypto(e.to_string()))
+}
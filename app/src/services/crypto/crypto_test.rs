@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod test {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
     use crate::{
         services::crypto::*,
         utils::error::{AppError, AppResult},
@@ -18,6 +21,7 @@ mod test {
         encrypted_dek: String,
     }
 
+    #[allow(clippy::needless_borrow)]
     fn signup() -> AppResult<SignupResponse> {
         let mut recovery_codes_data: Vec<RecoveryCodeResponse> = vec![];
 
@@ -58,6 +62,23 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_signup() -> AppResult<()> {
+        let signup_data = signup()?;
+
+        if !verify_master_password("testing_password@123", &signup_data.master_password_hash)? {
+            return Err(AppError::Crypto("Password verification failed".to_string()));
+        }
+
+        let kek = derive_kek("testing_password@123")?;
+
+        if decrypt_dek(&signup_data.encrypted_dek, &kek)? != signup_data.dek {
+            return Err(AppError::Crypto("DEK mismatch".to_string()));
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_recovery() -> AppResult<()> {
         let signup_data = signup()?;
@@ -95,4 +116,132 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_blind_index() -> AppResult<()> {
+        let dek = generate_dek();
+
+        let index = blind_index("Alice@Example.com ", &dek)?;
+
+        if index != blind_index("alice@example.com", &dek)? {
+            return Err(AppError::Crypto(
+                "Blind index is not normalized".to_string(),
+            ));
+        }
+
+        if index == blind_index("alice@example.com", &generate_dek())? {
+            return Err(AppError::Crypto("Blind index is not keyed".to_string()));
+        }
+
+        // DEK only encrypts, the index is keyed with a subkey of it
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&dek)
+            .map_err(|e| AppError::Crypto(e.to_string()))?;
+        mac.update(b"alice@example.com");
+
+        if index == format!("{:x}", mac.finalize().into_bytes()) {
+            return Err(AppError::Crypto(
+                "Blind index is keyed with DEK".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hash_receipt_email() -> AppResult<()> {
+        let email_hash = hash_receipt_email("Alice@Example.com ", "secret")?;
+
+        if email_hash != hash_receipt_email("alice@example.com", "secret")? {
+            return Err(AppError::Crypto(
+                "Receipt email hash is not normalized".to_string(),
+            ));
+        }
+
+        // a plain hash of the email could be matched against a list of addresses
+        if email_hash == hash_receipt_email("alice@example.com", "other secret")?
+            || email_hash == hash_recovery_code("alice@example.com")
+        {
+            return Err(AppError::Crypto(
+                "Receipt email hash is not keyed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_encryption() -> AppResult<()> {
+        let file_key = generate_dek();
+        let data: Vec<u8> = (0..FILE_CHUNK_SIZE * 2 + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        let encrypted = encrypt_file(&data, &file_key)?;
+        let (nonce_prefix, chunks) = encrypted.split_at(FILE_HEADER_LENGTH);
+        let chunk_count = file_chunk_count(data.len() as u64);
+
+        let mut decrypted = vec![];
+
+        for (index, chunk) in chunks.chunks(ENCRYPTED_FILE_CHUNK_SIZE).enumerate() {
+            let index = index as u64;
+            decrypted.extend(decrypt_file_chunk(
+                chunk,
+                nonce_prefix,
+                index,
+                index + 1 == chunk_count,
+                &file_key,
+            )?);
+        }
+
+        if decrypted != data {
+            return Err(AppError::Crypto("File content mismatch".to_string()));
+        }
+
+        // a truncated file must not pass as complete
+        if decrypt_file_chunk(
+            &chunks[ENCRYPTED_FILE_CHUNK_SIZE..ENCRYPTED_FILE_CHUNK_SIZE * 2],
+            nonce_prefix,
+            1,
+            true,
+            &file_key,
+        )
+        .is_ok()
+        {
+            return Err(AppError::Crypto("Truncated file was accepted".to_string()));
+        }
+
+        let dek = generate_dek();
+
+        if unwrap_file_key(&wrap_file_key(&file_key, &dek)?, &dek)? != file_key {
+            return Err(AppError::Crypto("File key mismatch".to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_key_sharing() -> AppResult<()> {
+        let item_key = generate_dek();
+
+        let recipient_dek = generate_dek();
+        let (public_key, encrypted_private_key) = generate_key_pair(&recipient_dek)?;
+
+        let sealed_item_key = seal_item_key(&item_key, &public_key)?;
+
+        if open_item_key(&sealed_item_key, &encrypted_private_key, &recipient_dek)? != item_key {
+            return Err(AppError::Crypto("Item key mismatch".to_string()));
+        }
+
+        // another user cannot open a key sealed to someone else
+        let other_dek = generate_dek();
+        let (_, other_encrypted_private_key) = generate_key_pair(&other_dek)?;
+
+        if open_item_key(&sealed_item_key, &other_encrypted_private_key, &other_dek).is_ok() {
+            return Err(AppError::Crypto(
+                "Item key was opened by another user".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
 }
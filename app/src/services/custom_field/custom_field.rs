@@ -0,0 +1,151 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::Context;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{app_state::AppState, response::GraphqlResponse},
+    models::{
+        custom_field::{self, CustomFieldType},
+        custom_field_dtos::{CustomFieldInput, CustomFieldResponse, RevealCustomFieldRequest},
+        password,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        crypto::{decrypt_password, encrypt_password},
+        share::accessible_entry_key,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Encrypt custom fields of an entry, each name and value on its own
+pub fn build_custom_fields(
+    password_id: Uuid,
+    user_id: Uuid,
+    custom_fields: Vec<CustomFieldInput>,
+    dek: &[u8; 32],
+) -> AppResult<Vec<custom_field::ActiveModel>> {
+    custom_fields
+        .into_iter()
+        .enumerate()
+        .map(|(position, custom_field)| {
+            Ok(custom_field::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                password_id: Set(password_id),
+                user_id: Set(user_id),
+                position: Set(position as i32),
+                encrypted_name: Set(encrypt_password(custom_field.name.trim(), dek)?),
+                field_type: Set(custom_field.field_type),
+                encrypted_value: Set(custom_field
+                    .value
+                    .as_ref()
+                    .map(|value| encrypt_password(value, dek))
+                    .transpose()?),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+pub async fn find_custom_fields(
+    database_connection: &DatabaseConnection,
+    password_ids: Vec<Uuid>,
+) -> AppResult<HashMap<Uuid, Vec<custom_field::Model>>> {
+    let custom_fields = custom_field::Entity::find()
+        .filter(custom_field::Column::PasswordId.is_in(password_ids))
+        .order_by(custom_field::Column::Position, Order::Asc)
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get custom fields: {}", e)))?;
+
+    let mut custom_fields_by_password: HashMap<Uuid, Vec<custom_field::Model>> = HashMap::new();
+
+    for custom_field in custom_fields {
+        custom_fields_by_password
+            .entry(custom_field.password_id)
+            .or_default()
+            .push(custom_field);
+    }
+
+    Ok(custom_fields_by_password)
+}
+
+/// Decrypt a custom field, leaving hidden values out unless revealed
+pub fn to_custom_field_response(
+    custom_field: &custom_field::Model,
+    dek: &[u8; 32],
+    reveal: bool,
+) -> AppResult<CustomFieldResponse> {
+    let revealed = reveal || custom_field.field_type != CustomFieldType::Hidden;
+
+    let value = if revealed {
+        custom_field
+            .encrypted_value
+            .as_ref()
+            .map(|value| decrypt_password(value, dek))
+            .transpose()?
+    } else {
+        None
+    };
+
+    Ok(CustomFieldResponse {
+        id: custom_field.id,
+        name: decrypt_password(&custom_field.encrypted_name, dek)?,
+        field_type: custom_field.field_type,
+        value,
+        revealed,
+    })
+}
+
+pub fn to_custom_field_responses(
+    custom_fields: Vec<custom_field::Model>,
+    dek: &[u8; 32],
+) -> AppResult<Vec<CustomFieldResponse>> {
+    custom_fields
+        .iter()
+        .map(|custom_field| to_custom_field_response(custom_field, dek, false))
+        .collect()
+}
+
+pub async fn reveal_custom_field(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RevealCustomFieldRequest,
+) -> AppResult<GraphqlResponse<CustomFieldResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let (custom_field, password_entry) = custom_field::Entity::find()
+        .filter(custom_field::Column::Id.eq(request.id))
+        .find_also_related(password::Entity)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find custom field: {}", e)))?
+        .and_then(|(custom_field, password_entry)| password_entry.map(|p| (custom_field, p)))
+        .ok_or(AppError::NotFound("Custom field not found".to_string()))?;
+
+    // fields of shared entries are revealed to their recipients too
+    let entry_key = accessible_entry_key(database_connection, &password_entry, user_id, &dek_u8_32)
+        .await
+        .map_err(|e| match e {
+            AppError::NotFound(_) => AppError::NotFound("Custom field not found".to_string()),
+            e => e,
+        })?;
+
+    Ok(GraphqlResponse::<CustomFieldResponse> {
+        success: true,
+        message: "Custom field found".to_string(),
+        data: to_custom_field_response(&custom_field, &entry_key, true)?,
+    })
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        models::{
+            custom_field::{self, CustomFieldType},
+            custom_field_dtos::CustomFieldInput,
+        },
+        services::{crypto::generate_dek, custom_field::*},
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_hidden_custom_field() -> AppResult<()> {
+        let dek = generate_dek();
+
+        let custom_fields = build_custom_fields(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            vec![
+                CustomFieldInput {
+                    name: "Account number".to_string(),
+                    field_type: CustomFieldType::Text,
+                    value: Some("0042".to_string()),
+                },
+                CustomFieldInput {
+                    name: "PIN".to_string(),
+                    field_type: CustomFieldType::Hidden,
+                    value: Some("1234".to_string()),
+                },
+            ],
+            &dek,
+        )?;
+
+        let mut models: Vec<custom_field::Model> = vec![];
+
+        for custom_field in custom_fields {
+            models.push(custom_field::Model {
+                id: custom_field.id.unwrap(),
+                password_id: custom_field.password_id.unwrap(),
+                user_id: custom_field.user_id.unwrap(),
+                position: custom_field.position.unwrap(),
+                encrypted_name: custom_field.encrypted_name.unwrap(),
+                field_type: custom_field.field_type.unwrap(),
+                encrypted_value: custom_field.encrypted_value.unwrap(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            });
+        }
+
+        let listed = to_custom_field_responses(models.clone(), &dek)?;
+
+        if listed[0].value.as_deref() != Some("0042") || listed[1].name != "PIN" {
+            return Err(AppError::Crypto("Custom field mismatch".to_string()));
+        }
+
+        if listed[1].value.is_some() || listed[1].revealed {
+            return Err(AppError::Crypto("Hidden value was revealed".to_string()));
+        }
+
+        let revealed = to_custom_field_response(&models[1], &dek, true)?;
+
+        if revealed.value.as_deref() != Some("1234") {
+            return Err(AppError::Crypto("Hidden value mismatch".to_string()));
+        }
+
+        Ok(())
+    }
+}
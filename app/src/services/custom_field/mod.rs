@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod custom_field;
+mod custom_field_test;
+
+pub use custom_field::*;
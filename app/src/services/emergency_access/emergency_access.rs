@@ -0,0 +1,637 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::Context;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, Order,
+    QueryFilter, QueryOrder, Set, TransactionError, TransactionTrait, sea_query::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        emergency_access::{self, EmergencyAccessStatus, EmergencyAccessType},
+        emergency_access_dtos::{
+            AddEmergencyContactRequest, ApproveEmergencyAccessRequest,
+            DeclineEmergencyAccessRequest, EmergencyAccessResponse, EmergencyAccessesResponse,
+            EmergencyTakeoverRequest, EmergencyVaultResponse, GetEmergencyVaultRequest,
+            RemoveEmergencyContactRequest, RequestEmergencyAccessRequest,
+        },
+        password::{self, VaultItemType},
+        user,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        crypto::{self, open_item_key, seal_item_key},
+        mail::{MailTemplate, send_mail},
+        password::to_shared_password_responses,
+        session::revoke_user_sessions,
+        share::entry_key,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Requests still pending once their waiting period has passed
+pub fn is_grant_due(emergency_access: &emergency_access::Model, now: DateTime<Utc>) -> bool {
+    emergency_access
+        .grant_at()
+        .is_some_and(|grant_at| grant_at <= now)
+}
+
+fn to_emergency_access_response(
+    emergency_access: &emergency_access::Model,
+    emails: &HashMap<Uuid, String>,
+) -> EmergencyAccessResponse {
+    let email = |user_id: &Uuid| emails.get(user_id).cloned().unwrap_or_default();
+
+    EmergencyAccessResponse {
+        id: emergency_access.id,
+        grantor_email: email(&emergency_access.grantor_id),
+        grantee_email: email(&emergency_access.grantee_id),
+        access_type: emergency_access.access_type,
+        status: emergency_access.status,
+        wait_days: emergency_access.wait_days,
+        requested_at: emergency_access.requested_at,
+        grant_at: emergency_access.grant_at(),
+        granted_at: emergency_access.granted_at,
+        taken_over_at: emergency_access.taken_over_at,
+        created_at: emergency_access.created_at,
+    }
+}
+
+async fn find_grantor_access(
+    database_connection: &DatabaseConnection,
+    grantor_id: Uuid,
+    id: Uuid,
+) -> AppResult<emergency_access::Model> {
+    emergency_access::Entity::find()
+        .filter(emergency_access::Column::GrantorId.eq(grantor_id))
+        .filter(emergency_access::Column::Id.eq(id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find emergency access: {}", e)))?
+        .ok_or(AppError::NotFound("Emergency access not found".to_string()))
+}
+
+async fn find_grantee_access(
+    database_connection: &DatabaseConnection,
+    grantee_id: Uuid,
+    id: Uuid,
+) -> AppResult<emergency_access::Model> {
+    emergency_access::Entity::find()
+        .filter(emergency_access::Column::GranteeId.eq(grantee_id))
+        .filter(emergency_access::Column::Id.eq(id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find emergency access: {}", e)))?
+        .ok_or(AppError::NotFound("Emergency access not found".to_string()))
+}
+
+/// Fail unless the access is granted and has not been used up by a takeover
+pub fn ensure_granted(emergency_access: &emergency_access::Model) -> AppResult<()> {
+    match emergency_access.status {
+        EmergencyAccessStatus::Granted => Ok(()),
+        EmergencyAccessStatus::TakenOver => Err(AppError::Conflict(
+            "Emergency access has already been used".to_string(),
+        )),
+        _ => Err(AppError::Authorization(
+            "Emergency access has not been granted".to_string(),
+        )),
+    }
+}
+
+/// Open the grantor's DEK for a contact whose access has been granted
+async fn open_grantor_dek(
+    database_connection: &DatabaseConnection,
+    emergency_access: &emergency_access::Model,
+    dek: &[u8; 32],
+) -> AppResult<[u8; 32]> {
+    ensure_granted(emergency_access)?;
+
+    let encrypted_private_key = user::Entity::find_by_id(emergency_access.grantee_id)
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .and_then(|user| user.encrypted_private_key)
+        .ok_or(AppError::Crypto("Private key is missing".to_string()))?;
+
+    open_item_key(&emergency_access.wrapped_dek, &encrypted_private_key, dek)?
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))
+}
+
+async fn update_status(
+    database_connection: &DatabaseConnection,
+    emergency_access: emergency_access::Model,
+    status: EmergencyAccessStatus,
+) -> AppResult<emergency_access::Model> {
+    let now = Utc::now();
+    let mut updated_access: emergency_access::ActiveModel = emergency_access.into();
+
+    match status {
+        EmergencyAccessStatus::Requested => {
+            updated_access.requested_at = Set(Some(now));
+            updated_access.declined_at = Set(None);
+        }
+        EmergencyAccessStatus::Granted => updated_access.granted_at = Set(Some(now)),
+        EmergencyAccessStatus::Declined => updated_access.declined_at = Set(Some(now)),
+        EmergencyAccessStatus::TakenOver => updated_access.taken_over_at = Set(Some(now)),
+        EmergencyAccessStatus::Idle => {}
+    }
+
+    updated_access.status = Set(status);
+    updated_access.updated_at = Set(now);
+
+    updated_access
+        .update(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update emergency access: {}", e)))
+}
+
+pub async fn add_emergency_contact(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: AddEmergencyContactRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let contact = user::Entity::find()
+        .filter(user::Column::Email.eq(&request.email))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+
+    if contact.id == user_id {
+        return Err(AppError::Validation(
+            "You cannot be your own emergency contact".to_string(),
+        ));
+    }
+
+    let public_key = contact.public_key.as_ref().ok_or(AppError::Conflict(
+        "User has not set up sharing yet".to_string(),
+    ))?;
+
+    let existing_access = emergency_access::Entity::find()
+        .filter(emergency_access::Column::GrantorId.eq(user_id))
+        .filter(emergency_access::Column::GranteeId.eq(contact.id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find emergency access: {}", e)))?;
+
+    if existing_access.is_some() {
+        return Err(AppError::Conflict(
+            "User is already an emergency contact".to_string(),
+        ));
+    }
+
+    // the contact can only open the DEK once the server grants the access
+    emergency_access::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        grantor_id: Set(user_id),
+        grantee_id: Set(contact.id),
+        access_type: Set(request.access_type.unwrap_or_default()),
+        status: Set(EmergencyAccessStatus::Idle),
+        wait_days: Set(request.wait_days),
+        wrapped_dek: Set(seal_item_key(&user_redis_session.dek, public_key)?),
+        ..Default::default()
+    }
+    .insert(database_connection.as_ref())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to save emergency access: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Emergency contact added successfully".to_string(),
+    })
+}
+
+pub async fn remove_emergency_contact(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RemoveEmergencyContactRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    // either side may end the arrangement
+    emergency_access::Entity::delete_many()
+        .filter(emergency_access::Column::Id.eq(request.id))
+        .filter(
+            Condition::any()
+                .add(emergency_access::Column::GrantorId.eq(user_id))
+                .add(emergency_access::Column::GranteeId.eq(user_id)),
+        )
+        .exec(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to delete emergency access: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Emergency contact removed successfully".to_string(),
+    })
+}
+
+pub async fn get_emergency_accesses(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<EmergencyAccessesResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let emergency_accesses = emergency_access::Entity::find()
+        .filter(
+            Condition::any()
+                .add(emergency_access::Column::GrantorId.eq(user_id))
+                .add(emergency_access::Column::GranteeId.eq(user_id)),
+        )
+        .order_by(emergency_access::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get emergency accesses: {}", e)))?;
+
+    let user_ids: Vec<Uuid> = emergency_accesses
+        .iter()
+        .flat_map(|emergency_access| [emergency_access.grantor_id, emergency_access.grantee_id])
+        .collect();
+
+    let emails: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .into_iter()
+        .map(|user| (user.id, user.email))
+        .collect();
+
+    let (trusted_contacts, trusted_by): (Vec<_>, Vec<_>) = emergency_accesses
+        .iter()
+        .partition(|emergency_access| emergency_access.grantor_id == user_id);
+
+    Ok(GraphqlResponse::<EmergencyAccessesResponse> {
+        success: true,
+        message: "Emergency accesses found".to_string(),
+        data: EmergencyAccessesResponse {
+            trusted_contacts: trusted_contacts
+                .into_iter()
+                .map(|emergency_access| to_emergency_access_response(emergency_access, &emails))
+                .collect(),
+            trusted_by: trusted_by
+                .into_iter()
+                .map(|emergency_access| to_emergency_access_response(emergency_access, &emails))
+                .collect(),
+        },
+    })
+}
+
+pub async fn request_emergency_access(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RequestEmergencyAccessRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let emergency_access = find_grantee_access(database_connection, user_id, request.id).await?;
+
+    match emergency_access.status {
+        EmergencyAccessStatus::Idle | EmergencyAccessStatus::Declined => {}
+        EmergencyAccessStatus::Requested => {
+            return Err(AppError::Conflict(
+                "Emergency access has already been requested".to_string(),
+            ));
+        }
+        EmergencyAccessStatus::Granted => {
+            return Err(AppError::Conflict(
+                "Emergency access has already been granted".to_string(),
+            ));
+        }
+        EmergencyAccessStatus::TakenOver => {
+            return Err(AppError::Conflict(
+                "Emergency access has already been used".to_string(),
+            ));
+        }
+    }
+
+    let grantor_id = emergency_access.grantor_id;
+    let wait_days = emergency_access.wait_days;
+
+    update_status(
+        database_connection,
+        emergency_access,
+        EmergencyAccessStatus::Requested,
+    )
+    .await?;
+
+    // the grantor has the waiting period to decline
+    if let Some(grantor) = user::Entity::find_by_id(grantor_id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+    {
+        send_mail(
+            app_state,
+            &grantor.email,
+            MailTemplate::EmergencyAccessRequested {
+                grantee_email: user_redis_session.email.clone(),
+                wait_days,
+            },
+        );
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Emergency access requested successfully".to_string(),
+    })
+}
+
+pub async fn approve_emergency_access(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ApproveEmergencyAccessRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let emergency_access = find_grantor_access(database_connection, user_id, request.id).await?;
+
+    if emergency_access.status != EmergencyAccessStatus::Requested {
+        return Err(AppError::Conflict(
+            "Emergency access has not been requested".to_string(),
+        ));
+    }
+
+    update_status(
+        database_connection,
+        emergency_access,
+        EmergencyAccessStatus::Granted,
+    )
+    .await?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Emergency access approved successfully".to_string(),
+    })
+}
+
+pub async fn decline_emergency_access(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeclineEmergencyAccessRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let emergency_access = find_grantor_access(database_connection, user_id, request.id).await?;
+
+    // declining also takes back access that has already been granted
+    match emergency_access.status {
+        EmergencyAccessStatus::Idle => {
+            return Err(AppError::Conflict(
+                "Emergency access has not been requested".to_string(),
+            ));
+        }
+        EmergencyAccessStatus::TakenOver => {
+            return Err(AppError::Conflict(
+                "Emergency access has already been used".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    update_status(
+        database_connection,
+        emergency_access,
+        EmergencyAccessStatus::Declined,
+    )
+    .await?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Emergency access declined successfully".to_string(),
+    })
+}
+
+pub async fn get_emergency_vault(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: GetEmergencyVaultRequest,
+) -> AppResult<GraphqlResponse<EmergencyVaultResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let emergency_access = find_grantee_access(database_connection, user_id, request.id).await?;
+    let grantor_dek = open_grantor_dek(database_connection, &emergency_access, &dek_u8_32).await?;
+
+    let grantor = user::Entity::find_by_id(emergency_access.grantor_id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+
+    // only the grantor's personal vault, organizations grant their own access
+    let passwords = password::Entity::find()
+        .filter(password::Column::UserId.eq(grantor.id))
+        .filter(password::Column::CollectionId.is_null())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(false))
+        .order_by(password::Column::CreatedAt, Order::Desc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    let item_keys = passwords
+        .iter()
+        .map(|password_entry| entry_key(password_entry, &grantor_dek))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let passwords_response =
+        to_shared_password_responses(database_connection, &passwords, &item_keys).await?;
+
+    Ok(GraphqlResponse::<EmergencyVaultResponse> {
+        success: true,
+        message: "Emergency vault found".to_string(),
+        data: EmergencyVaultResponse {
+            grantor_email: grantor.email,
+            passwords: passwords_response,
+        },
+    })
+}
+
+pub async fn emergency_takeover(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: EmergencyTakeoverRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let emergency_access = find_grantee_access(database_connection, user_id, request.id).await?;
+
+    if emergency_access.access_type != EmergencyAccessType::Takeover {
+        return Err(AppError::Authorization(
+            "Emergency access is view only".to_string(),
+        ));
+    }
+
+    let grantor_dek = open_grantor_dek(database_connection, &emergency_access, &dek_u8_32).await?;
+
+    let grantor = user::Entity::find_by_id(emergency_access.grantor_id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+
+    // the DEK stays the same, only the master password wrapping it changes
+    let new_master_password_hash = crypto::hash_master_password(&request.new_master_password)?;
+    let new_kek = crypto::derive_kek(&request.new_master_password)?;
+    let encrypted_dek = crypto::encrypt_dek(&grantor_dek, &new_kek)?;
+
+    let grantor_id = grantor.id;
+    let grantor_email = grantor.email.clone();
+    let emergency_access_id = emergency_access.id;
+    let now = Utc::now();
+
+    let mut user_model: user::ActiveModel = grantor.into();
+    user_model.master_password_hash = Set(new_master_password_hash);
+    user_model.encrypted_dek = Set(encrypted_dek);
+    user_model.updated_at = Set(now);
+
+    database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                // only one takeover gets the grant, a concurrent one finds it used up
+                let update_result = emergency_access::Entity::update_many()
+                    .col_expr(
+                        emergency_access::Column::Status,
+                        Expr::value(EmergencyAccessStatus::TakenOver),
+                    )
+                    .col_expr(emergency_access::Column::TakenOverAt, Expr::value(now))
+                    .col_expr(emergency_access::Column::UpdatedAt, Expr::value(now))
+                    .filter(emergency_access::Column::Id.eq(emergency_access_id))
+                    .filter(emergency_access::Column::Status.eq(EmergencyAccessStatus::Granted))
+                    .exec(txn)
+                    .await?;
+
+                if update_result.rows_affected == 0 {
+                    return Err(DbErr::RecordNotUpdated);
+                }
+
+                user_model.update(txn).await?;
+
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| match e {
+            TransactionError::Transaction(DbErr::RecordNotUpdated) => {
+                AppError::Conflict("Emergency access has already been used".to_string())
+            }
+            e => AppError::Internal(format!("Failed to take over account: {}", e)),
+        })?;
+
+    // whoever was signed in as the grantor is signed out
+    revoke_user_sessions(app_state, grantor_id, None)?;
+
+    send_mail(
+        app_state,
+        &grantor_email,
+        MailTemplate::EmergencyTakeover {
+            grantee_email: user_redis_session.email.clone(),
+            at: now,
+        },
+    );
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Master Password Changed Successfully".to_string(),
+    })
+}
+
+/// Grant the requests whose waiting period passed without the grantor declining
+pub async fn grant_due_emergency_accesses(
+    database_connection: &DatabaseConnection,
+) -> AppResult<u64> {
+    let now = Utc::now();
+
+    let requested_accesses = emergency_access::Entity::find()
+        .filter(emergency_access::Column::Status.eq(EmergencyAccessStatus::Requested))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get emergency accesses: {}", e)))?;
+
+    let due_ids: Vec<Uuid> = requested_accesses
+        .iter()
+        .filter(|emergency_access| is_grant_due(emergency_access, now))
+        .map(|emergency_access| emergency_access.id)
+        .collect();
+
+    if due_ids.is_empty() {
+        return Ok(0);
+    }
+
+    // a request declined in the meantime is no longer requested and stays declined
+    let update_result = emergency_access::Entity::update_many()
+        .col_expr(
+            emergency_access::Column::Status,
+            Expr::value(EmergencyAccessStatus::Granted),
+        )
+        .col_expr(emergency_access::Column::GrantedAt, Expr::value(now))
+        .col_expr(emergency_access::Column::UpdatedAt, Expr::value(now))
+        .filter(emergency_access::Column::Id.is_in(due_ids))
+        .filter(emergency_access::Column::Status.eq(EmergencyAccessStatus::Requested))
+        .exec(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to grant emergency accesses: {}", e)))?;
+
+    Ok(update_result.rows_affected)
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        models::emergency_access::{self, EmergencyAccessStatus, EmergencyAccessType},
+        services::emergency_access::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    fn emergency_access(
+        status: EmergencyAccessStatus,
+        requested_days_ago: Option<i64>,
+    ) -> emergency_access::Model {
+        emergency_access::Model {
+            id: Uuid::new_v4(),
+            grantor_id: Uuid::new_v4(),
+            grantee_id: Uuid::new_v4(),
+            access_type: EmergencyAccessType::View,
+            status,
+            wait_days: 7,
+            wrapped_dek: String::new(),
+            requested_at: requested_days_ago.map(|days| Utc::now() - Duration::days(days)),
+            granted_at: None,
+            declined_at: None,
+            taken_over_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_is_grant_due() -> AppResult<()> {
+        let now = Utc::now();
+
+        if !is_grant_due(
+            &emergency_access(EmergencyAccessStatus::Requested, Some(8)),
+            now,
+        ) {
+            return Err(AppError::Internal(
+                "Request past its waiting period should be granted".to_string(),
+            ));
+        }
+
+        if is_grant_due(
+            &emergency_access(EmergencyAccessStatus::Requested, Some(6)),
+            now,
+        ) {
+            return Err(AppError::Internal(
+                "Request within its waiting period should not be granted".to_string(),
+            ));
+        }
+
+        if is_grant_due(
+            &emergency_access(EmergencyAccessStatus::Declined, Some(8)),
+            now,
+        ) {
+            return Err(AppError::Internal(
+                "Declined request should not be granted".to_string(),
+            ));
+        }
+
+        if emergency_access(EmergencyAccessStatus::Idle, None)
+            .grant_at()
+            .is_some()
+        {
+            return Err(AppError::Internal(
+                "Access without a request should have no grant time".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ensure_granted() -> AppResult<()> {
+        ensure_granted(&emergency_access(EmergencyAccessStatus::Granted, Some(8)))?;
+
+        for status in [
+            EmergencyAccessStatus::Idle,
+            EmergencyAccessStatus::Requested,
+            EmergencyAccessStatus::Declined,
+        ] {
+            if !matches!(
+                ensure_granted(&emergency_access(status, Some(8))),
+                Err(AppError::Authorization(_))
+            ) {
+                return Err(AppError::Internal(
+                    "Access that was not granted opened the vault".to_string(),
+                ));
+            }
+        }
+
+        // a takeover uses up the grant
+        let taken_over = emergency_access(EmergencyAccessStatus::TakenOver, Some(8));
+
+        if !matches!(ensure_granted(&taken_over), Err(AppError::Conflict(_))) {
+            return Err(AppError::Internal(
+                "Grant was used again after a takeover".to_string(),
+            ));
+        }
+
+        if is_grant_due(&taken_over, Utc::now()) {
+            return Err(AppError::Internal(
+                "Taken over access should not be granted again".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
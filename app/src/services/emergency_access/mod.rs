@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod emergency_access;
+mod emergency_access_test;
+
+pub use emergency_access::*;
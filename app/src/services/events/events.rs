@@ -0,0 +1,211 @@
+use std::{sync::Arc, time::Duration};
+
+use async_graphql::Context;
+use chrono::Utc;
+use futures::{Stream, StreamExt, future, stream};
+use redis::Commands;
+use tokio::{sync::broadcast::error::RecvError, time};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{app_state::AppState, graphql_context::GraphQLContext},
+    models::{
+        event_dtos::{SessionRevokedEvent, VaultChangeType, VaultChangedEvent, VaultEvent},
+        user_dtos::SessionState,
+    },
+    services::crypto::hash_session_token,
+    utils::error::{AppError, AppResult},
+};
+
+/// Redis channel the events of all instances go through
+pub const EVENTS_CHANNEL: &str = "vault_events";
+
+/// Events kept for a slow subscription before it starts skipping them
+pub const EVENTS_BUFFER: usize = 256;
+
+const EVENTS_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+fn try_publish_event(app_state: &AppState, event: &VaultEvent) -> AppResult<()> {
+    let payload = serde_json::to_string(event)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize event: {}", e)))?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    redis_connection
+        .publish::<&str, String, ()>(EVENTS_CHANNEL, payload)
+        .map_err(|e| AppError::Internal(format!("Failed to publish event: {}", e)))
+}
+
+/// Publish an event to the subscriptions of every instance
+pub fn publish_event(app_state: &AppState, event: VaultEvent) {
+    // the change is already committed, clients that miss it catch up with changesSince
+    if let Err(e) = try_publish_event(app_state, &event) {
+        tracing::error!("{}", e);
+    }
+}
+
+pub fn publish_vault_changed(
+    app_state: &AppState,
+    user_id: Uuid,
+    entry_id: Option<Uuid>,
+    change_type: VaultChangeType,
+    revision: i64,
+) {
+    publish_event(
+        app_state,
+        VaultEvent::VaultChanged {
+            user_id,
+            event: VaultChangedEvent {
+                entry_id,
+                change_type,
+                revision,
+            },
+        },
+    );
+}
+
+pub fn publish_session_revoked(app_state: &AppState, user_id: Uuid, session_token: &str) {
+    publish_event(
+        app_state,
+        VaultEvent::SessionRevoked {
+            user_id,
+            session_token_hash: hash_session_token(session_token),
+            event: SessionRevokedEvent {
+                revoked_at: Utc::now(),
+            },
+        },
+    );
+}
+
+/// Forward the events published on redis to the subscriptions of this instance
+pub async fn listen_for_events(app_state: Arc<AppState>) {
+    loop {
+        if let Err(e) = forward_events(&app_state).await {
+            tracing::error!("Event listener failed: {}", e);
+        }
+
+        time::sleep(EVENTS_RECONNECT_DELAY).await;
+    }
+}
+
+async fn forward_events(app_state: &AppState) -> redis::RedisResult<()> {
+    let client = redis::Client::open(app_state.env_variables.redis_url.to_string())?;
+
+    let mut pubsub = client.get_async_pubsub().await?;
+    pubsub.subscribe(EVENTS_CHANNEL).await?;
+
+    let mut messages = pubsub.into_on_message();
+
+    while let Some(message) = messages.next().await {
+        let event = message
+            .get_payload::<String>()
+            .ok()
+            .and_then(|payload| serde_json::from_str::<VaultEvent>(&payload).ok());
+
+        // sending only fails while nobody is subscribed
+        if let Some(event) = event {
+            let _ = app_state.event_sender.send(event);
+        }
+    }
+
+    Ok(())
+}
+
+fn subscribe_events(app_state: &AppState) -> impl Stream<Item = VaultEvent> + use<> {
+    stream::unfold(
+        app_state.event_sender.subscribe(),
+        |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    // a lagging subscription skips ahead, clients catch up with changesSince
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    )
+}
+
+/// Hash of the session token of this connection
+fn current_session_token_hash(ctx: &Context<'_>) -> AppResult<String> {
+    let gql_ctx = ctx
+        .data::<Arc<GraphQLContext>>()
+        .map_err(|_| AppError::Internal("GraphQL Context is not passed".to_string()))?;
+
+    let session_token = gql_ctx
+        .session_token
+        .as_ref()
+        .ok_or(AppError::Authorization(
+            "Session token is missing".to_string(),
+        ))?;
+
+    Ok(hash_session_token(session_token))
+}
+
+fn is_session_revoked(event: &VaultEvent, user_id: Uuid, session_token_hash: &str) -> bool {
+    matches!(
+        event,
+        VaultEvent::SessionRevoked {
+            user_id: event_user_id,
+            session_token_hash: event_session_token_hash,
+            ..
+        } if *event_user_id == user_id && event_session_token_hash == session_token_hash
+    )
+}
+
+pub fn vault_changed(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+) -> AppResult<impl Stream<Item = VaultChangedEvent> + use<>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user_id = session_state.user_id();
+    let session_token_hash = current_session_token_hash(ctx)?;
+
+    // a revoked session stops hearing about the vault
+    Ok(subscribe_events(app_state)
+        .take_while(move |event| {
+            future::ready(!is_session_revoked(event, user_id, &session_token_hash))
+        })
+        .filter_map(move |event| async move {
+            match event {
+                VaultEvent::VaultChanged {
+                    user_id: event_user_id,
+                    event,
+                } if event_user_id == user_id => Some(event),
+                _ => None,
+            }
+        }))
+}
+
+pub fn session_revoked(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+) -> AppResult<impl Stream<Item = SessionRevokedEvent> + use<>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user_id = session_state.user_id();
+    let session_token_hash = current_session_token_hash(ctx)?;
+
+    // the subscription ends with the session it belongs to
+    Ok(subscribe_events(app_state)
+        .filter_map(move |event| {
+            let revoked = is_session_revoked(&event, user_id, &session_token_hash);
+
+            async move {
+                match event {
+                    VaultEvent::SessionRevoked { event, .. } if revoked => Some(event),
+                    _ => None,
+                }
+            }
+        })
+        .take(1))
+}
@@ -0,0 +1,308 @@
+use std::io::Read;
+
+use aes_gcm::{
+    AeadCore, Aes256Gcm, Key, KeyInit, Nonce,
+    aead::{Aead, OsRng, Payload, rand_core::RngCore},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        custom_field_dtos::CustomFieldInput, password::VaultItemType,
+        password_dtos::PasswordUriInput,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+const ARCHIVE_FORMAT: &str = "vault-archive";
+const ARCHIVE_SALT_LENGTH: usize = 16;
+
+// the archive decides how hard its key is to derive, these keep it bounded
+const MAX_ARGON2_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ARGON2_ITERATIONS: u32 = 10;
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveFolder {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTag {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveAttachment {
+    pub file_name: String,
+    pub content_type: Option<String>,
+    /// Files the client encrypted are archived as uploaded, together with their key
+    pub client_encrypted: bool,
+    pub client_file_key: Option<String>,
+    /// Base64 encoded file
+    pub data: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub id: Uuid,
+    pub item_type: VaultItemType,
+    pub website_url: Option<String>,
+    pub app_name: Option<String>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub password: Option<String>,
+    /// Versioned JSON payload of the non-login item types
+    pub payload: Option<String>,
+    pub payload_version: Option<i32>,
+    pub folder_id: Option<Uuid>,
+    pub favorite: bool,
+    pub trashed: bool,
+    pub uris: Vec<PasswordUriInput>,
+    pub custom_fields: Vec<CustomFieldInput>,
+    pub tag_ids: Vec<Uuid>,
+    pub attachments: Vec<ArchiveAttachment>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Everything in a personal vault, decrypted
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub folders: Vec<ArchiveFolder>,
+    pub tags: Vec<ArchiveTag>,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// Argon2id cost of the archive key
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveKdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for ArchiveKdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 4,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedArchive {
+    format: String,
+    version: u32,
+    kdf: ArchiveKdfParams,
+    salt: String,
+    nonce: String,
+    cipher_text: String,
+}
+
+fn archive_key(password: &str, salt: &[u8], kdf: &ArchiveKdfParams) -> AppResult<Key<Aes256Gcm>> {
+    if kdf.memory_kib > MAX_ARGON2_MEMORY_KIB
+        || kdf.iterations > MAX_ARGON2_ITERATIONS
+        || kdf.parallelism > MAX_ARGON2_PARALLELISM
+    {
+        return Err(AppError::Validation(
+            "Archive KDF parameters are too high".to_string(),
+        ));
+    }
+
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| AppError::Validation(format!("Invalid archive KDF: {}", e)))?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    Ok(key.into())
+}
+
+// the format and version are authenticated along with the content
+fn archive_aad(version: u32) -> String {
+    format!("{}:{}", ARCHIVE_FORMAT, version)
+}
+
+/// Compress and encrypt an archive with a key derived from its password
+pub fn seal_archive(
+    archive: &VaultArchive,
+    password: &str,
+    kdf: &ArchiveKdfParams,
+) -> AppResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    serde_json::to_writer(&mut encoder, archive)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize archive: {}", e)))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| AppError::Internal(format!("Failed to compress archive: {}", e)))?;
+
+    let mut salt = [0u8; ARCHIVE_SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let cipher_text = Aes256Gcm::new(&archive_key(password, &salt, kdf)?)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &compressed,
+                aad: archive_aad(ARCHIVE_VERSION).as_bytes(),
+            },
+        )
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    serde_json::to_vec(&SealedArchive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        kdf: kdf.clone(),
+        salt: general_purpose::STANDARD.encode(salt),
+        nonce: general_purpose::STANDARD.encode(nonce),
+        cipher_text: general_purpose::STANDARD.encode(cipher_text),
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to serialize archive: {}", e)))
+}
+
+/// Read an encrypted or plain archive
+pub fn open_archive(data: &[u8], password: Option<&str>) -> AppResult<VaultArchive> {
+    let value: serde_json::Value = serde_json::from_slice(data)
+        .map_err(|e| AppError::Validation(format!("Invalid archive: {}", e)))?;
+
+    let archive_data =
+        if value.get("format").and_then(|format| format.as_str()) == Some(ARCHIVE_FORMAT) {
+            let sealed_archive: SealedArchive = serde_json::from_value(value)
+                .map_err(|e| AppError::Validation(format!("Invalid archive: {}", e)))?;
+
+            if sealed_archive.version > ARCHIVE_VERSION {
+                return Err(AppError::Validation(format!(
+                    "Unsupported archive version {}",
+                    sealed_archive.version
+                )));
+            }
+
+            let password = password.ok_or(AppError::Validation(
+                "Archive password is required".to_string(),
+            ))?;
+            let decode = |value: &str| {
+                general_purpose::STANDARD
+                    .decode(value)
+                    .map_err(|_| AppError::Validation("Invalid archive encoding".to_string()))
+            };
+            let nonce = decode(&sealed_archive.nonce)?;
+
+            if nonce.len() != 12 {
+                return Err(AppError::Validation("Invalid archive nonce".to_string()));
+            }
+
+            let key = archive_key(
+                password,
+                &decode(&sealed_archive.salt)?,
+                &sealed_archive.kdf,
+            )?;
+            let compressed = Aes256Gcm::new(&key)
+                .decrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: &decode(&sealed_archive.cipher_text)?,
+                        aad: archive_aad(sealed_archive.version).as_bytes(),
+                    },
+                )
+                .map_err(|_| AppError::Validation("Invalid archive password".to_string()))?;
+
+            let mut archive_data = vec![];
+            GzDecoder::new(compressed.as_slice())
+                .read_to_end(&mut archive_data)
+                .map_err(|e| AppError::Validation(format!("Invalid archive: {}", e)))?;
+
+            archive_data
+        } else {
+            data.to_vec()
+        };
+
+    let archive: VaultArchive = serde_json::from_slice(&archive_data)
+        .map_err(|e| AppError::Validation(format!("Invalid archive: {}", e)))?;
+
+    if archive.version > ARCHIVE_VERSION {
+        return Err(AppError::Validation(format!(
+            "Unsupported archive version {}",
+            archive.version
+        )));
+    }
+
+    Ok(archive)
+}
+
+/// Logins of an archive in the column layout of browser exports, plus their folder
+pub fn archive_to_csv(archive: &VaultArchive) -> AppResult<Vec<u8>> {
+    let folder_path = |mut folder_id: Option<Uuid>| {
+        let mut path = vec![];
+
+        // bounded by the folder count, in case the parents form a cycle
+        while let Some(folder) = folder_id
+            .and_then(|id| archive.folders.iter().find(|folder| folder.id == id))
+            .filter(|_| path.len() < archive.folders.len())
+        {
+            path.insert(0, folder.name.as_str());
+            folder_id = folder.parent_id;
+        }
+
+        path.join("/")
+    };
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer
+        .write_record(["name", "url", "username", "password", "note", "folder"])
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
+
+    for entry in archive
+        .entries
+        .iter()
+        .filter(|entry| entry.item_type == VaultItemType::Login && !entry.trashed)
+    {
+        let note = entry
+            .custom_fields
+            .iter()
+            .find(|custom_field| custom_field.name == "Notes")
+            .and_then(|custom_field| custom_field.value.as_deref());
+
+        writer
+            .write_record([
+                entry.app_name.as_deref().unwrap_or_default(),
+                entry.website_url.as_deref().unwrap_or_default(),
+                entry
+                    .username
+                    .as_deref()
+                    .or(entry.email.as_deref())
+                    .unwrap_or_default(),
+                entry.password.as_deref().unwrap_or_default(),
+                note.unwrap_or_default(),
+                &folder_path(entry.folder_id),
+            ])
+            .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))
+}
@@ -0,0 +1,833 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    sync::Arc,
+};
+
+use async_graphql::Context;
+use base64::{Engine as _, engine::general_purpose};
+use chrono::Utc;
+use futures::{SinkExt, StreamExt, TryStreamExt, channel::mpsc, stream::BoxStream};
+use sea_orm::{
+    ColumnTrait, DbErr, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, Set,
+    TransactionError, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{app_state::AppState, response::GraphqlResponse},
+    models::{
+        attachment, custom_field,
+        custom_field_dtos::CustomFieldInput,
+        event_dtos::VaultChangeType,
+        export_dtos::{
+            ExportFormat, ExportKdbxRequest, ExportVaultRequest, ExportVaultResponse,
+            RestoreVaultRequest, RestoreVaultResponse,
+        },
+        folder,
+        password::{self, VaultItemType},
+        password_dtos::PasswordUriInput,
+        password_tag, password_uri, tag, user,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        attachment::{
+            attachment_stream, decode_client_file_key, delete_attachment_blobs, find_attachments,
+            used_attachment_bytes,
+        },
+        crypto::{
+            blind_index, decrypt_password, encrypt_file, encrypt_password, generate_dek,
+            unwrap_file_key, wrap_file_key,
+        },
+        custom_field::{build_custom_fields, find_custom_fields},
+        events::publish_vault_changed,
+        export::{
+            ARCHIVE_VERSION, ArchiveAttachment, ArchiveEntry, ArchiveFolder, ArchiveKdfParams,
+            ArchiveTag, KdbxReferences, KdbxWriter, VaultArchive, archive_to_csv, kdbx_layout,
+            open_archive, seal_archive,
+        },
+        import::INSERT_BATCH_SIZE,
+        password::{account_identity, build_password_uris, find_password_uris, next_revision},
+        session::check_master_password,
+        share::entry_key,
+        tag::find_password_tags,
+        throttle::{AuthAction, AuthAttempt},
+        uri::normalize_url,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Chunks of a KeePass download held while the client catches up
+const KDBX_CHANNEL_CAPACITY: usize = 4;
+
+/// Folders ordered so that every parent comes before its subfolders
+pub fn folders_parents_first(folders: &[ArchiveFolder]) -> Vec<&ArchiveFolder> {
+    let folder_ids: HashSet<Uuid> = folders.iter().map(|folder| folder.id).collect();
+    let mut ordered_ids: HashSet<Uuid> = HashSet::new();
+    let mut ordered: Vec<&ArchiveFolder> = vec![];
+    let mut pending: Vec<&ArchiveFolder> = folders.iter().collect();
+
+    while !pending.is_empty() {
+        let (ready, waiting): (Vec<&ArchiveFolder>, Vec<&ArchiveFolder>) =
+            pending.into_iter().partition(|folder| {
+                folder.parent_id.is_none_or(|parent_id| {
+                    !folder_ids.contains(&parent_id) || ordered_ids.contains(&parent_id)
+                })
+            });
+
+        // folders whose parents form a cycle are restored at the top level
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            break;
+        }
+
+        ordered_ids.extend(ready.iter().map(|folder| folder.id));
+        ordered.extend(ready);
+        pending = waiting;
+    }
+
+    ordered
+}
+
+/// A decrypted personal vault whose attachments are still in the blob store
+struct VaultSnapshot {
+    /// Archive with the attachments of every entry left empty
+    archive: VaultArchive,
+    attachments: HashMap<Uuid, Vec<attachment::Model>>,
+}
+
+async fn snapshot_vault(
+    app_state: &AppState,
+    user_id: Uuid,
+    dek: &[u8; 32],
+) -> AppResult<VaultSnapshot> {
+    let database_connection = &app_state.database_connection;
+
+    let folders = folder::Entity::find()
+        .filter(folder::Column::UserId.eq(user_id))
+        .order_by(folder::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get folders: {}", e)))?;
+
+    let tags = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .order_by(tag::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get tags: {}", e)))?;
+
+    // collection entries belong to their organization and are left out
+    let passwords = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::CollectionId.is_null())
+        .order_by(password::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    let password_ids: Vec<Uuid> = passwords.iter().map(|p| p.id).collect();
+
+    let mut uris_by_password =
+        find_password_uris(database_connection, password_ids.clone()).await?;
+    let mut custom_fields_by_password =
+        find_custom_fields(database_connection, password_ids.clone()).await?;
+    let mut tags_by_password =
+        find_password_tags(database_connection, user_id, password_ids.clone()).await?;
+    let attachments = find_attachments(database_connection, password_ids).await?;
+
+    let mut entries = vec![];
+
+    for password_entry in &passwords {
+        let entry_key = entry_key(password_entry, dek)?;
+        let decrypt = |value: &Option<String>| {
+            value
+                .as_ref()
+                .map(|value| decrypt_password(value, &entry_key))
+                .transpose()
+        };
+
+        entries.push(ArchiveEntry {
+            id: password_entry.id,
+            item_type: password_entry.item_type,
+            website_url: password_entry.website_url.clone(),
+            app_name: password_entry.app_name.clone(),
+            username: decrypt(&password_entry.encrypted_username)?,
+            email: decrypt(&password_entry.encrypted_email)?,
+            password: decrypt(&password_entry.encrypted_password)?,
+            payload: decrypt(&password_entry.encrypted_payload)?,
+            payload_version: password_entry.payload_version,
+            folder_id: password_entry.folder_id,
+            favorite: password_entry.favorite,
+            trashed: password_entry.is_deleted,
+            uris: uris_by_password
+                .remove(&password_entry.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|uri| PasswordUriInput {
+                    uri: uri.uri,
+                    match_type: Some(uri.match_type),
+                })
+                .collect(),
+            custom_fields: custom_fields_by_password
+                .remove(&password_entry.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|custom_field| {
+                    Ok(CustomFieldInput {
+                        name: decrypt_password(&custom_field.encrypted_name, &entry_key)?,
+                        field_type: custom_field.field_type,
+                        value: decrypt(&custom_field.encrypted_value)?,
+                    })
+                })
+                .collect::<AppResult<Vec<_>>>()?,
+            tag_ids: tags_by_password
+                .remove(&password_entry.id)
+                .unwrap_or_default()
+                .iter()
+                .map(|tag| tag.id)
+                .collect(),
+            attachments: vec![],
+            created_at: password_entry.created_at,
+            updated_at: password_entry.updated_at,
+        });
+    }
+
+    let archive = VaultArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        folders: folders
+            .iter()
+            .map(|folder| {
+                Ok(ArchiveFolder {
+                    id: folder.id,
+                    parent_id: folder.parent_id,
+                    name: decrypt_password(&folder.encrypted_name, dek)?,
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?,
+        tags: tags
+            .iter()
+            .map(|tag| {
+                Ok(ArchiveTag {
+                    id: tag.id,
+                    name: decrypt_password(&tag.encrypted_name, dek)?,
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?,
+        entries,
+    };
+
+    Ok(VaultSnapshot {
+        archive,
+        attachments,
+    })
+}
+
+/// Decrypt the personal vault of a user into an archive
+async fn build_archive(
+    app_state: &AppState,
+    user_id: Uuid,
+    dek: &[u8; 32],
+) -> AppResult<VaultArchive> {
+    let VaultSnapshot {
+        mut archive,
+        mut attachments,
+    } = snapshot_vault(app_state, user_id, dek).await?;
+
+    // attachments of personal entries are encrypted with the DEK
+    for entry in archive.entries.iter_mut() {
+        for attachment in attachments.remove(&entry.id).unwrap_or_default() {
+            let data: Vec<u8> = attachment_stream(&app_state.blob_store, &attachment, dek)
+                .await?
+                .try_concat()
+                .await?;
+
+            entry.attachments.push(ArchiveAttachment {
+                file_name: decrypt_password(&attachment.encrypted_file_name, dek)?,
+                content_type: attachment
+                    .encrypted_content_type
+                    .as_ref()
+                    .map(|content_type| decrypt_password(content_type, dek))
+                    .transpose()?,
+                client_encrypted: attachment.client_encrypted,
+                client_file_key: if attachment.client_encrypted {
+                    Some(
+                        general_purpose::STANDARD
+                            .encode(unwrap_file_key(&attachment.wrapped_file_key, dek)?),
+                    )
+                } else {
+                    None
+                },
+                data: general_purpose::STANDARD.encode(data),
+            });
+        }
+    }
+
+    Ok(archive)
+}
+
+pub async fn export_vault(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ExportVaultRequest,
+) -> AppResult<GraphqlResponse<ExportVaultResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    if let Some(master_password) = &request.master_password {
+        let user = user::Entity::find_by_id(user_id)
+            .one(database_connection.as_ref())
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to find user: {}", e)))?
+            .ok_or(AppError::NotFound("User not found".to_string()))?;
+
+        let mut redis_connection = app_state.redis_pool_manager.get().map_err(|_| {
+            AppError::Internal("Failed to get redis connection from pool".to_string())
+        })?;
+
+        // a plain export is guessed against like a login, so failures are throttled the same way
+        let attempt = AuthAttempt::new(ctx, AuthAction::MasterPassword, &user_id.to_string());
+        check_master_password(
+            &mut redis_connection,
+            &app_state.env_variables,
+            &attempt,
+            master_password,
+            &user.master_password_hash,
+        )?;
+    }
+
+    let archive = build_archive(app_state, user_id, &dek_u8_32).await?;
+
+    let entries = archive.entries.len() as i32;
+    let attachments = archive
+        .entries
+        .iter()
+        .map(|entry| entry.attachments.len() as i32)
+        .sum();
+    let date = archive.exported_at.format("%Y%m%d");
+
+    let (file_name, content_type, data) = match request.format {
+        ExportFormat::EncryptedArchive => {
+            let archive_password = request.archive_password.unwrap_or_default();
+
+            // deriving the archive key is deliberately slow
+            let data = tokio::task::spawn_blocking(move || {
+                seal_archive(&archive, &archive_password, &ArchiveKdfParams::default())
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to seal archive: {}", e)))??;
+
+            (format!("vault-{}.vault", date), "application/json", data)
+        }
+        ExportFormat::Json => (
+            format!("vault-{}.json", date),
+            "application/json",
+            serde_json::to_vec_pretty(&archive)
+                .map_err(|e| AppError::Internal(format!("Failed to serialize archive: {}", e)))?,
+        ),
+        ExportFormat::Csv => (
+            format!("vault-{}.csv", date),
+            "text/csv",
+            archive_to_csv(&archive)?,
+        ),
+    };
+
+    Ok(GraphqlResponse::<ExportVaultResponse> {
+        success: true,
+        message: "Vault exported successfully".to_string(),
+        data: ExportVaultResponse {
+            file_name,
+            content_type: content_type.to_string(),
+            data: general_purpose::STANDARD.encode(data),
+            entries,
+            attachments,
+        },
+    })
+}
+
+/// A KeePass database, written while it is streamed
+pub struct KdbxDownload {
+    pub file_name: String,
+    pub stream: BoxStream<'static, AppResult<Vec<u8>>>,
+}
+
+pub async fn export_kdbx(
+    app_state: &Arc<AppState>,
+    user_redis_session: &UserRedisSession,
+    request: ExportKdbxRequest,
+) -> AppResult<KdbxDownload> {
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let VaultSnapshot {
+        archive,
+        mut attachments,
+    } = snapshot_vault(app_state, user_id, &dek_u8_32).await?;
+
+    let mut references = KdbxReferences {
+        tag_names: archive
+            .tags
+            .iter()
+            .map(|tag| (tag.id, tag.name.clone()))
+            .collect(),
+        ..Default::default()
+    };
+    let mut binaries: Vec<attachment::Model> = vec![];
+
+    // files the client encrypted cannot be opened by KeePass and are left out
+    for entry in &archive.entries {
+        for attachment in attachments
+            .remove(&entry.id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|attachment| !attachment.client_encrypted)
+        {
+            references.binaries.entry(entry.id).or_default().push((
+                decrypt_password(&attachment.encrypted_file_name, &dek_u8_32)?,
+                binaries.len(),
+            ));
+            binaries.push(attachment);
+        }
+    }
+
+    // deriving the database key is deliberately slow
+    let password = request.password;
+    let (mut writer, header) = tokio::task::spawn_blocking(move || {
+        KdbxWriter::new(&password, &ArchiveKdfParams::default())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to create database: {}", e)))??;
+
+    let file_name = format!("vault-{}.kdbx", archive.exported_at.format("%Y%m%d"));
+    let blob_store = app_state.blob_store.clone();
+    let (mut sender, receiver) = mpsc::channel::<AppResult<Vec<u8>>>(KDBX_CHANNEL_CAPACITY);
+
+    // the database is written as the client reads it, attachments chunk by chunk
+    tokio::spawn(async move {
+        let result: AppResult<()> = async {
+            let mut send = async |data: Vec<u8>| {
+                if data.is_empty() {
+                    return Ok(());
+                }
+
+                sender
+                    .send(Ok(data))
+                    .await
+                    .map_err(|_| AppError::Internal("Download was cancelled".to_string()))
+            };
+
+            send(header).await?;
+
+            for attachment in &binaries {
+                send(writer.start_binary(attachment.size as u64)?).await?;
+
+                let mut written = 0u64;
+                let mut stream = attachment_stream(&blob_store, attachment, &dek_u8_32).await?;
+
+                while let Some(chunk) = stream.try_next().await? {
+                    written += chunk.len() as u64;
+                    send(writer.write(&chunk)?).await?;
+                }
+
+                if written != attachment.size as u64 {
+                    return Err(AppError::Internal(
+                        "Attachment size does not match".to_string(),
+                    ));
+                }
+            }
+
+            let recycle_bin_id = Uuid::new_v4();
+            send(writer.start_document(recycle_bin_id)?).await?;
+
+            for node in kdbx_layout(&archive, Uuid::new_v4(), recycle_bin_id) {
+                send(writer.write_node(&node, &references)?).await?;
+            }
+
+            send(writer.finish()?).await
+        }
+        .await;
+
+        // a failed download ends with an error instead of a truncated database
+        if let Err(e) = result {
+            let _ = sender.send(Err(e)).await;
+        }
+    });
+
+    Ok(KdbxDownload {
+        file_name,
+        stream: receiver.boxed(),
+    })
+}
+
+pub async fn restore_vault(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RestoreVaultRequest,
+) -> AppResult<GraphqlResponse<RestoreVaultResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let env_variables = &app_state.env_variables;
+    let blob_store = &app_state.blob_store;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    // restoring rebuilds an account, merging into existing data is what importVault is for
+    let existing_passwords = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::CollectionId.is_null())
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count passwords: {}", e)))?;
+    let existing_folders = folder::Entity::find()
+        .filter(folder::Column::UserId.eq(user_id))
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count folders: {}", e)))?;
+    let existing_tags = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count tags: {}", e)))?;
+
+    if existing_passwords + existing_folders + existing_tags > 0 {
+        return Err(AppError::Conflict(
+            "An archive can only be restored into an empty vault".to_string(),
+        ));
+    }
+
+    let upload = request
+        .file
+        .value(ctx)
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    let size = upload
+        .size()
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    if size > env_variables.import_max_bytes {
+        return Err(AppError::Validation(format!(
+            "Archive is larger than {} bytes",
+            env_variables.import_max_bytes
+        )));
+    }
+
+    let archive_password = request.archive_password;
+    let archive = tokio::task::spawn_blocking(move || {
+        let mut data = vec![];
+        upload
+            .into_read()
+            .read_to_end(&mut data)
+            .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+
+        open_archive(&data, archive_password.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))??;
+
+    let mut folder_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut folder_models: Vec<folder::ActiveModel> = vec![];
+
+    for archive_folder in folders_parents_first(&archive.folders) {
+        let id = Uuid::new_v4();
+
+        folder_models.push(folder::ActiveModel {
+            id: Set(id),
+            user_id: Set(user_id),
+            parent_id: Set(archive_folder
+                .parent_id
+                .and_then(|parent_id| folder_ids.get(&parent_id).copied())),
+            encrypted_name: Set(encrypt_password(archive_folder.name.trim(), &dek_u8_32)?),
+            ..Default::default()
+        });
+        folder_ids.insert(archive_folder.id, id);
+    }
+
+    let mut tag_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut tag_ids_by_blind_index: HashMap<String, Uuid> = HashMap::new();
+    let mut tag_models: Vec<tag::ActiveModel> = vec![];
+
+    for archive_tag in &archive.tags {
+        let name = archive_tag.name.trim();
+        let name_blind_index = blind_index(name, &dek_u8_32)?;
+
+        // tag names are unique per user, so tags differing only in case are merged
+        let id = match tag_ids_by_blind_index.get(&name_blind_index) {
+            Some(id) => *id,
+            None => {
+                let id = Uuid::new_v4();
+                tag_models.push(tag::ActiveModel {
+                    id: Set(id),
+                    user_id: Set(user_id),
+                    encrypted_name: Set(encrypt_password(name, &dek_u8_32)?),
+                    name_blind_index: Set(name_blind_index.clone()),
+                    ..Default::default()
+                });
+                tag_ids_by_blind_index.insert(name_blind_index, id);
+                id
+            }
+        };
+
+        tag_ids.insert(archive_tag.id, id);
+    }
+
+    let mut attachment_data: Vec<(Uuid, ArchiveAttachment, Vec<u8>)> = vec![];
+
+    for entry in &archive.entries {
+        for archive_attachment in &entry.attachments {
+            let data = general_purpose::STANDARD
+                .decode(&archive_attachment.data)
+                .map_err(|_| AppError::Validation("Invalid attachment in archive".to_string()))?;
+
+            attachment_data.push((entry.id, archive_attachment.clone(), data));
+        }
+    }
+
+    let attachment_bytes: u64 = attachment_data
+        .iter()
+        .map(|(_, _, data)| data.len() as u64)
+        .sum();
+
+    if used_attachment_bytes(database_connection, user_id).await? + attachment_bytes
+        > env_variables.attachment_quota_bytes
+    {
+        return Err(AppError::Validation(
+            "Attachment storage quota exceeded".to_string(),
+        ));
+    }
+
+    let mut password_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut password_models: Vec<password::ActiveModel> = vec![];
+    let mut password_uris: Vec<password_uri::ActiveModel> = vec![];
+    let mut custom_fields: Vec<custom_field::ActiveModel> = vec![];
+    let mut password_tags: Vec<password_tag::ActiveModel> = vec![];
+
+    for entry in archive.entries {
+        let password_id = Uuid::new_v4();
+        let encrypt = |value: &Option<String>| {
+            value
+                .as_ref()
+                .map(|value| encrypt_password(value, &dek_u8_32))
+                .transpose()
+        };
+
+        let normalized_url = entry
+            .website_url
+            .as_deref()
+            .and_then(|website_url| normalize_url(website_url).ok());
+        let (canonical_website_url, website_host) = normalized_url
+            .map(|normalized_url| (normalized_url.canonical, normalized_url.host))
+            .unzip();
+
+        let account_blind_index =
+            account_identity(entry.username.as_deref(), entry.email.as_deref())
+                .filter(|_| entry.item_type == VaultItemType::Login)
+                .map(|account| blind_index(&account, &dek_u8_32))
+                .transpose()?;
+
+        password_models.push(password::ActiveModel {
+            id: Set(password_id),
+            user_id: Set(user_id),
+            item_type: Set(entry.item_type),
+            website_url: Set(entry.website_url.clone()),
+            canonical_website_url: Set(canonical_website_url),
+            website_host: Set(website_host),
+            app_name: Set(entry.app_name.clone()),
+            encrypted_username: Set(encrypt(&entry.username)?),
+            encrypted_email: Set(encrypt(&entry.email)?),
+            encrypted_password: Set(encrypt(&entry.password)?),
+            encrypted_payload: Set(encrypt(&entry.payload)?),
+            payload_version: Set(entry.payload_version),
+            account_blind_index: Set(account_blind_index),
+            is_deleted: Set(entry.trashed),
+            folder_id: Set(entry
+                .folder_id
+                .and_then(|folder_id| folder_ids.get(&folder_id).copied())),
+            favorite: Set(entry.favorite),
+            created_at: Set(entry.created_at),
+            updated_at: Set(entry.updated_at),
+            ..Default::default()
+        });
+
+        password_uris.extend(build_password_uris(password_id, user_id, entry.uris));
+        custom_fields.extend(build_custom_fields(
+            password_id,
+            user_id,
+            entry.custom_fields,
+            &dek_u8_32,
+        )?);
+
+        let entry_tag_ids: HashSet<Uuid> = entry
+            .tag_ids
+            .iter()
+            .filter_map(|tag_id| tag_ids.get(tag_id).copied())
+            .collect();
+        password_tags.extend(
+            entry_tag_ids
+                .into_iter()
+                .map(|tag_id| password_tag::ActiveModel {
+                    password_id: Set(password_id),
+                    tag_id: Set(tag_id),
+                }),
+        );
+
+        password_ids.insert(entry.id, password_id);
+    }
+
+    let mut attachment_models: Vec<attachment::ActiveModel> = vec![];
+    let mut stored_attachments: Vec<attachment::Model> = vec![];
+
+    for (entry_id, archive_attachment, data) in attachment_data {
+        let size = data.len() as i64;
+
+        let stored = async {
+            let (blob, file_key) = match &archive_attachment.client_file_key {
+                Some(client_file_key) if archive_attachment.client_encrypted => {
+                    (data, decode_client_file_key(client_file_key)?)
+                }
+                _ => {
+                    let file_key = generate_dek();
+                    (encrypt_file(&data, &file_key)?, file_key.to_vec())
+                }
+            };
+
+            let attachment_model = attachment::Model {
+                id: Uuid::new_v4(),
+                password_id: password_ids[&entry_id],
+                user_id,
+                encrypted_file_name: encrypt_password(&archive_attachment.file_name, &dek_u8_32)?,
+                encrypted_content_type: archive_attachment
+                    .content_type
+                    .as_ref()
+                    .map(|content_type| encrypt_password(content_type, &dek_u8_32))
+                    .transpose()?,
+                size,
+                client_encrypted: archive_attachment.client_encrypted
+                    && archive_attachment.client_file_key.is_some(),
+                wrapped_file_key: wrap_file_key(&file_key, &dek_u8_32)?,
+                storage_backend: blob_store.backend(),
+                storage_key: blob_store.put(blob).await?,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+
+            AppResult::Ok(attachment_model)
+        }
+        .await;
+
+        match stored {
+            Ok(attachment_model) => {
+                attachment_models.push(attachment_model.clone().into());
+                stored_attachments.push(attachment_model);
+            }
+            Err(e) => {
+                delete_attachment_blobs(blob_store.as_ref(), &stored_attachments).await?;
+                return Err(e);
+            }
+        }
+    }
+
+    let response = RestoreVaultResponse {
+        folders: folder_models.len() as i32,
+        tags: tag_models.len() as i32,
+        entries: password_models.len() as i32,
+        attachments: attachment_models.len() as i32,
+    };
+
+    let result: Result<i64, TransactionError<DbErr>> = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                for folder_model in folder_models {
+                    folder::Entity::insert(folder_model).exec(txn).await?;
+                }
+
+                if !tag_models.is_empty() {
+                    tag::Entity::insert_many(tag_models).exec(txn).await?;
+                }
+
+                let revision = next_revision(txn, user_id).await?;
+
+                for password_model in password_models.iter_mut() {
+                    password_model.revision = Set(revision);
+                    password_model.created_revision = Set(revision);
+                }
+
+                for batch in password_models.chunks(INSERT_BATCH_SIZE) {
+                    password::Entity::insert_many(batch.to_vec())
+                        .exec(txn)
+                        .await?;
+                }
+
+                for batch in password_uris.chunks(INSERT_BATCH_SIZE) {
+                    password_uri::Entity::insert_many(batch.to_vec())
+                        .exec(txn)
+                        .await?;
+                }
+
+                for batch in custom_fields.chunks(INSERT_BATCH_SIZE) {
+                    custom_field::Entity::insert_many(batch.to_vec())
+                        .exec(txn)
+                        .await?;
+                }
+
+                for batch in password_tags.chunks(INSERT_BATCH_SIZE) {
+                    password_tag::Entity::insert_many(batch.to_vec())
+                        .exec(txn)
+                        .await?;
+                }
+
+                for batch in attachment_models.chunks(INSERT_BATCH_SIZE) {
+                    attachment::Entity::insert_many(batch.to_vec())
+                        .exec(txn)
+                        .await?;
+                }
+
+                Ok(revision)
+            })
+        })
+        .await;
+
+    let revision = match result {
+        Ok(revision) => revision,
+        Err(e) => {
+            // the blobs were stored before the transaction and would be orphaned
+            delete_attachment_blobs(blob_store.as_ref(), &stored_attachments).await?;
+
+            return Err(AppError::Internal(format!(
+                "Failed to restore vault: {}",
+                e
+            )));
+        }
+    };
+
+    publish_vault_changed(app_state, user_id, None, VaultChangeType::Bulk, revision);
+
+    Ok(GraphqlResponse::<RestoreVaultResponse> {
+        success: true,
+        message: "Vault restored successfully".to_string(),
+        data: response,
+    })
+}
@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod test {
+    use aes_gcm::aead::{OsRng, rand_core::RngCore};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        models::{
+            custom_field::CustomFieldType, custom_field_dtos::CustomFieldInput,
+            password::VaultItemType,
+        },
+        services::{export::*, import::parse_keepass_kdbx},
+        utils::error::{AppError, AppResult},
+    };
+
+    // cheap parameters, the defaults take seconds in debug builds
+    fn test_kdf() -> ArchiveKdfParams {
+        ArchiveKdfParams {
+            memory_kib: 1024,
+            iterations: 1,
+            parallelism: 1,
+        }
+    }
+
+    fn archive() -> VaultArchive {
+        let parent_id = Uuid::new_v4();
+        let child_id = Uuid::new_v4();
+
+        VaultArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            folders: vec![
+                ArchiveFolder {
+                    id: child_id,
+                    parent_id: Some(parent_id),
+                    name: "Mail".to_string(),
+                },
+                ArchiveFolder {
+                    id: parent_id,
+                    parent_id: None,
+                    name: "Work".to_string(),
+                },
+            ],
+            tags: vec![],
+            entries: vec![ArchiveEntry {
+                id: Uuid::new_v4(),
+                item_type: VaultItemType::Login,
+                website_url: Some("https://example.com".to_string()),
+                app_name: Some("Example".to_string()),
+                username: None,
+                email: Some("alice@example.com".to_string()),
+                password: Some("secret".to_string()),
+                payload: None,
+                payload_version: None,
+                folder_id: Some(child_id),
+                favorite: false,
+                trashed: false,
+                uris: vec![],
+                custom_fields: vec![CustomFieldInput {
+                    name: "Notes".to_string(),
+                    field_type: CustomFieldType::Text,
+                    value: Some("recovery hint".to_string()),
+                }],
+                tag_ids: vec![],
+                attachments: vec![ArchiveAttachment {
+                    file_name: "codes.txt".to_string(),
+                    content_type: Some("text/plain".to_string()),
+                    client_encrypted: false,
+                    client_file_key: None,
+                    data: "cmVjb3ZlcnkgY29kZXM=".to_string(),
+                }],
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sealed_archive_round_trip() -> AppResult<()> {
+        let sealed_archive = seal_archive(&archive(), "correct horse battery", &test_kdf())?;
+
+        if String::from_utf8_lossy(&sealed_archive).contains("secret") {
+            return Err(AppError::Internal(
+                "Sealed archive contains plaintext".to_string(),
+            ));
+        }
+
+        let opened_archive = open_archive(&sealed_archive, Some("correct horse battery"))?;
+
+        if opened_archive.entries[0].password.as_deref() != Some("secret")
+            || opened_archive.entries[0].attachments[0].data != "cmVjb3ZlcnkgY29kZXM="
+        {
+            return Err(AppError::Internal(
+                "Archive changed in the round trip".to_string(),
+            ));
+        }
+
+        if open_archive(&sealed_archive, Some("wrong password")).is_ok()
+            || open_archive(&sealed_archive, None).is_ok()
+        {
+            return Err(AppError::Internal(
+                "Sealed archive opened without its password".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plain_archive() -> AppResult<()> {
+        let plain_archive =
+            serde_json::to_vec(&archive()).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if open_archive(&plain_archive, None)?.folders.len() != 2 {
+            return Err(AppError::Internal("Plain archive was not read".to_string()));
+        }
+
+        let csv = String::from_utf8(archive_to_csv(&archive())?)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if csv
+            != "name,url,username,password,note,folder\n\
+                Example,https://example.com,alice@example.com,secret,recovery hint,Work/Mail\n"
+        {
+            return Err(AppError::Internal(format!("Unexpected CSV: {}", csv)));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_folders_parents_first() -> AppResult<()> {
+        let archive = archive();
+        let ordered = folders_parents_first(&archive.folders);
+
+        if ordered
+            .iter()
+            .map(|folder| folder.name.as_str())
+            .collect::<Vec<_>>()
+            != ["Work", "Mail"]
+        {
+            return Err(AppError::Internal(
+                "Subfolder was ordered before its parent".to_string(),
+            ));
+        }
+
+        let first_id = Uuid::new_v4();
+        let second_id = Uuid::new_v4();
+        let cycle = vec![
+            ArchiveFolder {
+                id: first_id,
+                parent_id: Some(second_id),
+                name: "First".to_string(),
+            },
+            ArchiveFolder {
+                id: second_id,
+                parent_id: Some(first_id),
+                name: "Second".to_string(),
+            },
+        ];
+
+        if folders_parents_first(&cycle).len() != 2 {
+            return Err(AppError::Internal(
+                "Folders in a cycle were dropped".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_kdbx_round_trip() -> AppResult<()> {
+        let mut archive = archive();
+        archive.entries[0].custom_fields.push(CustomFieldInput {
+            name: "TOTP".to_string(),
+            field_type: CustomFieldType::Hidden,
+            value: Some("JBSWY3DPEHPK3PXP".to_string()),
+        });
+
+        let mut trashed_entry = archive.entries[0].clone();
+        trashed_entry.id = Uuid::new_v4();
+        trashed_entry.trashed = true;
+        archive.entries.push(trashed_entry);
+
+        let mut references = KdbxReferences::default();
+        references
+            .binaries
+            .insert(archive.entries[0].id, vec![("codes.txt".to_string(), 0)]);
+
+        // larger than a block, so the payload is sealed in several
+        let mut attachment = vec![0u8; 3 * KDBX_BLOCK_SIZE];
+        OsRng.fill_bytes(&mut attachment);
+
+        let (mut writer, mut database) = KdbxWriter::new("correct horse battery", &test_kdf())?;
+        database.extend(writer.start_binary(attachment.len() as u64)?);
+        database.extend(writer.write(&attachment)?);
+
+        let recycle_bin_id = Uuid::new_v4();
+        database.extend(writer.start_document(recycle_bin_id)?);
+
+        for node in kdbx_layout(&archive, Uuid::new_v4(), recycle_bin_id) {
+            database.extend(writer.write_node(&node, &references)?);
+        }
+
+        database.extend(writer.finish()?);
+
+        let parsed_import = parse_keepass_kdbx(&database, "correct horse battery")?;
+
+        // the recycle bin is not imported
+        if parsed_import.entries.len() != 1 {
+            return Err(AppError::Internal(format!(
+                "Expected 1 entry, got {}",
+                parsed_import.entries.len()
+            )));
+        }
+
+        let entry = &parsed_import.entries[0];
+
+        if entry.name.as_deref() != Some("Example")
+            || entry.username.as_deref() != Some("alice@example.com")
+            || entry.password.as_deref() != Some("secret")
+            || entry.notes.as_deref() != Some("recovery hint")
+            || entry.folder != ["Work", "Mail"]
+            || !entry
+                .totp
+                .as_deref()
+                .is_some_and(|totp| totp.contains("secret=JBSWY3DPEHPK3PXP"))
+        {
+            return Err(AppError::Internal(format!(
+                "Entry changed in the round trip: {:?}",
+                entry
+            )));
+        }
+
+        if parse_keepass_kdbx(&database, "wrong password").is_ok() {
+            return Err(AppError::Internal(
+                "Database opened with a wrong password".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,626 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20::{
+    ChaCha20,
+    cipher::{KeyIvInit, StreamCipher},
+};
+use chrono::{DateTime, Utc};
+use flate2::{Compression, write::GzEncoder};
+use hmac::Mac;
+use sha2::{Digest, Sha256, Sha512};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    models::{custom_field::CustomFieldType, password::VaultItemType},
+    services::{
+        export::{
+            ArchiveEntry, ArchiveFolder, ArchiveKdfParams, VaultArchive, folders_parents_first,
+        },
+        import::{
+            CIPHER_CHACHA20, EXTRA_URL_PREFIX, HEADER_CIPHER_ID, HEADER_COMPRESSION_FLAGS,
+            HEADER_ENCRYPTION_IV, HEADER_END, HEADER_KDF_PARAMETERS, HEADER_MASTER_SEED,
+            INNER_HEADER_END, INNER_HEADER_STREAM_ID, INNER_HEADER_STREAM_KEY,
+            INNER_STREAM_CHACHA20, KDF_ARGON2ID, SIGNATURE_1, SIGNATURE_2, block_hmac, hmac_key,
+            transform_key,
+        },
+    },
+    utils::error::{AppError, AppResult},
+};
+
+const INNER_HEADER_BINARY: u8 = 3;
+
+const VARIANT_UINT32: u8 = 0x04;
+const VARIANT_UINT64: u8 = 0x05;
+const VARIANT_BYTES: u8 = 0x42;
+
+/// Size of the HMAC protected blocks, and how much compressed data is held before sealing
+pub const KDBX_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// Seconds from 0001-01-01, where KDBX 4 times start, to the unix epoch
+const KDBX_EPOCH_OFFSET: i64 = 62_135_596_800;
+
+/// Payload fields of the non-login item types that KeePass should keep protected
+const PROTECTED_PAYLOAD_FIELDS: [&str; 5] = [
+    "number",
+    "security_code",
+    "secret",
+    "private_key",
+    "passphrase",
+];
+
+/// A database in document order, entries of a group come before its subgroups
+pub enum KdbxNode<'a> {
+    OpenGroup { id: Uuid, name: &'a str },
+    Entry(&'a ArchiveEntry),
+    CloseGroup,
+}
+
+/// Everything the XML of an entry refers to, besides the entry itself
+#[derive(Default)]
+pub struct KdbxReferences {
+    pub tag_names: HashMap<Uuid, String>,
+    /// Attachment file names of each entry, with their index in the inner header
+    pub binaries: HashMap<Uuid, Vec<(String, usize)>>,
+}
+
+fn push_field(buffer: &mut Vec<u8>, field_id: u8, value: &[u8]) {
+    buffer.push(field_id);
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value);
+}
+
+fn variant_dictionary(items: &[(u8, &str, Vec<u8>)]) -> Vec<u8> {
+    let mut dictionary = 0x0100u16.to_le_bytes().to_vec();
+
+    for (value_type, key, value) in items {
+        dictionary.push(*value_type);
+        dictionary.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        dictionary.extend_from_slice(key.as_bytes());
+        dictionary.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        dictionary.extend_from_slice(value);
+    }
+
+    dictionary.push(0);
+    dictionary
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+// characters XML 1.0 cannot hold are dropped
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn kdbx_uuid(id: Uuid) -> String {
+    general_purpose::STANDARD.encode(id.as_bytes())
+}
+
+fn kdbx_time(time: DateTime<Utc>) -> String {
+    general_purpose::STANDARD.encode((time.timestamp() + KDBX_EPOCH_OFFSET).to_le_bytes())
+}
+
+// KeePass clients read TOTP from an otpauth URI, bare seeds are wrapped in one
+fn otp_uri(totp: &str, label: &str) -> String {
+    if totp.starts_with("otpauth://") {
+        return totp.to_string();
+    }
+
+    let mut uri = Url::parse("otpauth://totp/").expect("static otpauth URI is valid");
+
+    if let Ok(mut path_segments) = uri.path_segments_mut() {
+        path_segments.pop_if_empty().push(label);
+    }
+
+    uri.query_pairs_mut()
+        .append_pair("secret", &totp.replace(' ', "").to_uppercase());
+
+    uri.to_string()
+}
+
+// snake_case payload keys become readable field names
+fn payload_field_name(key: &str) -> String {
+    let name = key.replace('_', " ");
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Entry strings as (key, value, protected), standard strings first
+fn entry_strings(entry: &ArchiveEntry) -> Vec<(String, String, bool)> {
+    let mut strings: Vec<(String, String, bool)> = vec![];
+    let mut push = |key: &str, value: Option<&str>, protected: bool| {
+        let Some(value) = value.filter(|value| !value.is_empty()) else {
+            return;
+        };
+
+        // string keys are unique within an entry
+        let mut unique_key = key.to_string();
+        let mut suffix = 2;
+
+        while strings.iter().any(|(key, _, _)| *key == unique_key) {
+            unique_key = format!("{} ({})", key, suffix);
+            suffix += 1;
+        }
+
+        strings.push((unique_key, value.to_string(), protected));
+    };
+
+    let mut custom_fields: Vec<_> = entry.custom_fields.iter().collect();
+    let mut take_custom_field = |name: &str| {
+        custom_fields
+            .iter()
+            .position(|custom_field| custom_field.name == name)
+            .map(|index| custom_fields.remove(index))
+            .and_then(|custom_field| custom_field.value.clone())
+    };
+
+    let notes = take_custom_field("Notes");
+    let totp = take_custom_field("TOTP");
+
+    if entry.item_type == VaultItemType::Login {
+        let title = entry.app_name.as_deref().or(entry.website_url.as_deref());
+
+        push("Title", title, false);
+        push(
+            "UserName",
+            entry.username.as_deref().or(entry.email.as_deref()),
+            false,
+        );
+        push("Password", entry.password.as_deref(), true);
+        push("URL", entry.website_url.as_deref(), false);
+        push("Notes", notes.as_deref(), false);
+
+        if entry.username.is_some() {
+            push("Email", entry.email.as_deref(), false);
+        }
+
+        for (index, uri) in entry
+            .uris
+            .iter()
+            .filter(|uri| Some(uri.uri.as_str()) != entry.website_url.as_deref())
+            .enumerate()
+        {
+            let key = match index {
+                0 => EXTRA_URL_PREFIX.to_string(),
+                index => format!("{}_{}", EXTRA_URL_PREFIX, index),
+            };
+
+            push(&key, Some(&uri.uri), false);
+        }
+
+        if let Some(totp) = totp {
+            push("otp", Some(&otp_uri(&totp, title.unwrap_or("Vault"))), true);
+        }
+    } else {
+        let payload = entry
+            .payload
+            .as_deref()
+            .and_then(|payload| serde_json::from_str::<serde_json::Value>(payload).ok());
+        let data = payload
+            .as_ref()
+            .and_then(|payload| payload.get("data"))
+            .and_then(|data| data.as_object());
+
+        let payload_string = |key: &str| {
+            data.and_then(|data| data.get(key))
+                .and_then(|value| match value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(value) => Some(value.clone()),
+                    value => Some(value.to_string()),
+                })
+        };
+
+        push("Title", payload_string("title").as_deref(), false);
+        push("Notes", payload_string("note").or(notes).as_deref(), false);
+
+        for key in data.into_iter().flat_map(|data| data.keys()) {
+            if key == "title" || key == "note" {
+                continue;
+            }
+
+            push(
+                &payload_field_name(key),
+                payload_string(key).as_deref(),
+                PROTECTED_PAYLOAD_FIELDS.contains(&key.as_str()),
+            );
+        }
+    }
+
+    for custom_field in custom_fields {
+        push(
+            &custom_field.name,
+            custom_field.value.as_deref(),
+            custom_field.field_type == CustomFieldType::Hidden,
+        );
+    }
+
+    strings
+}
+
+/// Folders become groups below a root group, trashed entries go to the recycle bin
+pub fn kdbx_layout(
+    archive: &VaultArchive,
+    root_id: Uuid,
+    recycle_bin_id: Uuid,
+) -> Vec<KdbxNode<'_>> {
+    let mut placed_ids: HashSet<Uuid> = HashSet::new();
+    let mut subfolders: HashMap<Option<Uuid>, Vec<&ArchiveFolder>> = HashMap::new();
+
+    // folders in a parent cycle end up at the top level
+    for folder in folders_parents_first(&archive.folders) {
+        let parent_id = folder
+            .parent_id
+            .filter(|parent_id| placed_ids.contains(parent_id));
+
+        subfolders.entry(parent_id).or_default().push(folder);
+        placed_ids.insert(folder.id);
+    }
+
+    let mut entries: HashMap<Option<Uuid>, Vec<&ArchiveEntry>> = HashMap::new();
+    let mut trashed_entries = vec![];
+
+    for entry in &archive.entries {
+        if entry.trashed {
+            trashed_entries.push(entry);
+        } else {
+            let folder_id = entry
+                .folder_id
+                .filter(|folder_id| placed_ids.contains(folder_id));
+
+            entries.entry(folder_id).or_default().push(entry);
+        }
+    }
+
+    fn push_group<'a>(
+        nodes: &mut Vec<KdbxNode<'a>>,
+        folder_id: Option<Uuid>,
+        subfolders: &HashMap<Option<Uuid>, Vec<&'a ArchiveFolder>>,
+        entries: &HashMap<Option<Uuid>, Vec<&'a ArchiveEntry>>,
+    ) {
+        for entry in entries.get(&folder_id).into_iter().flatten() {
+            nodes.push(KdbxNode::Entry(entry));
+        }
+
+        for folder in subfolders.get(&folder_id).into_iter().flatten() {
+            nodes.push(KdbxNode::OpenGroup {
+                id: folder.id,
+                name: &folder.name,
+            });
+            push_group(nodes, Some(folder.id), subfolders, entries);
+            nodes.push(KdbxNode::CloseGroup);
+        }
+    }
+
+    let mut nodes = vec![KdbxNode::OpenGroup {
+        id: root_id,
+        name: "Vault",
+    }];
+    push_group(&mut nodes, None, &subfolders, &entries);
+
+    if !trashed_entries.is_empty() {
+        nodes.push(KdbxNode::OpenGroup {
+            id: recycle_bin_id,
+            name: "Recycle Bin",
+        });
+        nodes.extend(trashed_entries.into_iter().map(KdbxNode::Entry));
+        nodes.push(KdbxNode::CloseGroup);
+    }
+
+    nodes.push(KdbxNode::CloseGroup);
+    nodes
+}
+
+/// Writes a KeePass KDBX 4 database piece by piece, so it can be streamed
+///
+/// The payload is gzip compressed, encrypted with ChaCha20 and cut into HMAC protected
+/// blocks. Every write returns the blocks that became ready, which may be none.
+pub struct KdbxWriter {
+    hmac_base_key: Vec<u8>,
+    cipher: ChaCha20,
+    encoder: GzEncoder<Vec<u8>>,
+    block_index: u64,
+    protected_stream: ChaCha20,
+}
+
+impl KdbxWriter {
+    /// Derive the database key from its password, returning the writer and the outer header
+    pub fn new(password: &str, kdf: &ArchiveKdfParams) -> AppResult<(Self, Vec<u8>)> {
+        let master_seed: [u8; 32] = random_bytes();
+        let encryption_iv: [u8; 12] = random_bytes();
+        let stream_key: [u8; 64] = random_bytes();
+
+        let kdf_parameters = [
+            (VARIANT_BYTES, "$UUID", KDF_ARGON2ID.to_vec()),
+            (VARIANT_BYTES, "S", random_bytes::<32>().to_vec()),
+            (
+                VARIANT_UINT64,
+                "M",
+                (kdf.memory_kib as u64 * 1024).to_le_bytes().to_vec(),
+            ),
+            (
+                VARIANT_UINT64,
+                "I",
+                (kdf.iterations as u64).to_le_bytes().to_vec(),
+            ),
+            (VARIANT_UINT32, "P", kdf.parallelism.to_le_bytes().to_vec()),
+            (VARIANT_UINT32, "V", 0x13u32.to_le_bytes().to_vec()),
+        ];
+
+        let composite_key: [u8; 32] = Sha256::digest(Sha256::digest(password.as_bytes())).into();
+        let transformed_key = transform_key(
+            &composite_key,
+            &kdf_parameters
+                .iter()
+                .map(|(_, key, value)| (key.to_string(), value.clone()))
+                .collect(),
+        )?;
+
+        let mut header = vec![];
+        header.extend_from_slice(&SIGNATURE_1.to_le_bytes());
+        header.extend_from_slice(&SIGNATURE_2.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes());
+        header.extend_from_slice(&4u16.to_le_bytes());
+        push_field(&mut header, HEADER_CIPHER_ID, &CIPHER_CHACHA20);
+        push_field(&mut header, HEADER_COMPRESSION_FLAGS, &1u32.to_le_bytes());
+        push_field(&mut header, HEADER_MASTER_SEED, &master_seed);
+        push_field(&mut header, HEADER_ENCRYPTION_IV, &encryption_iv);
+        push_field(
+            &mut header,
+            HEADER_KDF_PARAMETERS,
+            &variant_dictionary(&kdf_parameters),
+        );
+        push_field(&mut header, HEADER_END, b"\r\n\r\n");
+
+        let mut hasher = Sha512::new();
+        hasher.update(master_seed);
+        hasher.update(transformed_key);
+        hasher.update([1u8]);
+        let hmac_base_key = hasher.finalize().to_vec();
+
+        let mut hasher = Sha256::new();
+        hasher.update(master_seed);
+        hasher.update(transformed_key);
+        let encryption_key = hasher.finalize();
+
+        let mut header_hmac = block_hmac(&hmac_key(u64::MAX, &hmac_base_key))?;
+        header_hmac.update(&header);
+
+        let header_hash = Sha256::digest(&header);
+        header.extend_from_slice(&header_hash);
+        header.extend_from_slice(&header_hmac.finalize().into_bytes());
+
+        let hashed_stream_key = Sha512::digest(stream_key);
+
+        let mut writer = Self {
+            hmac_base_key,
+            cipher: ChaCha20::new_from_slices(&encryption_key, &encryption_iv)
+                .map_err(|e| AppError::Crypto(e.to_string()))?,
+            encoder: GzEncoder::new(vec![], Compression::default()),
+            block_index: 0,
+            protected_stream: ChaCha20::new_from_slices(
+                &hashed_stream_key[..32],
+                &hashed_stream_key[32..44],
+            )
+            .map_err(|e| AppError::Crypto(e.to_string()))?,
+        };
+
+        let mut inner_header = vec![];
+        push_field(
+            &mut inner_header,
+            INNER_HEADER_STREAM_ID,
+            &INNER_STREAM_CHACHA20.to_le_bytes(),
+        );
+        push_field(&mut inner_header, INNER_HEADER_STREAM_KEY, &stream_key);
+
+        // a single small write never fills a block
+        writer.write(&inner_header)?;
+
+        Ok((writer, header))
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> AppResult<Vec<u8>> {
+        self.encoder
+            .write_all(data)
+            .map_err(|e| AppError::Internal(format!("Failed to compress database: {}", e)))?;
+
+        if self.encoder.get_ref().len() < KDBX_BLOCK_SIZE {
+            return Ok(vec![]);
+        }
+
+        let compressed = std::mem::take(self.encoder.get_mut());
+
+        self.seal_blocks(compressed)
+    }
+
+    /// Start an attachment in the inner header, its content follows in plain writes
+    pub fn start_binary(&mut self, size: u64) -> AppResult<Vec<u8>> {
+        let field_size = u32::try_from(size + 1).map_err(|_| {
+            AppError::Validation("Attachment is too large for a KeePass database".to_string())
+        })?;
+
+        let mut field = vec![INNER_HEADER_BINARY];
+        field.extend_from_slice(&field_size.to_le_bytes());
+        // flags, attachments are not kept protected in memory
+        field.push(0);
+
+        self.write(&field)
+    }
+
+    /// End the inner header and start the XML document
+    pub fn start_document(&mut self, recycle_bin_id: Uuid) -> AppResult<Vec<u8>> {
+        let mut data = vec![];
+        push_field(&mut data, INNER_HEADER_END, &[]);
+
+        data.extend_from_slice(
+            format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\" standalone=\"yes\"?>\n\
+                 <KeePassFile><Meta><Generator>Vault</Generator>\
+                 <DatabaseName>Vault</DatabaseName>\
+                 <RecycleBinEnabled>True</RecycleBinEnabled>\
+                 <RecycleBinUUID>{}</RecycleBinUUID></Meta><Root>",
+                kdbx_uuid(recycle_bin_id)
+            )
+            .as_bytes(),
+        );
+
+        self.write(&data)
+    }
+
+    pub fn write_node(
+        &mut self,
+        node: &KdbxNode,
+        references: &KdbxReferences,
+    ) -> AppResult<Vec<u8>> {
+        let xml = match node {
+            KdbxNode::OpenGroup { id, name } => format!(
+                "<Group><UUID>{}</UUID><Name>{}</Name><IsExpanded>True</IsExpanded>",
+                kdbx_uuid(*id),
+                xml_escape(name)
+            ),
+            KdbxNode::Entry(entry) => self.entry_xml(entry, references),
+            KdbxNode::CloseGroup => "</Group>".to_string(),
+        };
+
+        self.write(xml.as_bytes())
+    }
+
+    /// Close the XML document and seal everything that is left
+    pub fn finish(mut self) -> AppResult<Vec<u8>> {
+        self.encoder
+            .write_all(b"</Root></KeePassFile>")
+            .and_then(|_| self.encoder.try_finish())
+            .map_err(|e| AppError::Internal(format!("Failed to compress database: {}", e)))?;
+
+        let compressed = std::mem::take(self.encoder.get_mut());
+
+        let mut output = self.seal_blocks(compressed)?;
+        // an empty block marks the end of the payload
+        output.extend(self.hmac_block(&[])?);
+
+        Ok(output)
+    }
+
+    // protected values share one key stream, in the order they appear in the document
+    fn protect(&mut self, value: &str) -> String {
+        let mut value = value.as_bytes().to_vec();
+        self.protected_stream.apply_keystream(&mut value);
+
+        general_purpose::STANDARD.encode(value)
+    }
+
+    // the vault keeps no earlier versions of an entry, so entries have no <History>
+    fn entry_xml(&mut self, entry: &ArchiveEntry, references: &KdbxReferences) -> String {
+        let tags: Vec<&str> = entry
+            .tag_ids
+            .iter()
+            .filter_map(|tag_id| references.tag_names.get(tag_id))
+            .map(String::as_str)
+            .collect();
+
+        let mut xml = format!(
+            "<Entry><UUID>{}</UUID><Tags>{}</Tags><Times>\
+             <CreationTime>{}</CreationTime>\
+             <LastModificationTime>{}</LastModificationTime>\
+             <LastAccessTime>{}</LastAccessTime>\
+             <Expires>False</Expires></Times>",
+            kdbx_uuid(entry.id),
+            xml_escape(&tags.join(";")),
+            kdbx_time(entry.created_at),
+            kdbx_time(entry.updated_at),
+            kdbx_time(entry.updated_at),
+        );
+
+        for (key, value, protected) in entry_strings(entry) {
+            let value = if protected {
+                format!("<Value Protected=\"True\">{}</Value>", self.protect(&value))
+            } else {
+                format!("<Value>{}</Value>", xml_escape(&value))
+            };
+
+            xml.push_str(&format!(
+                "<String><Key>{}</Key>{}</String>",
+                xml_escape(&key),
+                value
+            ));
+        }
+
+        let mut used_names: HashSet<String> = HashSet::new();
+
+        for (file_name, index) in references.binaries.get(&entry.id).into_iter().flatten() {
+            // binary keys are unique within an entry
+            let mut unique_name = file_name.to_string();
+            let mut suffix = 2;
+
+            while !used_names.insert(unique_name.clone()) {
+                unique_name = format!("{} ({})", file_name, suffix);
+                suffix += 1;
+            }
+
+            xml.push_str(&format!(
+                "<Binary><Key>{}</Key><Value Ref=\"{}\"/></Binary>",
+                xml_escape(&unique_name),
+                index
+            ));
+        }
+
+        xml.push_str("</Entry>");
+        xml
+    }
+
+    fn seal_blocks(&mut self, mut data: Vec<u8>) -> AppResult<Vec<u8>> {
+        self.cipher.apply_keystream(&mut data);
+
+        let mut output = Vec::with_capacity(data.len() + 36);
+
+        for block in data.chunks(KDBX_BLOCK_SIZE) {
+            output.extend(self.hmac_block(block)?);
+        }
+
+        Ok(output)
+    }
+
+    fn hmac_block(&mut self, block: &[u8]) -> AppResult<Vec<u8>> {
+        let size = block.len() as u32;
+
+        let mut hmac = block_hmac(&hmac_key(self.block_index, &self.hmac_base_key))?;
+        hmac.update(&self.block_index.to_le_bytes());
+        hmac.update(&size.to_le_bytes());
+        hmac.update(block);
+
+        let mut output = hmac.finalize().into_bytes().to_vec();
+        output.extend_from_slice(&size.to_le_bytes());
+        output.extend_from_slice(block);
+
+        self.block_index += 1;
+
+        Ok(output)
+    }
+}
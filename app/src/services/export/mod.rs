@@ -0,0 +1,9 @@
+pub mod archive;
+#[allow(clippy::module_inception)]
+pub mod export;
+mod export_test;
+pub mod kdbx;
+
+pub use archive::*;
+pub use export::*;
+pub use kdbx::*;
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use async_graphql::Context;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    TransactionError, TransactionTrait, sea_query::Expr,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        event_dtos::VaultChangeType,
+        folder,
+        folder_dtos::{
+            CreateFolderRequest, DeleteFolderRequest, FolderDeleteMode, FolderResponse,
+            FoldersResponse, UpdateFolderRequest,
+        },
+        password,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        crypto::{decrypt_password, encrypt_password},
+        events::publish_vault_changed,
+        password::next_revision,
+    },
+    utils::error::{AppError, AppResult},
+};
+
+pub fn to_folder_response(folder: &folder::Model, dek: &[u8; 32]) -> AppResult<FolderResponse> {
+    Ok(FolderResponse {
+        id: folder.id,
+        parent_id: folder.parent_id,
+        name: decrypt_password(&folder.encrypted_name, dek)?,
+        created_at: folder.created_at,
+        updated_at: folder.updated_at,
+    })
+}
+
+/// Ids of a folder and every folder nested below it
+pub fn folder_subtree(folders: &[folder::Model], folder_id: Uuid) -> Vec<Uuid> {
+    let mut subtree = vec![folder_id];
+    let mut index = 0;
+
+    while index < subtree.len() {
+        let parent_id = subtree[index];
+
+        for folder in folders {
+            if folder.parent_id == Some(parent_id) && !subtree.contains(&folder.id) {
+                subtree.push(folder.id);
+            }
+        }
+
+        index += 1;
+    }
+
+    subtree
+}
+
+pub async fn find_folder(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    id: Uuid,
+) -> AppResult<folder::Model> {
+    folder::Entity::find()
+        .filter(folder::Column::UserId.eq(user_id))
+        .filter(folder::Column::Id.eq(id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find folder: {}", e)))?
+        .ok_or(AppError::NotFound("Folder not found".to_string()))
+}
+
+async fn find_folders(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<Vec<folder::Model>> {
+    folder::Entity::find()
+        .filter(folder::Column::UserId.eq(user_id))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get folders: {}", e)))
+}
+
+pub async fn create_folder(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: CreateFolderRequest,
+) -> AppResult<GraphqlResponse<FolderResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    if let Some(parent_id) = request.parent_id {
+        find_folder(database_connection, user_id, parent_id).await?;
+    }
+
+    let folder = folder::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        parent_id: Set(request.parent_id),
+        encrypted_name: Set(encrypt_password(request.name.trim(), &dek_u8_32)?),
+        ..Default::default()
+    }
+    .insert(database_connection.as_ref())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to save folder: {}", e)))?;
+
+    Ok(GraphqlResponse::<FolderResponse> {
+        success: true,
+        message: "Folder created successfully".to_string(),
+        data: to_folder_response(&folder, &dek_u8_32)?,
+    })
+}
+
+pub async fn update_folder(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UpdateFolderRequest,
+) -> AppResult<GraphqlResponse<FolderResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let folders = find_folders(database_connection, user_id).await?;
+
+    let folder = folders
+        .iter()
+        .find(|folder| folder.id == request.id)
+        .cloned()
+        .ok_or(AppError::NotFound("Folder not found".to_string()))?;
+
+    if let Some(parent_id) = request.parent_id {
+        if !folders.iter().any(|folder| folder.id == parent_id) {
+            return Err(AppError::NotFound("Parent folder not found".to_string()));
+        }
+
+        if folder_subtree(&folders, folder.id).contains(&parent_id) {
+            return Err(AppError::Validation(
+                "A folder cannot be moved into itself or one of its subfolders".to_string(),
+            ));
+        }
+    }
+
+    let mut updated_folder: folder::ActiveModel = folder.into();
+    updated_folder.encrypted_name = Set(encrypt_password(request.name.trim(), &dek_u8_32)?);
+    updated_folder.parent_id = Set(request.parent_id);
+    updated_folder.updated_at = Set(Utc::now());
+
+    let folder = updated_folder
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update folder: {}", e)))?;
+
+    Ok(GraphqlResponse::<FolderResponse> {
+        success: true,
+        message: "Folder updated successfully".to_string(),
+        data: to_folder_response(&folder, &dek_u8_32)?,
+    })
+}
+
+pub async fn delete_folder(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeleteFolderRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let folders = find_folders(database_connection, user_id).await?;
+
+    let folder = folders
+        .iter()
+        .find(|folder| folder.id == request.id)
+        .cloned()
+        .ok_or(AppError::NotFound("Folder not found".to_string()))?;
+
+    let subtree = folder_subtree(&folders, folder.id);
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let revision = next_revision(txn, user_id).await?;
+
+                match request.mode {
+                    FolderDeleteMode::MoveToParent => {
+                        folder::Entity::update_many()
+                            .col_expr(folder::Column::ParentId, Expr::value(folder.parent_id))
+                            .filter(folder::Column::UserId.eq(user_id))
+                            .filter(folder::Column::ParentId.eq(folder.id))
+                            .exec(txn)
+                            .await?;
+
+                        password::Entity::update_many()
+                            .col_expr(password::Column::FolderId, Expr::value(folder.parent_id))
+                            .col_expr(password::Column::Revision, Expr::value(revision))
+                            .filter(password::Column::UserId.eq(user_id))
+                            .filter(password::Column::FolderId.eq(folder.id))
+                            .exec(txn)
+                            .await?;
+                    }
+                    FolderDeleteMode::Trash => {
+                        password::Entity::update_many()
+                            .col_expr(password::Column::IsDeleted, Expr::value(true))
+                            .col_expr(password::Column::FolderId, Expr::value(None::<Uuid>))
+                            .col_expr(password::Column::Revision, Expr::value(revision))
+                            .col_expr(password::Column::UpdatedAt, Expr::value(Utc::now()))
+                            .filter(password::Column::UserId.eq(user_id))
+                            .filter(password::Column::FolderId.is_in(subtree.clone()))
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                folder::Entity::delete_many()
+                    .filter(folder::Column::UserId.eq(user_id))
+                    .filter(folder::Column::Id.is_in(subtree))
+                    .exec(txn)
+                    .await?;
+
+                Ok(revision)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to delete folder: {}", e))
+        })?;
+
+    publish_vault_changed(app_state, user_id, None, VaultChangeType::Bulk, revision);
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Folder deleted successfully".to_string(),
+    })
+}
+
+pub async fn get_folders(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<FoldersResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let folders = find_folders(database_connection, user_id)
+        .await?
+        .iter()
+        .map(|folder| to_folder_response(folder, &dek_u8_32))
+        .collect::<AppResult<Vec<FolderResponse>>>()?;
+
+    Ok(GraphqlResponse::<FoldersResponse> {
+        success: true,
+        message: if folders.is_empty() {
+            "No folders found".to_string()
+        } else {
+            "Folders found".to_string()
+        },
+        data: FoldersResponse { folders },
+    })
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        models::folder,
+        services::folder::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    fn folder(id: Uuid, parent_id: Option<Uuid>) -> folder::Model {
+        folder::Model {
+            id,
+            user_id: Uuid::nil(),
+            parent_id,
+            encrypted_name: String::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_folder_subtree() -> AppResult<()> {
+        let (work, projects, archive, personal) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+
+        let folders = [
+            folder(archive, Some(projects)),
+            folder(projects, Some(work)),
+            folder(work, None),
+            folder(personal, None),
+        ];
+
+        let subtree = folder_subtree(&folders, work);
+
+        if subtree.len() != 3 || !subtree.contains(&projects) || !subtree.contains(&archive) {
+            return Err(AppError::Internal(format!(
+                "Unexpected subtree of work: {:?}",
+                subtree
+            )));
+        }
+
+        if folder_subtree(&folders, personal) != vec![personal] {
+            return Err(AppError::Internal(
+                "Personal folder should have no subfolders".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
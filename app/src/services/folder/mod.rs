@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod folder;
+mod folder_test;
+
+pub use folder::*;
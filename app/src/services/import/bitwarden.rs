@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose};
+use cbc::cipher::{BlockDecryptMut, KeyIvInit, block_padding::Pkcs7};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    models::{custom_field::CustomFieldType, custom_field_dtos::CustomFieldInput},
+    services::import::{ImportedEntry, ParsedImport, non_empty},
+    utils::error::{AppError, AppResult},
+};
+
+const LOGIN_ITEM_TYPE: u32 = 1;
+const KDF_PBKDF2: u32 = 0;
+const KDF_ARGON2ID: u32 = 1;
+
+// the export decides how hard its key is to derive, these keep it bounded
+const MAX_PBKDF2_ITERATIONS: u32 = 2_000_000;
+const MAX_ARGON2_MEMORY_MIB: u32 = 1024;
+const MAX_ARGON2_ITERATIONS: u32 = 10;
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenExport {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    password_protected: bool,
+    salt: Option<String>,
+    kdf_type: Option<u32>,
+    kdf_iterations: Option<u32>,
+    kdf_memory: Option<u32>,
+    kdf_parallelism: Option<u32>,
+    #[serde(rename = "encKeyValidation_DO_NOT_EDIT")]
+    enc_key_validation: Option<String>,
+    data: Option<String>,
+    folders: Option<Vec<BitwardenFolder>>,
+    items: Option<Vec<BitwardenItem>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenFolder {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenItem {
+    #[serde(rename = "type")]
+    item_type: u32,
+    name: Option<String>,
+    notes: Option<String>,
+    #[serde(default)]
+    favorite: bool,
+    folder_id: Option<String>,
+    login: Option<BitwardenLogin>,
+    fields: Option<Vec<BitwardenField>>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenLogin {
+    uris: Option<Vec<BitwardenUri>>,
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenUri {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenField {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type")]
+    field_type: u32,
+}
+
+/// Derive the encryption and MAC keys of a password protected export
+fn export_keys(password: &str, export: &BitwardenExport) -> AppResult<([u8; 32], [u8; 32])> {
+    let salt = export
+        .salt
+        .as_ref()
+        .ok_or(AppError::Validation("Export salt is missing".to_string()))?;
+    let iterations = export.kdf_iterations.ok_or(AppError::Validation(
+        "Export KDF iterations are missing".to_string(),
+    ))?;
+
+    let mut key = [0u8; 32];
+
+    match export.kdf_type.unwrap_or(KDF_PBKDF2) {
+        KDF_PBKDF2 => {
+            if iterations > MAX_PBKDF2_ITERATIONS {
+                return Err(AppError::Validation(
+                    "Export KDF iterations are too high".to_string(),
+                ));
+            }
+
+            pbkdf2::pbkdf2_hmac::<Sha256>(
+                password.as_bytes(),
+                salt.as_bytes(),
+                iterations,
+                &mut key,
+            );
+        }
+        KDF_ARGON2ID => {
+            let memory = export.kdf_memory.unwrap_or(64);
+            let parallelism = export.kdf_parallelism.unwrap_or(4);
+
+            if memory > MAX_ARGON2_MEMORY_MIB
+                || iterations > MAX_ARGON2_ITERATIONS
+                || parallelism > MAX_ARGON2_PARALLELISM
+            {
+                return Err(AppError::Validation(
+                    "Export KDF parameters are too high".to_string(),
+                ));
+            }
+
+            let params = Params::new(memory * 1024, iterations, parallelism, Some(32))
+                .map_err(|e| AppError::Validation(format!("Invalid export KDF: {}", e)))?;
+
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(password.as_bytes(), &Sha256::digest(salt), &mut key)
+                .map_err(|e| AppError::Crypto(e.to_string()))?;
+        }
+        _ => {
+            return Err(AppError::Validation("Unsupported export KDF".to_string()));
+        }
+    }
+
+    // the derived key is stretched into separate keys for encryption and authentication
+    let hkdf = Hkdf::<Sha256>::from_prk(&key).map_err(|e| AppError::Crypto(e.to_string()))?;
+    let mut enc_key = [0u8; 32];
+    let mut mac_key = [0u8; 32];
+    hkdf.expand(b"enc", &mut enc_key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+    hkdf.expand(b"mac", &mut mac_key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    Ok((enc_key, mac_key))
+}
+
+/// Decrypt a type 2 (AES-256-CBC with HMAC-SHA256) encrypted string
+fn decrypt_enc_string(
+    enc_string: &str,
+    enc_key: &[u8; 32],
+    mac_key: &[u8; 32],
+) -> AppResult<Vec<u8>> {
+    let parts: Vec<Vec<u8>> = enc_string
+        .strip_prefix("2.")
+        .ok_or(AppError::Validation(
+            "Unsupported export encryption".to_string(),
+        ))?
+        .split('|')
+        .map(|part| general_purpose::STANDARD.decode(part))
+        .collect::<Result<_, _>>()
+        .map_err(|_| AppError::Validation("Invalid export encryption".to_string()))?;
+
+    let [iv, cipher_text, mac] = parts.as_slice() else {
+        return Err(AppError::Validation(
+            "Invalid export encryption".to_string(),
+        ));
+    };
+
+    let mut hmac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+    hmac.update(iv);
+    hmac.update(cipher_text);
+    hmac.verify_slice(mac)
+        .map_err(|_| AppError::Validation("Invalid export password".to_string()))?;
+
+    cbc::Decryptor::<Aes256>::new_from_slices(enc_key, iv)
+        .map_err(|_| AppError::Validation("Invalid export encryption".to_string()))?
+        .decrypt_padded_vec_mut::<Pkcs7>(cipher_text)
+        .map_err(|_| AppError::Validation("Invalid export encryption".to_string()))
+}
+
+fn to_custom_field(field: BitwardenField) -> Option<CustomFieldInput> {
+    let name = non_empty(field.name.as_deref())?;
+
+    let field_type = match field.field_type {
+        1 => CustomFieldType::Hidden,
+        2 if matches!(field.value.as_deref(), Some("true" | "false")) => CustomFieldType::Boolean,
+        // linked fields point at another field of the item and carry no value
+        3 => return None,
+        _ => CustomFieldType::Text,
+    };
+
+    Some(CustomFieldInput {
+        name,
+        field_type,
+        value: field.value,
+    })
+}
+
+/// Bitwarden JSON, either plain or protected with an export password
+pub fn parse_bitwarden_json(data: &[u8], password: Option<&str>) -> AppResult<ParsedImport> {
+    let mut export: BitwardenExport = serde_json::from_slice(data)
+        .map_err(|e| AppError::Validation(format!("Invalid Bitwarden export: {}", e)))?;
+
+    if export.encrypted {
+        if !export.password_protected {
+            return Err(AppError::Validation(
+                "Exports encrypted with the Bitwarden account key cannot be imported".to_string(),
+            ));
+        }
+
+        let password = password.ok_or(AppError::Validation(
+            "Export password is required".to_string(),
+        ))?;
+        let (enc_key, mac_key) = export_keys(password, &export)?;
+
+        if let Some(enc_key_validation) = &export.enc_key_validation {
+            decrypt_enc_string(enc_key_validation, &enc_key, &mac_key)?;
+        }
+
+        let encrypted_data = export
+            .data
+            .as_ref()
+            .ok_or(AppError::Validation("Export data is missing".to_string()))?;
+
+        export = serde_json::from_slice(&decrypt_enc_string(encrypted_data, &enc_key, &mac_key)?)
+            .map_err(|e| AppError::Validation(format!("Invalid Bitwarden export: {}", e)))?;
+    }
+
+    let folders: HashMap<String, String> = export
+        .folders
+        .unwrap_or_default()
+        .into_iter()
+        .map(|folder| (folder.id, folder.name))
+        .collect();
+
+    let mut parsed_import = ParsedImport::default();
+
+    for item in export.items.unwrap_or_default() {
+        let (LOGIN_ITEM_TYPE, Some(login)) = (item.item_type, item.login) else {
+            parsed_import.unsupported += 1;
+            continue;
+        };
+
+        parsed_import.entries.push(ImportedEntry {
+            name: non_empty(item.name.as_deref()),
+            uris: login
+                .uris
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|uri| non_empty(uri.uri.as_deref()))
+                .collect(),
+            username: non_empty(login.username.as_deref()),
+            password: non_empty(login.password.as_deref()),
+            notes: non_empty(item.notes.as_deref()),
+            totp: non_empty(login.totp.as_deref()),
+            // nested folders are exported with their full path as the name
+            folder: item
+                .folder_id
+                .and_then(|folder_id| folders.get(&folder_id))
+                .map(|name| {
+                    name.split('/')
+                        .filter_map(|name| non_empty(Some(name)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            favorite: item.favorite,
+            custom_fields: item
+                .fields
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(to_custom_field)
+                .collect(),
+        });
+    }
+
+    Ok(parsed_import)
+}
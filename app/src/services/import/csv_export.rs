@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::{
+    services::import::{ImportedEntry, ParsedImport, non_empty},
+    utils::error::{AppError, AppResult},
+};
+
+/// Rows of a CSV export, keyed by their lowercased header names
+fn read_rows(data: &[u8], required_headers: &[&str]) -> AppResult<Vec<HashMap<String, String>>> {
+    let mut reader = ReaderBuilder::new().flexible(true).from_reader(data);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::Validation(format!("Invalid CSV export: {}", e)))?
+        .iter()
+        .map(|header| header.trim().trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+
+    if !required_headers
+        .iter()
+        .all(|required_header| headers.iter().any(|header| header == required_header))
+    {
+        return Err(AppError::Validation(format!(
+            "CSV export needs the columns {}",
+            required_headers.join(", ")
+        )));
+    }
+
+    reader
+        .records()
+        .map(|record| {
+            let record: StringRecord =
+                record.map_err(|e| AppError::Validation(format!("Invalid CSV export: {}", e)))?;
+
+            Ok(headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(|value| value.to_string()))
+                .collect())
+        })
+        .collect()
+}
+
+fn field<'a>(row: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    row.get(name).map(|value| value.as_str())
+}
+
+/// LastPass CSV, secure notes are exported with the url `http://sn`
+pub fn parse_lastpass_csv(data: &[u8]) -> AppResult<ParsedImport> {
+    let rows = read_rows(data, &["url", "username", "password", "name"])?;
+
+    let mut parsed_import = ParsedImport::default();
+
+    for row in rows {
+        let url = non_empty(field(&row, "url"));
+
+        if url.as_deref() == Some("http://sn") {
+            parsed_import.unsupported += 1;
+            continue;
+        }
+
+        parsed_import.entries.push(ImportedEntry {
+            name: non_empty(field(&row, "name")),
+            uris: url.into_iter().collect(),
+            username: non_empty(field(&row, "username")),
+            password: non_empty(field(&row, "password")),
+            notes: non_empty(field(&row, "extra")),
+            totp: non_empty(field(&row, "totp")),
+            folder: field(&row, "grouping")
+                .map(|grouping| {
+                    grouping
+                        .split('\\')
+                        .filter_map(|name| non_empty(Some(name)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            favorite: field(&row, "fav") == Some("1"),
+            custom_fields: vec![],
+        });
+    }
+
+    Ok(parsed_import)
+}
+
+/// Chrome and Firefox CSV, Firefox leaves out the name and notes columns
+pub fn parse_browser_csv(data: &[u8]) -> AppResult<ParsedImport> {
+    let rows = read_rows(data, &["url", "username", "password"])?;
+
+    Ok(ParsedImport {
+        entries: rows
+            .iter()
+            .map(|row| ImportedEntry {
+                name: non_empty(field(row, "name")),
+                uris: non_empty(field(row, "url")).into_iter().collect(),
+                username: non_empty(field(row, "username")),
+                password: non_empty(field(row, "password")),
+                notes: non_empty(field(row, "note").or(field(row, "notes"))),
+                ..Default::default()
+            })
+            .collect(),
+        unsupported: 0,
+    })
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    sync::Arc,
+};
+
+use async_graphql::Context;
+use sea_orm::{
+    ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set, TransactionError,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{app_state::AppState, response::GraphqlResponse},
+    models::{
+        custom_field::{self, CustomFieldType},
+        custom_field_dtos::CustomFieldInput,
+        event_dtos::VaultChangeType,
+        folder,
+        import_dtos::{
+            ImportDuplicateMode, ImportFormat, ImportPreviewEntry, ImportVaultRequest,
+            ImportVaultResponse,
+        },
+        password::{self, VaultItemType},
+        password_dtos::PasswordUriInput,
+        password_uri,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        crypto::{blind_index, decrypt_password, encrypt_password},
+        custom_field::build_custom_fields,
+        events::publish_vault_changed,
+        import::{
+            parse_bitwarden_json, parse_browser_csv, parse_keepass_kdbx, parse_lastpass_csv,
+            parse_one_password_1pux,
+        },
+        password::{
+            account_identity, build_password_uris, decrypted_account_identity, next_revision,
+            site_key,
+        },
+        share::entry_key,
+        uri::{normalize_url, validate_uri},
+    },
+    utils::error::{AppError, AppResult},
+    validators::custom_field::validate_custom_fields,
+};
+
+// keeps every insert below the Postgres bind parameter limit
+pub const INSERT_BATCH_SIZE: usize = 500;
+
+/// A login read from an export, before it is mapped onto the password model
+#[derive(Clone, Debug, Default)]
+pub struct ImportedEntry {
+    pub name: Option<String>,
+    pub uris: Vec<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub notes: Option<String>,
+    pub totp: Option<String>,
+    /// Folder path from the top level down
+    pub folder: Vec<String>,
+    pub favorite: bool,
+    pub custom_fields: Vec<CustomFieldInput>,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedImport {
+    pub entries: Vec<ImportedEntry>,
+    /// Items that are not logins, like cards or secure notes
+    pub unsupported: u32,
+}
+
+pub fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Parse an export file of the given format
+pub fn parse_export(
+    format: ImportFormat,
+    data: &[u8],
+    password: Option<&str>,
+) -> AppResult<ParsedImport> {
+    match format {
+        ImportFormat::BitwardenJson => parse_bitwarden_json(data, password),
+        ImportFormat::OnePasswordPux => parse_one_password_1pux(data),
+        ImportFormat::LastpassCsv => parse_lastpass_csv(data),
+        ImportFormat::KeepassKdbx => parse_keepass_kdbx(
+            data,
+            password.ok_or(AppError::Validation(
+                "Database password is required".to_string(),
+            ))?,
+        ),
+        ImportFormat::BrowserCsv => parse_browser_csv(data),
+    }
+}
+
+/// Custom fields of an entry, with its notes and TOTP secret appended
+fn entry_custom_fields(entry: &ImportedEntry) -> Vec<CustomFieldInput> {
+    let mut custom_fields: Vec<CustomFieldInput> = entry
+        .custom_fields
+        .iter()
+        .cloned()
+        .map(|mut custom_field| {
+            // values that do not fit their type are kept as text
+            if validate_custom_fields(std::slice::from_ref(&custom_field)).is_err() {
+                custom_field.field_type = CustomFieldType::Text;
+            }
+            custom_field
+        })
+        .collect();
+
+    if let Some(notes) = &entry.notes {
+        custom_fields.push(CustomFieldInput {
+            name: "Notes".to_string(),
+            field_type: CustomFieldType::Text,
+            value: Some(notes.clone()),
+        });
+    }
+
+    if let Some(totp) = &entry.totp {
+        custom_fields.push(CustomFieldInput {
+            name: "TOTP".to_string(),
+            field_type: CustomFieldType::Hidden,
+            value: Some(totp.clone()),
+        });
+    }
+
+    custom_fields
+}
+
+/// Folders of the user by parent and lowercased name, used to reuse existing folders
+async fn folder_ids_by_path(
+    database_connection: &sea_orm::DatabaseConnection,
+    user_id: Uuid,
+    dek: &[u8; 32],
+) -> AppResult<HashMap<(Option<Uuid>, String), Uuid>> {
+    let folders = folder::Entity::find()
+        .filter(folder::Column::UserId.eq(user_id))
+        .order_by_asc(folder::Column::CreatedAt)
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get folders: {}", e)))?;
+
+    let mut folder_ids = HashMap::new();
+
+    for folder in folders {
+        let name = decrypt_password(&folder.encrypted_name, dek)?.to_lowercase();
+        folder_ids
+            .entry((folder.parent_id, name))
+            .or_insert(folder.id);
+    }
+
+    Ok(folder_ids)
+}
+
+pub async fn import_vault(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ImportVaultRequest,
+) -> AppResult<GraphqlResponse<ImportVaultResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let env_variables = &app_state.env_variables;
+    let user_id = user_redis_session.id;
+    let dry_run = request.dry_run.unwrap_or(false);
+    let duplicate_mode = request.duplicates.unwrap_or_default();
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let upload = request
+        .file
+        .value(ctx)
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    let size = upload
+        .size()
+        .map_err(|e| AppError::Validation(format!("Invalid upload: {}", e)))?;
+
+    if size > env_variables.import_max_bytes {
+        return Err(AppError::Validation(format!(
+            "Import file is larger than {} bytes",
+            env_variables.import_max_bytes
+        )));
+    }
+
+    // reading the spooled upload and deriving export keys both block
+    let format = request.format;
+    let password = request.password;
+    let parsed_import = tokio::task::spawn_blocking(move || {
+        let mut data = vec![];
+        upload
+            .into_read()
+            .read_to_end(&mut data)
+            .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))?;
+
+        parse_export(format, &data, password.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read upload: {}", e)))??;
+
+    let existing_passwords = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::CollectionId.is_null())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(false))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    // entries count as duplicates on the same site and account as find_duplicates
+    let mut duplicate_keys: HashSet<(String, Option<String>)> = HashSet::new();
+
+    for password_entry in &existing_passwords {
+        if let Some(site) = site_key(password_entry) {
+            duplicate_keys.insert((
+                site,
+                decrypted_account_identity(
+                    password_entry,
+                    &entry_key(password_entry, &dek_u8_32)?,
+                )?,
+            ));
+        }
+    }
+
+    let mut folder_ids = folder_ids_by_path(database_connection, user_id, &dek_u8_32).await?;
+
+    let mut folder_models: Vec<folder::ActiveModel> = vec![];
+    let mut password_models: Vec<password::ActiveModel> = vec![];
+    let mut password_uris: Vec<password_uri::ActiveModel> = vec![];
+    let mut custom_fields: Vec<custom_field::ActiveModel> = vec![];
+
+    let mut response = ImportVaultResponse {
+        dry_run,
+        unsupported: parsed_import.unsupported as i32,
+        ..Default::default()
+    };
+
+    for entry in parsed_import.entries {
+        // the first uri that normalizes becomes the website of the entry
+        let normalized_url = entry
+            .uris
+            .iter()
+            .find_map(|uri| normalize_url(uri).ok().map(|normalized| (uri, normalized)));
+        let website_url = normalized_url.as_ref().map(|(uri, _)| uri.to_string());
+
+        let (username, email) = match entry.username.clone() {
+            Some(account) if account.contains('@') => (None, Some(account)),
+            username => (username, None),
+        };
+        let account = account_identity(username.as_deref(), email.as_deref());
+
+        let mut preview_entry = ImportPreviewEntry {
+            name: entry.name.clone(),
+            website_url: website_url.clone(),
+            account: account.clone(),
+            folder: (!entry.folder.is_empty()).then(|| entry.folder.join("/")),
+            duplicate: false,
+            imported: false,
+        };
+
+        let site = match &normalized_url {
+            Some((_, normalized_url)) => normalized_url.host.clone(),
+            None => match &entry.name {
+                Some(name) => name.to_lowercase(),
+                None => {
+                    response.skipped += 1;
+                    response.entries.push(preview_entry);
+                    continue;
+                }
+            },
+        };
+
+        preview_entry.duplicate = !duplicate_keys.insert((site, account.clone()));
+
+        if preview_entry.duplicate {
+            response.duplicates += 1;
+
+            if duplicate_mode == ImportDuplicateMode::Skip {
+                response.entries.push(preview_entry);
+                continue;
+            }
+        }
+
+        preview_entry.imported = true;
+        response.imported += 1;
+        response.entries.push(preview_entry);
+
+        if dry_run {
+            continue;
+        }
+
+        let mut folder_id = None;
+
+        for name in &entry.folder {
+            let folder_key = (folder_id, name.to_lowercase());
+
+            folder_id = match folder_ids.get(&folder_key) {
+                Some(id) => Some(*id),
+                None => {
+                    let id = Uuid::new_v4();
+                    folder_models.push(folder::ActiveModel {
+                        id: Set(id),
+                        user_id: Set(user_id),
+                        parent_id: Set(folder_id),
+                        encrypted_name: Set(encrypt_password(name, &dek_u8_32)?),
+                        ..Default::default()
+                    });
+                    folder_ids.insert(folder_key, id);
+                    Some(id)
+                }
+            };
+        }
+
+        let password_id = Uuid::new_v4();
+
+        let uris = entry
+            .uris
+            .iter()
+            .filter(|uri| validate_uri(uri, &Default::default()).is_ok())
+            .map(|uri| PasswordUriInput {
+                uri: uri.to_string(),
+                match_type: None,
+            })
+            .collect();
+        password_uris.extend(build_password_uris(password_id, user_id, uris));
+        custom_fields.extend(build_custom_fields(
+            password_id,
+            user_id,
+            entry_custom_fields(&entry),
+            &dek_u8_32,
+        )?);
+
+        let (canonical_website_url, website_host) = normalized_url
+            .map(|(_, normalized_url)| (normalized_url.canonical, normalized_url.host))
+            .unzip();
+
+        password_models.push(password::ActiveModel {
+            id: Set(password_id),
+            website_url: Set(website_url),
+            canonical_website_url: Set(canonical_website_url),
+            website_host: Set(website_host),
+            app_name: Set(entry.name),
+            encrypted_email: Set(email
+                .as_ref()
+                .map(|e| encrypt_password(e, &dek_u8_32))
+                .transpose()?),
+            encrypted_username: Set(username
+                .as_ref()
+                .map(|u| encrypt_password(u, &dek_u8_32))
+                .transpose()?),
+            encrypted_password: Set(Some(encrypt_password(
+                entry.password.as_deref().unwrap_or_default(),
+                &dek_u8_32,
+            )?)),
+            account_blind_index: Set(account
+                .as_deref()
+                .map(|account| blind_index(account, &dek_u8_32))
+                .transpose()?),
+            folder_id: Set(folder_id),
+            favorite: Set(entry.favorite),
+            user_id: Set(user_id),
+            ..Default::default()
+        });
+    }
+
+    if !password_models.is_empty() {
+        let revision = database_connection
+            .transaction(move |txn| {
+                Box::pin(async move {
+                    for folder_model in folder_models {
+                        folder::Entity::insert(folder_model).exec(txn).await?;
+                    }
+
+                    let revision = next_revision(txn, user_id).await?;
+
+                    for password_model in password_models.iter_mut() {
+                        password_model.revision = Set(revision);
+                        password_model.created_revision = Set(revision);
+                    }
+
+                    for batch in password_models.chunks(INSERT_BATCH_SIZE) {
+                        password::Entity::insert_many(batch.to_vec())
+                            .exec(txn)
+                            .await?;
+                    }
+
+                    for batch in password_uris.chunks(INSERT_BATCH_SIZE) {
+                        password_uri::Entity::insert_many(batch.to_vec())
+                            .exec(txn)
+                            .await?;
+                    }
+
+                    for batch in custom_fields.chunks(INSERT_BATCH_SIZE) {
+                        custom_field::Entity::insert_many(batch.to_vec())
+                            .exec(txn)
+                            .await?;
+                    }
+
+                    Ok(revision)
+                })
+            })
+            .await
+            .map_err(|e: TransactionError<DbErr>| {
+                AppError::Internal(format!("Failed to import vault: {}", e))
+            })?;
+
+        publish_vault_changed(app_state, user_id, None, VaultChangeType::Bulk, revision);
+    }
+
+    Ok(GraphqlResponse::<ImportVaultResponse> {
+        success: true,
+        message: if dry_run {
+            format!("{} entries would be imported", response.imported)
+        } else {
+            format!("{} entries imported successfully", response.imported)
+        },
+        data: response,
+    })
+}
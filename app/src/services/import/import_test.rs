@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use aes::Aes256;
+    use base64::{Engine as _, engine::general_purpose};
+    use cbc::cipher::{BlockEncryptMut, KeyIvInit, block_padding::Pkcs7};
+    use hkdf::Hkdf;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+    use zip::{ZipWriter, write::FileOptions};
+
+    use crate::{
+        models::custom_field::CustomFieldType,
+        services::import::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    fn enc_string(plain_text: &[u8], enc_key: &[u8; 32], mac_key: &[u8; 32]) -> AppResult<String> {
+        let iv = [7u8; 16];
+        let cipher_text = cbc::Encryptor::<Aes256>::new_from_slices(enc_key, &iv)
+            .map_err(|e| AppError::Crypto(e.to_string()))?
+            .encrypt_padded_vec_mut::<Pkcs7>(plain_text);
+
+        let mut hmac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key)
+            .map_err(|e| AppError::Crypto(e.to_string()))?;
+        hmac.update(&iv);
+        hmac.update(&cipher_text);
+
+        Ok(format!(
+            "2.{}|{}|{}",
+            general_purpose::STANDARD.encode(iv),
+            general_purpose::STANDARD.encode(cipher_text),
+            general_purpose::STANDARD.encode(hmac.finalize().into_bytes())
+        ))
+    }
+
+    const BITWARDEN_EXPORT: &str = r#"{
+        "encrypted": false,
+        "folders": [{"id": "f1", "name": "Work/Mail"}],
+        "items": [
+            {
+                "type": 1,
+                "name": "Example",
+                "notes": "recovery hint",
+                "favorite": true,
+                "folderId": "f1",
+                "login": {
+                    "uris": [{"uri": "https://example.com/login"}, {"uri": "https://mail.example.com"}],
+                    "username": "alice@example.com",
+                    "password": "secret",
+                    "totp": "JBSWY3DPEHPK3PXP"
+                },
+                "fields": [
+                    {"name": "PIN", "value": "1234", "type": 1},
+                    {"name": "Linked", "value": null, "type": 3}
+                ]
+            },
+            {"type": 2, "name": "Note", "secureNote": {"type": 0}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_csv_exports() -> AppResult<()> {
+        let lastpass = parse_lastpass_csv(
+            b"url,username,password,totp,extra,name,grouping,fav\n\
+              https://example.com,alice,secret,,note,Example,Work\\Mail,1\n\
+              http://sn,,,,card number,Card,,0\n",
+        )?;
+
+        if lastpass.unsupported != 1
+            || lastpass.entries.len() != 1
+            || lastpass.entries[0].folder != vec!["Work".to_string(), "Mail".to_string()]
+            || !lastpass.entries[0].favorite
+            || lastpass.entries[0].notes.as_deref() != Some("note")
+        {
+            return Err(AppError::Internal(format!(
+                "Unexpected LastPass import: {:?}",
+                lastpass.entries
+            )));
+        }
+
+        let chrome = parse_browser_csv(
+            b"name,url,username,password,note\nExample,https://example.com,alice,secret,\n",
+        )?;
+        let firefox = parse_browser_csv(
+            b"\"url\",\"username\",\"password\",\"httpRealm\"\n\"https://example.com\",\"alice\",\"secret\",\n",
+        )?;
+
+        if chrome.entries[0].name.as_deref() != Some("Example")
+            || chrome.entries[0].notes.is_some()
+            || firefox.entries[0].uris != vec!["https://example.com".to_string()]
+            || firefox.entries[0].password.as_deref() != Some("secret")
+        {
+            return Err(AppError::Internal("Unexpected browser import".to_string()));
+        }
+
+        if parse_browser_csv(b"name,password\nExample,secret\n").is_ok() {
+            return Err(AppError::Internal(
+                "CSV without a url column was parsed".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_bitwarden_json() -> AppResult<()> {
+        let parsed_import = parse_bitwarden_json(BITWARDEN_EXPORT.as_bytes(), None)?;
+        let entry = &parsed_import.entries[0];
+
+        if parsed_import.unsupported != 1
+            || entry.uris.len() != 2
+            || entry.folder != vec!["Work".to_string(), "Mail".to_string()]
+            || entry.totp.as_deref() != Some("JBSWY3DPEHPK3PXP")
+            || entry.custom_fields.len() != 1
+            || entry.custom_fields[0].field_type != CustomFieldType::Hidden
+        {
+            return Err(AppError::Internal(format!(
+                "Unexpected Bitwarden import: {:?}",
+                parsed_import.entries
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_password_protected_bitwarden_json() -> AppResult<()> {
+        let salt = "c2FsdHNhbHRzYWx0";
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(b"export password", salt.as_bytes(), 1000, &mut key);
+
+        let hkdf = Hkdf::<Sha256>::from_prk(&key).map_err(|e| AppError::Crypto(e.to_string()))?;
+        let mut enc_key = [0u8; 32];
+        let mut mac_key = [0u8; 32];
+        hkdf.expand(b"enc", &mut enc_key)
+            .map_err(|e| AppError::Crypto(e.to_string()))?;
+        hkdf.expand(b"mac", &mut mac_key)
+            .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+        let export = serde_json::json!({
+            "encrypted": true,
+            "passwordProtected": true,
+            "salt": salt,
+            "kdfType": 0,
+            "kdfIterations": 1000,
+            "encKeyValidation_DO_NOT_EDIT": enc_string(b"validation", &enc_key, &mac_key)?,
+            "data": enc_string(BITWARDEN_EXPORT.as_bytes(), &enc_key, &mac_key)?,
+        })
+        .to_string();
+
+        let parsed_import = parse_bitwarden_json(export.as_bytes(), Some("export password"))?;
+
+        if parsed_import.entries[0].password.as_deref() != Some("secret") {
+            return Err(AppError::Internal(
+                "Encrypted export was not decrypted".to_string(),
+            ));
+        }
+
+        match parse_bitwarden_json(export.as_bytes(), Some("wrong password")) {
+            Err(AppError::Validation(_)) => Ok(()),
+            _ => Err(AppError::Internal(
+                "Wrong export password was accepted".to_string(),
+            )),
+        }
+    }
+
+    #[test]
+    fn test_parse_one_password_1pux() -> AppResult<()> {
+        let export_data = serde_json::json!({
+            "accounts": [{
+                "vaults": [{
+                    "attrs": {"name": "Private"},
+                    "items": [
+                        {
+                            "state": "active",
+                            "categoryUuid": "001",
+                            "favIndex": 1,
+                            "overview": {
+                                "title": "Example",
+                                "url": "https://example.com",
+                                "urls": [{"url": "https://example.com"}, {"url": "https://example.org"}]
+                            },
+                            "details": {
+                                "loginFields": [
+                                    {"designation": "username", "value": "alice"},
+                                    {"designation": "password", "value": "secret"}
+                                ],
+                                "notesPlain": "recovery hint",
+                                "sections": [{
+                                    "fields": [
+                                        {"title": "one-time password", "value": {"totp": "otpauth://totp/x"}},
+                                        {"title": "PIN", "value": {"concealed": "1234"}}
+                                    ]
+                                }]
+                            }
+                        },
+                        {"state": "archived", "categoryUuid": "001", "overview": {"title": "Old"}},
+                        {"state": "active", "categoryUuid": "002", "overview": {"title": "Card"}}
+                    ]
+                }]
+            }]
+        });
+
+        let mut zip = ZipWriter::new(std::io::Cursor::new(vec![]));
+        zip.start_file("export.data", FileOptions::default())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        zip.write_all(export_data.to_string().as_bytes())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let archive = zip
+            .finish()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .into_inner();
+
+        let parsed_import = parse_one_password_1pux(&archive)?;
+        let entry = &parsed_import.entries[0];
+
+        if parsed_import.entries.len() != 1
+            || parsed_import.unsupported != 1
+            || entry.uris.len() != 2
+            || entry.folder != vec!["Private".to_string()]
+            || !entry.favorite
+            || entry.totp.as_deref() != Some("otpauth://totp/x")
+            || entry.custom_fields.len() != 1
+        {
+            return Err(AppError::Internal(format!(
+                "Unexpected 1Password import: {:?}",
+                parsed_import.entries
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,553 @@
+use std::{collections::HashMap, io::Read};
+
+use aes::{
+    Aes256,
+    cipher::{BlockEncrypt, KeyInit, generic_array::GenericArray},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose};
+use cbc::cipher::{BlockDecryptMut, KeyIvInit, block_padding::Pkcs7};
+use chacha20::{ChaCha20, cipher::StreamCipher};
+use flate2::read::GzDecoder;
+use hmac::{Hmac, Mac};
+use roxmltree::{Document, Node, NodeId};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{
+    models::{custom_field::CustomFieldType, custom_field_dtos::CustomFieldInput},
+    services::import::{ImportedEntry, ParsedImport, non_empty},
+    utils::error::{AppError, AppResult},
+};
+
+pub const SIGNATURE_1: u32 = 0x9AA2_D903;
+pub const SIGNATURE_2: u32 = 0xB54B_FB67;
+
+pub const HEADER_END: u8 = 0;
+pub const HEADER_CIPHER_ID: u8 = 2;
+pub const HEADER_COMPRESSION_FLAGS: u8 = 3;
+pub const HEADER_MASTER_SEED: u8 = 4;
+pub const HEADER_ENCRYPTION_IV: u8 = 7;
+pub const HEADER_KDF_PARAMETERS: u8 = 11;
+
+pub const INNER_HEADER_END: u8 = 0;
+pub const INNER_HEADER_STREAM_ID: u8 = 1;
+pub const INNER_HEADER_STREAM_KEY: u8 = 2;
+pub const INNER_STREAM_CHACHA20: u32 = 3;
+
+const CIPHER_AES256: [u8; 16] = [
+    0x31, 0xC1, 0xF2, 0xE6, 0xBF, 0x71, 0x43, 0x50, 0xBE, 0x58, 0x05, 0x21, 0x6A, 0xFC, 0x5A, 0xFF,
+];
+pub const CIPHER_CHACHA20: [u8; 16] = [
+    0xD6, 0x03, 0x8A, 0x2B, 0x8B, 0x6F, 0x4C, 0xB5, 0xA5, 0x24, 0x33, 0x9A, 0x31, 0xDB, 0xB5, 0x9A,
+];
+const KDF_AES: [u8; 16] = [
+    0xC9, 0xD9, 0xF3, 0x9A, 0x62, 0x8A, 0x44, 0x60, 0xBF, 0x74, 0x0D, 0x08, 0xC1, 0x8A, 0x4F, 0xEA,
+];
+const KDF_ARGON2D: [u8; 16] = [
+    0xEF, 0x63, 0x6D, 0xDF, 0x8C, 0x29, 0x44, 0x4B, 0x91, 0xF7, 0xA9, 0xA4, 0x03, 0xE3, 0x0A, 0x0C,
+];
+pub const KDF_ARGON2ID: [u8; 16] = [
+    0x9E, 0x29, 0x8B, 0x19, 0x56, 0xDB, 0x47, 0x73, 0xB2, 0x3D, 0xFC, 0x3E, 0xC6, 0xF0, 0xA1, 0xE6,
+];
+
+// the database decides how hard its key is to derive, these keep it bounded
+const MAX_AES_KDF_ROUNDS: u64 = 50_000_000;
+const MAX_ARGON2_MEMORY_BYTES: u64 = 1024 * 1024 * 1024;
+const MAX_ARGON2_ITERATIONS: u64 = 100;
+const MAX_ARGON2_PARALLELISM: u32 = 16;
+
+/// Standard entry strings, everything else becomes a custom field
+const STANDARD_STRINGS: [&str; 5] = ["Title", "UserName", "Password", "URL", "Notes"];
+const TOTP_STRINGS: [&str; 2] = ["otp", "TOTP Seed"];
+/// KeePass2Android and KeePassXC keep additional URLs under this prefix
+pub const EXTRA_URL_PREFIX: &str = "KP2A_URL";
+
+fn invalid_database() -> AppError {
+    AppError::Validation("Invalid KeePass database".to_string())
+}
+
+/// Little endian reader over the database bytes
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn take(&mut self, length: usize) -> AppResult<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(invalid_database)?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> AppResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> AppResult<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> AppResult<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+}
+
+fn le_u32(value: &[u8]) -> AppResult<u32> {
+    Ok(u32::from_le_bytes(
+        value.try_into().map_err(|_| invalid_database())?,
+    ))
+}
+
+fn le_u64(value: &[u8]) -> AppResult<u64> {
+    Ok(u64::from_le_bytes(
+        value.try_into().map_err(|_| invalid_database())?,
+    ))
+}
+
+/// KDF parameters are stored as a VariantDictionary of typed values
+fn read_variant_dictionary(data: &[u8]) -> AppResult<HashMap<String, Vec<u8>>> {
+    let mut reader = ByteReader::new(data);
+
+    if reader.u16()? >> 8 != 1 {
+        return Err(invalid_database());
+    }
+
+    let mut dictionary = HashMap::new();
+
+    loop {
+        let value_type = reader.u8()?;
+
+        if value_type == 0 {
+            return Ok(dictionary);
+        }
+
+        let key_length = reader.u32()? as usize;
+        let key = String::from_utf8_lossy(reader.take(key_length)?).to_string();
+        let value_length = reader.u32()? as usize;
+        dictionary.insert(key, reader.take(value_length)?.to_vec());
+    }
+}
+
+fn kdf_value<'a>(parameters: &'a HashMap<String, Vec<u8>>, key: &str) -> AppResult<&'a [u8]> {
+    parameters
+        .get(key)
+        .map(Vec::as_slice)
+        .ok_or_else(invalid_database)
+}
+
+/// Stretch the composite key with the KDF named in the header
+pub fn transform_key(
+    composite_key: &[u8; 32],
+    parameters: &HashMap<String, Vec<u8>>,
+) -> AppResult<[u8; 32]> {
+    let kdf_uuid = kdf_value(parameters, "$UUID")?;
+    let salt = kdf_value(parameters, "S")?;
+
+    if kdf_uuid == KDF_AES {
+        let rounds = le_u64(kdf_value(parameters, "R")?)?;
+
+        if rounds > MAX_AES_KDF_ROUNDS {
+            return Err(AppError::Validation(
+                "KeePass key derivation rounds are too high".to_string(),
+            ));
+        }
+
+        let cipher = Aes256::new_from_slice(salt).map_err(|_| invalid_database())?;
+        let mut key = *composite_key;
+
+        for _ in 0..rounds {
+            for block in key.chunks_exact_mut(16) {
+                cipher.encrypt_block(GenericArray::from_mut_slice(block));
+            }
+        }
+
+        return Ok(Sha256::digest(key).into());
+    }
+
+    let algorithm = if kdf_uuid == KDF_ARGON2D {
+        Algorithm::Argon2d
+    } else if kdf_uuid == KDF_ARGON2ID {
+        Algorithm::Argon2id
+    } else {
+        return Err(AppError::Validation(
+            "Unsupported KeePass key derivation".to_string(),
+        ));
+    };
+
+    let memory = le_u64(kdf_value(parameters, "M")?)?;
+    let iterations = le_u64(kdf_value(parameters, "I")?)?;
+    let parallelism = le_u32(kdf_value(parameters, "P")?)?;
+
+    if memory > MAX_ARGON2_MEMORY_BYTES
+        || iterations > MAX_ARGON2_ITERATIONS
+        || parallelism > MAX_ARGON2_PARALLELISM
+    {
+        return Err(AppError::Validation(
+            "KeePass key derivation parameters are too high".to_string(),
+        ));
+    }
+
+    let version = match parameters
+        .get("V")
+        .map(|version| le_u32(version))
+        .transpose()?
+    {
+        Some(0x10) => Version::V0x10,
+        _ => Version::V0x13,
+    };
+
+    let params = Params::new(
+        (memory / 1024) as u32,
+        iterations as u32,
+        parallelism,
+        Some(32),
+    )
+    .map_err(|e| AppError::Validation(format!("Invalid KeePass key derivation: {}", e)))?;
+
+    let mut key = [0u8; 32];
+    Argon2::new(algorithm, version, params)
+        .hash_password_into(composite_key, salt, &mut key)
+        .map_err(|e| AppError::Crypto(e.to_string()))?;
+
+    Ok(key)
+}
+
+pub fn hmac_key(index: u64, hmac_base_key: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+    hasher.update(index.to_le_bytes());
+    hasher.update(hmac_base_key);
+    hasher.finalize().to_vec()
+}
+
+pub fn block_hmac(key: &[u8]) -> AppResult<Hmac<Sha256>> {
+    <Hmac<Sha256> as Mac>::new_from_slice(key).map_err(|e| AppError::Crypto(e.to_string()))
+}
+
+/// Read the HMAC protected blocks that follow the header
+fn read_hmac_blocks(reader: &mut ByteReader, hmac_base_key: &[u8]) -> AppResult<Vec<u8>> {
+    let mut data = vec![];
+
+    for index in 0u64.. {
+        let block_mac = reader.take(32)?;
+        let size = reader.u32()?;
+        let block = reader.take(size as usize)?;
+
+        let mut hmac = block_hmac(&hmac_key(index, hmac_base_key))?;
+        hmac.update(&index.to_le_bytes());
+        hmac.update(&size.to_le_bytes());
+        hmac.update(block);
+        hmac.verify_slice(block_mac)
+            .map_err(|_| AppError::Validation("KeePass database is corrupted".to_string()))?;
+
+        if size == 0 {
+            break;
+        }
+
+        data.extend_from_slice(block);
+    }
+
+    Ok(data)
+}
+
+/// Decrypt protected values, which share one key stream in document order
+fn unprotect_values(
+    document: &Document,
+    stream_id: u32,
+    stream_key: &[u8],
+) -> AppResult<HashMap<NodeId, String>> {
+    let mut values = HashMap::new();
+
+    let protected_nodes: Vec<Node> = document
+        .descendants()
+        .filter(|node| node.has_tag_name("Value") && node.attribute("Protected") == Some("True"))
+        .collect();
+
+    if protected_nodes.is_empty() {
+        return Ok(values);
+    }
+
+    if stream_id != INNER_STREAM_CHACHA20 {
+        return Err(AppError::Validation(
+            "Unsupported KeePass inner stream cipher".to_string(),
+        ));
+    }
+
+    let stream_key = Sha512::digest(stream_key);
+    let mut cipher = ChaCha20::new_from_slices(&stream_key[..32], &stream_key[32..44])
+        .map_err(|_| invalid_database())?;
+
+    for node in protected_nodes {
+        let mut value = general_purpose::STANDARD
+            .decode(node.text().unwrap_or_default())
+            .map_err(|_| invalid_database())?;
+        cipher.apply_keystream(&mut value);
+
+        values.insert(node.id(), String::from_utf8_lossy(&value).to_string());
+    }
+
+    Ok(values)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|child| child.text())
+}
+
+fn to_imported_entry(
+    entry: Node,
+    folder: &[String],
+    protected_values: &HashMap<NodeId, String>,
+) -> ImportedEntry {
+    let mut strings: Vec<(&str, Option<String>, bool)> = vec![];
+
+    for string in entry
+        .children()
+        .filter(|child| child.has_tag_name("String"))
+    {
+        let (Some(key), Some(value)) = (child_text(string, "Key"), child(string, "Value")) else {
+            continue;
+        };
+
+        let protected = protected_values.get(&value.id());
+        let text = protected
+            .map(String::as_str)
+            .or(value.text())
+            .and_then(|text| non_empty(Some(text)));
+
+        strings.push((key, text, protected.is_some()));
+    }
+
+    let string = |name: &str| {
+        strings
+            .iter()
+            .find(|(key, _, _)| *key == name)
+            .and_then(|(_, value, _)| value.clone())
+    };
+
+    let uris = strings
+        .iter()
+        .filter(|(key, _, _)| *key == "URL" || key.starts_with(EXTRA_URL_PREFIX))
+        .filter_map(|(_, value, _)| value.clone())
+        .collect();
+
+    ImportedEntry {
+        name: string("Title"),
+        uris,
+        username: string("UserName"),
+        password: string("Password"),
+        notes: string("Notes"),
+        totp: TOTP_STRINGS.iter().find_map(|name| string(name)),
+        folder: folder.to_vec(),
+        favorite: false,
+        custom_fields: strings
+            .iter()
+            .filter(|(key, _, _)| {
+                !STANDARD_STRINGS.contains(key)
+                    && !TOTP_STRINGS.contains(key)
+                    && !key.starts_with(EXTRA_URL_PREFIX)
+            })
+            .map(|(key, value, protected)| CustomFieldInput {
+                name: key.to_string(),
+                field_type: if *protected {
+                    CustomFieldType::Hidden
+                } else {
+                    CustomFieldType::Text
+                },
+                value: value.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn read_group(
+    group: Node,
+    folder: &[String],
+    recycle_bin_uuid: Option<&str>,
+    protected_values: &HashMap<NodeId, String>,
+    parsed_import: &mut ParsedImport,
+) {
+    // history entries sit below their entry, so only direct children are imported
+    for entry in group.children().filter(|child| child.has_tag_name("Entry")) {
+        parsed_import
+            .entries
+            .push(to_imported_entry(entry, folder, protected_values));
+    }
+
+    for subgroup in group.children().filter(|child| child.has_tag_name("Group")) {
+        if recycle_bin_uuid.is_some() && child_text(subgroup, "UUID") == recycle_bin_uuid {
+            continue;
+        }
+
+        let mut subfolder = folder.to_vec();
+        subfolder.extend(non_empty(child_text(subgroup, "Name")));
+
+        read_group(
+            subgroup,
+            &subfolder,
+            recycle_bin_uuid,
+            protected_values,
+            parsed_import,
+        );
+    }
+}
+
+/// KeePass KDBX 4 database, opened with its master password
+pub fn parse_keepass_kdbx(data: &[u8], password: &str) -> AppResult<ParsedImport> {
+    let mut reader = ByteReader::new(data);
+
+    if reader.u32()? != SIGNATURE_1 || reader.u32()? != SIGNATURE_2 {
+        return Err(invalid_database());
+    }
+
+    let _minor_version = reader.u16()?;
+
+    if reader.u16()? != 4 {
+        return Err(AppError::Validation(
+            "Only KDBX 4 databases can be imported".to_string(),
+        ));
+    }
+
+    let mut header_fields: HashMap<u8, &[u8]> = HashMap::new();
+
+    loop {
+        let field_id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+
+        if field_id == HEADER_END {
+            break;
+        }
+
+        header_fields.insert(field_id, value);
+    }
+
+    let header = &data[..reader.position];
+    let header_field = |field_id: u8| {
+        header_fields
+            .get(&field_id)
+            .copied()
+            .ok_or_else(invalid_database)
+    };
+
+    if reader.take(32)? != Sha256::digest(header).as_slice() {
+        return Err(AppError::Validation(
+            "KeePass database is corrupted".to_string(),
+        ));
+    }
+
+    let master_seed = header_field(HEADER_MASTER_SEED)?;
+    let kdf_parameters = read_variant_dictionary(header_field(HEADER_KDF_PARAMETERS)?)?;
+
+    let composite_key: [u8; 32] = Sha256::digest(Sha256::digest(password.as_bytes())).into();
+    let transformed_key = transform_key(&composite_key, &kdf_parameters)?;
+
+    let mut hasher = Sha512::new();
+    hasher.update(master_seed);
+    hasher.update(transformed_key);
+    hasher.update([1u8]);
+    let hmac_base_key = hasher.finalize();
+
+    // a wrong password is first noticed on the header HMAC
+    let mut header_hmac = block_hmac(&hmac_key(u64::MAX, &hmac_base_key))?;
+    header_hmac.update(header);
+    header_hmac
+        .verify_slice(reader.take(32)?)
+        .map_err(|_| AppError::Validation("Invalid database password".to_string()))?;
+
+    let mut encrypted_payload = read_hmac_blocks(&mut reader, &hmac_base_key)?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(master_seed);
+    hasher.update(transformed_key);
+    let encryption_key = hasher.finalize();
+
+    let cipher_id = header_field(HEADER_CIPHER_ID)?;
+    let encryption_iv = header_field(HEADER_ENCRYPTION_IV)?;
+
+    let payload = if cipher_id == CIPHER_AES256 {
+        cbc::Decryptor::<Aes256>::new_from_slices(&encryption_key, encryption_iv)
+            .map_err(|_| invalid_database())?
+            .decrypt_padded_vec_mut::<Pkcs7>(&encrypted_payload)
+            .map_err(|_| invalid_database())?
+    } else if cipher_id == CIPHER_CHACHA20 {
+        ChaCha20::new_from_slices(&encryption_key, encryption_iv)
+            .map_err(|_| invalid_database())?
+            .apply_keystream(&mut encrypted_payload);
+        encrypted_payload
+    } else {
+        return Err(AppError::Validation(
+            "Unsupported KeePass cipher".to_string(),
+        ));
+    };
+
+    let payload = if le_u32(header_field(HEADER_COMPRESSION_FLAGS)?)? == 1 {
+        let mut decompressed = vec![];
+        GzDecoder::new(payload.as_slice())
+            .read_to_end(&mut decompressed)
+            .map_err(|_| invalid_database())?;
+        decompressed
+    } else {
+        payload
+    };
+
+    let mut reader = ByteReader::new(&payload);
+    let mut stream_id = 0;
+    let mut stream_key: &[u8] = &[];
+
+    // binary attachments in the inner header are not imported
+    loop {
+        let field_id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let value = reader.take(size)?;
+
+        match field_id {
+            INNER_HEADER_END => break,
+            INNER_HEADER_STREAM_ID => stream_id = le_u32(value)?,
+            INNER_HEADER_STREAM_KEY => stream_key = value,
+            _ => {}
+        }
+    }
+
+    let xml = std::str::from_utf8(reader.remaining()).map_err(|_| invalid_database())?;
+    let document = Document::parse(xml)
+        .map_err(|e| AppError::Validation(format!("Invalid KeePass database: {}", e)))?;
+
+    let protected_values = unprotect_values(&document, stream_id, stream_key)?;
+
+    let root = document.root_element();
+    let recycle_bin_uuid = child(root, "Meta")
+        .filter(|meta| child_text(*meta, "RecycleBinEnabled") != Some("False"))
+        .and_then(|meta| child_text(meta, "RecycleBinUUID"));
+
+    let mut parsed_import = ParsedImport::default();
+
+    // the root group stands for the database itself and is not a folder
+    if let Some(root_group) = child(root, "Root").and_then(|root| child(root, "Group")) {
+        read_group(
+            root_group,
+            &[],
+            recycle_bin_uuid,
+            &protected_values,
+            &mut parsed_import,
+        );
+    }
+
+    Ok(parsed_import)
+}
@@ -0,0 +1,13 @@
+pub mod bitwarden;
+pub mod csv_export;
+#[allow(clippy::module_inception)]
+pub mod import;
+mod import_test;
+pub mod keepass;
+pub mod one_password;
+
+pub use bitwarden::*;
+pub use csv_export::*;
+pub use import::*;
+pub use keepass::*;
+pub use one_password::*;
@@ -0,0 +1,149 @@
+use std::io::{Cursor, Read};
+
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::{
+    models::{custom_field::CustomFieldType, custom_field_dtos::CustomFieldInput},
+    services::import::{ImportedEntry, ParsedImport, non_empty},
+    utils::error::{AppError, AppResult},
+};
+
+const LOGIN_CATEGORY: &str = "001";
+const PASSWORD_CATEGORY: &str = "005";
+
+fn string_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(Value::as_str)
+}
+
+fn array_at<'a>(value: &'a Value, pointer: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// Section fields hold their value under a key naming its kind
+fn to_custom_field(field: &Value) -> Option<(CustomFieldInput, bool)> {
+    let name = non_empty(string_at(field, "/title"))?;
+    let value = field.get("value")?.as_object()?;
+    let (kind, value) = value.iter().next()?;
+
+    let (field_type, value) = match (kind.as_str(), value) {
+        ("concealed", Value::String(value)) => (CustomFieldType::Hidden, value.to_string()),
+        ("totp", Value::String(value)) => return Some((hidden_field(name, value), true)),
+        ("url", Value::String(value)) => (CustomFieldType::Url, value.to_string()),
+        ("email", Value::Object(email)) => (
+            CustomFieldType::Text,
+            email.get("email_address")?.as_str()?.to_string(),
+        ),
+        (_, Value::String(value)) => (CustomFieldType::Text, value.to_string()),
+        _ => return None,
+    };
+
+    Some((
+        CustomFieldInput {
+            name,
+            field_type,
+            value: non_empty(Some(&value)),
+        },
+        false,
+    ))
+}
+
+fn hidden_field(name: String, value: &str) -> CustomFieldInput {
+    CustomFieldInput {
+        name,
+        field_type: CustomFieldType::Hidden,
+        value: non_empty(Some(value)),
+    }
+}
+
+fn to_imported_entry(item: &Value, vault_name: Option<&str>) -> ImportedEntry {
+    let login_field = |designation: &str| {
+        array_at(item, "/details/loginFields")
+            .find(|field| string_at(field, "/designation") == Some(designation))
+            .and_then(|field| non_empty(string_at(field, "/value")))
+    };
+
+    let mut uris: Vec<String> = non_empty(string_at(item, "/overview/url"))
+        .into_iter()
+        .collect();
+
+    for url in array_at(item, "/overview/urls") {
+        if let Some(url) = non_empty(string_at(url, "/url")) {
+            if !uris.contains(&url) {
+                uris.push(url);
+            }
+        }
+    }
+
+    let mut totp = None;
+    let mut custom_fields = vec![];
+
+    for field in
+        array_at(item, "/details/sections").flat_map(|section| array_at(section, "/fields"))
+    {
+        match to_custom_field(field) {
+            Some((custom_field, true)) if totp.is_none() => totp = custom_field.value,
+            Some((custom_field, _)) => custom_fields.push(custom_field),
+            None => {}
+        }
+    }
+
+    ImportedEntry {
+        name: non_empty(string_at(item, "/overview/title")),
+        uris,
+        username: login_field("username"),
+        // password items keep their secret in the details instead of a login field
+        password: login_field("password")
+            .or_else(|| non_empty(string_at(item, "/details/password"))),
+        notes: non_empty(string_at(item, "/details/notesPlain")),
+        totp,
+        folder: non_empty(vault_name).into_iter().collect(),
+        favorite: item
+            .get("favIndex")
+            .and_then(Value::as_i64)
+            .is_some_and(|fav_index| fav_index > 0),
+        custom_fields,
+    }
+}
+
+/// 1Password 1PUX, a zip archive holding the export as export.data
+pub fn parse_one_password_1pux(data: &[u8]) -> AppResult<ParsedImport> {
+    let mut archive = ZipArchive::new(Cursor::new(data))
+        .map_err(|e| AppError::Validation(format!("Invalid 1PUX export: {}", e)))?;
+
+    let mut export_data = String::new();
+    archive
+        .by_name("export.data")
+        .map_err(|e| AppError::Validation(format!("Invalid 1PUX export: {}", e)))?
+        .read_to_string(&mut export_data)
+        .map_err(|e| AppError::Validation(format!("Invalid 1PUX export: {}", e)))?;
+
+    let export: Value = serde_json::from_str(&export_data)
+        .map_err(|e| AppError::Validation(format!("Invalid 1PUX export: {}", e)))?;
+
+    let mut parsed_import = ParsedImport::default();
+
+    for vault in array_at(&export, "/accounts").flat_map(|account| array_at(account, "/vaults")) {
+        let vault_name = string_at(vault, "/attrs/name");
+
+        for item in array_at(vault, "/items") {
+            // archived and deleted items stay behind
+            if string_at(item, "/state").is_some_and(|state| state != "active") {
+                continue;
+            }
+
+            match string_at(item, "/categoryUuid") {
+                Some(LOGIN_CATEGORY | PASSWORD_CATEGORY) => parsed_import
+                    .entries
+                    .push(to_imported_entry(item, vault_name)),
+                _ => parsed_import.unsupported += 1,
+            }
+        }
+    }
+
+    Ok(parsed_import)
+}
@@ -0,0 +1,17 @@
+use crate::dtos::app_state::AppState;
+
+use super::MailTemplate;
+
+/// Send a mail in the background, a failed delivery is logged and never fails the request
+pub fn send_mail(app_state: &AppState, to: &str, template: MailTemplate) {
+    let mailer = app_state.mailer.clone();
+    let mail = template.to_mail(to, &app_state.env_variables.app_url);
+
+    tokio::spawn(async move {
+        let subject = mail.subject.clone();
+
+        if let Err(e) = mailer.send(mail).await {
+            tracing::error!("Failed to send mail \"{}\": {}", subject, e);
+        }
+    });
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod test {
+    use crate::{
+        services::mail::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_render_templates() -> AppResult<()> {
+        let mail = MailTemplate::SignupVerification {
+            token: "signup-token".to_string(),
+        }
+        .to_mail("user@example.com", "https://vault.example.com");
+
+        if mail.to != "user@example.com"
+            || !mail
+                .body
+                .contains("https://vault.example.com/signup/complete?token=signup-token")
+        {
+            return Err(AppError::Internal(
+                "Signup mail is missing its link".to_string(),
+            ));
+        }
+
+        let (_, body) = MailTemplate::EmailVerification {
+            token: "verification-token".to_string(),
+        }
+        .render("https://vault.example.com");
+
+        if !body.contains("https://vault.example.com/verify-email?token=verification-token") {
+            return Err(AppError::Internal(
+                "Verification mail is missing its link".to_string(),
+            ));
+        }
+
+        let (_, body) = MailTemplate::EmailChangeRequested {
+            new_email: "new@example.com".to_string(),
+            token: "cancel-token".to_string(),
+        }
+        .render("https://vault.example.com");
+
+        if !body.contains("n***@example.com")
+            || body.contains("new@example.com")
+            || !body.contains("https://vault.example.com/change-email/cancel?token=cancel-token")
+        {
+            return Err(AppError::Internal(
+                "Email change mail is missing its cancel link".to_string(),
+            ));
+        }
+
+        let (_, body) = MailTemplate::NewDeviceLogin {
+            user_agent: None,
+            ip_address: Some("198.51.100.7".to_string()),
+            at: chrono::Utc::now(),
+        }
+        .render("");
+
+        if !body.contains("Device: Unknown") || !body.contains("Address: 198.51.100.7") {
+            return Err(AppError::Internal(
+                "New device mail is missing its details".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_file_mailer() -> AppResult<()> {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let mailer = FileMailer::new(&root);
+
+        mailer
+            .send(OutgoingMail {
+                to: "user@example.com".to_string(),
+                subject: "Verify your email".to_string(),
+                body: "link".to_string(),
+            })
+            .await?;
+
+        let files = std::fs::read_dir(&root)
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let [file] = files.as_slice() else {
+            return Err(AppError::Internal("Mail was not written once".to_string()));
+        };
+
+        let contents =
+            std::fs::read_to_string(file.path()).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if contents != "To: user@example.com\nSubject: Verify your email\n\nlink" {
+            return Err(AppError::Internal("Mail was written wrong".to_string()));
+        }
+
+        let _ = std::fs::remove_dir_all(root);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::utils::error::{AppError, AppResult};
+
+/// A rendered mail ready to be handed to a mailer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivery of transactional mail
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()>;
+}
+
+/// Mail sent through an SMTP relay over STARTTLS
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        credentials: Option<(String, String)>,
+        from: &str,
+    ) -> AppResult<Self> {
+        let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| AppError::Internal(format!("Invalid SMTP relay: {}", e)))?
+            .port(port);
+
+        if let Some((username, password)) = credentials {
+            transport = transport.credentials(Credentials::new(username, password));
+        }
+
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::Internal(format!("Invalid sender address: {}", e)))?;
+
+        Ok(Self {
+            transport: transport.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        let to = mail
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| AppError::Internal(format!("Invalid recipient address: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body)
+            .map_err(|e| AppError::Internal(format!("Failed to build mail: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to send mail: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Mail written as files to a directory and noted in the log, for development and tests
+pub struct FileMailer {
+    root: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to create mail directory: {}", e)))?;
+
+        let path = self.root.join(format!("{}.txt", Uuid::new_v4()));
+
+        fs::write(
+            &path,
+            format!(
+                "To: {}\nSubject: {}\n\n{}",
+                mail.to, mail.subject, mail.body
+            ),
+        )
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write mail: {}", e)))?;
+
+        // links in the body are secrets, the log only says where to find them
+        tracing::info!(
+            "Mail \"{}\" to {} written to {}",
+            mail.subject,
+            mail.to,
+            path.display()
+        );
+
+        Ok(())
+    }
+}
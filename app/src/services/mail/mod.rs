@@ -0,0 +1,9 @@
+#[allow(clippy::module_inception)]
+pub mod mail;
+mod mail_test;
+pub mod mailer;
+pub mod templates;
+
+pub use mail::*;
+pub use mailer::*;
+pub use templates::*;
@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::OutgoingMail;
+
+/// The transactional mails the app sends
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MailTemplate {
+    /// Link to finish a signup, which also proves the email
+    SignupVerification {
+        token: String,
+    },
+    /// Link to verify the email of an existing account
+    EmailVerification {
+        token: String,
+    },
+    /// Link to the new address of an email change, which commits it
+    EmailChangeConfirmation {
+        token: String,
+    },
+    /// Sent to an address another account asked to move to, which already has an account
+    EmailAlreadyInUse,
+    /// Sent to the old address of an email change, with the new address masked and a link to
+    /// cancel it
+    EmailChangeRequested {
+        new_email: String,
+        token: String,
+    },
+    NewDeviceLogin {
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        at: DateTime<Utc>,
+    },
+    MasterPasswordChanged {
+        at: DateTime<Utc>,
+    },
+    RecoveryCodeUsed {
+        at: DateTime<Utc>,
+    },
+    AccountDeletionScheduled {
+        deletion_scheduled_at: DateTime<Utc>,
+    },
+    /// Sent once the account is erased, to the address it had
+    AccountDeleted {
+        receipt_id: Uuid,
+        deleted_at: DateTime<Utc>,
+    },
+    /// Sent to the grantor when a contact asks for emergency access
+    EmergencyAccessRequested {
+        grantee_email: String,
+        wait_days: i32,
+    },
+    /// Sent to the grantor once a contact used emergency access to take over the account
+    EmergencyTakeover {
+        grantee_email: String,
+        at: DateTime<Utc>,
+    },
+}
+
+fn format_time(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+/// Keep the first character of the local part and the domain, e.g. `j***@example.com`
+pub fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            let first = local.chars().next().map(String::from).unwrap_or_default();
+            format!("{}***@{}", first, domain)
+        }
+        None => "***".to_string(),
+    }
+}
+
+impl MailTemplate {
+    /// Subject and plain text body, links point at the web app
+    pub fn render(&self, app_url: &str) -> (String, String) {
+        match self {
+            MailTemplate::SignupVerification { token } => (
+                "Finish signing up".to_string(),
+                format!(
+                    "Follow this link to choose your master password and create your vault:\n\n\
+                     {}/signup/complete?token={}\n\n\
+                     If you did not sign up, ignore this mail.",
+                    app_url, token
+                ),
+            ),
+            MailTemplate::EmailVerification { token } => (
+                "Verify your email".to_string(),
+                format!(
+                    "Follow this link to verify the email of your account:\n\n\
+                     {}/verify-email?token={}\n\n\
+                     Sharing, organizations, emergency access and exports stay unavailable until you do.",
+                    app_url, token
+                ),
+            ),
+            MailTemplate::EmailChangeConfirmation { token } => (
+                "Confirm your new email".to_string(),
+                format!(
+                    "Follow this link to make this the email of your vault account:\n\n\
+                     {}/change-email/confirm?token={}\n\n\
+                     If you did not ask for this, ignore this mail.",
+                    app_url, token
+                ),
+            ),
+            MailTemplate::EmailAlreadyInUse => (
+                "Someone tried to use your email".to_string(),
+                "Another vault account asked to change its email to this address. \
+                 This address already has an account, so nothing was changed.\n\n\
+                 If this was you, sign in to that account instead. Otherwise, ignore this mail."
+                    .to_string(),
+            ),
+            MailTemplate::EmailChangeRequested { new_email, token } => (
+                "Your email is being changed".to_string(),
+                format!(
+                    "Someone asked to change the email of your account to {}. \
+                     It changes once the new address is confirmed.\n\n\
+                     If this was not you, follow this link to cancel it and change your master password:\n\n\
+                     {}/change-email/cancel?token={}",
+                    mask_email(new_email),
+                    app_url,
+                    token
+                ),
+            ),
+            MailTemplate::NewDeviceLogin {
+                user_agent,
+                ip_address,
+                at,
+            } => (
+                "New sign-in to your vault".to_string(),
+                format!(
+                    "Your account was signed in to from a new device.\n\n\
+                     Device: {}\nAddress: {}\nTime: {}\n\n\
+                     If this was not you, change your master password and revoke the session.",
+                    user_agent.as_deref().unwrap_or("Unknown"),
+                    ip_address.as_deref().unwrap_or("Unknown"),
+                    format_time(at)
+                ),
+            ),
+            MailTemplate::MasterPasswordChanged { at } => (
+                "Your master password was changed".to_string(),
+                format!(
+                    "The master password of your account was changed at {}, \
+                     and your other sessions were signed out.\n\n\
+                     If this was not you, recover your account with a recovery code.",
+                    format_time(at)
+                ),
+            ),
+            MailTemplate::RecoveryCodeUsed { at } => (
+                "A recovery code was used".to_string(),
+                format!(
+                    "A recovery code was used to set a new master password for your account at {}. \
+                     The code cannot be used again.\n\n\
+                     If this was not you, the rest of your recovery codes may be exposed.",
+                    format_time(at)
+                ),
+            ),
+            MailTemplate::AccountDeletionScheduled {
+                deletion_scheduled_at,
+            } => (
+                "Your account will be deleted".to_string(),
+                format!(
+                    "Your account and everything in your vault will be erased at {}.\n\n\
+                     Sign in and cancel the deletion before then to keep it.",
+                    format_time(deletion_scheduled_at)
+                ),
+            ),
+            MailTemplate::AccountDeleted {
+                receipt_id,
+                deleted_at,
+            } => (
+                "Your account was deleted".to_string(),
+                format!(
+                    "Your account and all of its data were erased at {}.\n\n\
+                     Deletion receipt: {}",
+                    format_time(deleted_at),
+                    receipt_id
+                ),
+            ),
+            MailTemplate::EmergencyAccessRequested {
+                grantee_email,
+                wait_days,
+            } => (
+                "Emergency access was requested".to_string(),
+                format!(
+                    "{} requested emergency access to your vault.\n\n\
+                     Access is granted automatically in {} days unless you decline it.",
+                    grantee_email, wait_days
+                ),
+            ),
+            MailTemplate::EmergencyTakeover { grantee_email, at } => (
+                "Your account was taken over".to_string(),
+                format!(
+                    "{} used emergency access to set a new master password for your account at {}, \
+                     and your sessions were signed out.\n\n\
+                     If this was not meant to happen, contact support.",
+                    grantee_email,
+                    format_time(at)
+                ),
+            ),
+        }
+    }
+
+    pub fn to_mail(&self, to: &str, app_url: &str) -> OutgoingMail {
+        let (subject, body) = self.render(app_url);
+
+        OutgoingMail {
+            to: to.to_string(),
+            subject,
+            body,
+        }
+    }
+}
@@ -1,3 +1,19 @@
+pub mod account;
+pub mod attachment;
 pub mod auth;
 mod crypto;
+pub mod custom_field;
+pub mod emergency_access;
+pub mod events;
+pub mod export;
+pub mod folder;
+pub mod import;
+pub mod mail;
+pub mod organization;
 pub mod password;
+pub mod session;
+pub mod share;
+pub mod tag;
+pub mod throttle;
+pub mod uri;
+pub mod vault_item;
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        collection,
+        organization_member::{self, OrganizationRole},
+        password, user,
+    },
+    services::{crypto::open_item_key, share::entry_key},
+    utils::error::{AppError, AppResult},
+};
+
+/// The organizations a user is a member of, with their role and opened organization key
+#[derive(Clone, Debug, Default)]
+pub struct OrganizationAccess {
+    user_id: Uuid,
+    organizations: HashMap<Uuid, (OrganizationRole, [u8; 32])>,
+    collections: HashMap<Uuid, Uuid>,
+}
+
+impl OrganizationAccess {
+    pub async fn load(
+        database_connection: &DatabaseConnection,
+        user_id: Uuid,
+        dek: &[u8; 32],
+    ) -> AppResult<Self> {
+        let memberships = organization_member::Entity::find()
+            .filter(organization_member::Column::UserId.eq(user_id))
+            .all(database_connection)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to get memberships: {}", e)))?;
+
+        if memberships.is_empty() {
+            return Ok(Self {
+                user_id,
+                ..Default::default()
+            });
+        }
+
+        let encrypted_private_key = user::Entity::find_by_id(user_id)
+            .one(database_connection)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .and_then(|user| user.encrypted_private_key)
+            .ok_or(AppError::Crypto("Private key is missing".to_string()))?;
+
+        let mut organizations = HashMap::new();
+
+        for membership in memberships {
+            let organization_key: [u8; 32] = open_item_key(
+                &membership.wrapped_organization_key,
+                &encrypted_private_key,
+                dek,
+            )?
+            .try_into()
+            .map_err(|_| {
+                AppError::Crypto("Unable to convert organization key to [u8; 32]".to_string())
+            })?;
+
+            organizations.insert(
+                membership.organization_id,
+                (membership.role, organization_key),
+            );
+        }
+
+        let collections = collection::Entity::find()
+            .filter(collection::Column::OrganizationId.is_in(organizations.keys().copied()))
+            .all(database_connection)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to get collections: {}", e)))?
+            .into_iter()
+            .map(|collection| (collection.id, collection.organization_id))
+            .collect();
+
+        Ok(Self {
+            user_id,
+            organizations,
+            collections,
+        })
+    }
+
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+
+    pub fn organization_ids(&self) -> Vec<Uuid> {
+        self.organizations.keys().copied().collect()
+    }
+
+    pub fn role(&self, organization_id: Uuid) -> AppResult<OrganizationRole> {
+        self.organizations
+            .get(&organization_id)
+            .map(|(role, _)| *role)
+            .ok_or(AppError::NotFound("Organization not found".to_string()))
+    }
+
+    pub fn organization_key(&self, organization_id: Uuid) -> AppResult<[u8; 32]> {
+        self.organizations
+            .get(&organization_id)
+            .map(|(_, organization_key)| *organization_key)
+            .ok_or(AppError::NotFound("Organization not found".to_string()))
+    }
+
+    /// The user's role in an organization, if it passes the given check
+    pub fn require_role(
+        &self,
+        organization_id: Uuid,
+        allowed: impl Fn(&OrganizationRole) -> bool,
+    ) -> AppResult<OrganizationRole> {
+        let role = self.role(organization_id)?;
+
+        if !allowed(&role) {
+            return Err(AppError::Authorization(
+                "Your organization role does not allow this".to_string(),
+            ));
+        }
+
+        Ok(role)
+    }
+
+    pub fn collection_organization(&self, collection_id: Uuid) -> AppResult<Uuid> {
+        self.collections
+            .get(&collection_id)
+            .copied()
+            .ok_or(AppError::NotFound("Collection not found".to_string()))
+    }
+
+    /// Entries the user can read, their own personal ones and those in their collections
+    pub fn entries_condition(&self) -> Condition {
+        Condition::any()
+            .add(
+                Condition::all()
+                    .add(password::Column::UserId.eq(self.user_id))
+                    .add(password::Column::CollectionId.is_null()),
+            )
+            .add(password::Column::CollectionId.is_in(self.collections.keys().copied()))
+    }
+
+    /// Check the user may change an entry, collection entries need a role that edits them
+    pub fn require_entry_edit(&self, password_entry: &password::Model) -> AppResult<()> {
+        match password_entry.collection_id {
+            Some(collection_id) => {
+                self.require_role(
+                    self.collection_organization(collection_id)?,
+                    OrganizationRole::can_edit_entries,
+                )?;
+
+                Ok(())
+            }
+            None if password_entry.user_id == self.user_id => Ok(()),
+            None => Err(AppError::NotFound("Password not found".to_string())),
+        }
+    }
+
+    /// The key an entry is encrypted with, the organization key for collection entries
+    pub fn entry_key(
+        &self,
+        password_entry: &password::Model,
+        dek: &[u8; 32],
+    ) -> AppResult<[u8; 32]> {
+        match password_entry.collection_id {
+            Some(collection_id) => {
+                self.organization_key(self.collection_organization(collection_id)?)
+            }
+            None => entry_key(password_entry, dek),
+        }
+    }
+
+    /// Attachments of personal entries stay wrapped with the owner's DEK
+    pub fn attachment_key(
+        &self,
+        password_entry: &password::Model,
+        dek: &[u8; 32],
+    ) -> AppResult<[u8; 32]> {
+        match password_entry.collection_id {
+            Some(collection_id) => {
+                self.organization_key(self.collection_organization(collection_id)?)
+            }
+            None => Ok(*dek),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod access;
+#[allow(clippy::module_inception)]
+pub mod organization;
+mod organization_test;
+
+pub use access::*;
+pub use organization::*;
@@ -0,0 +1,762 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::Context;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionError, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        attachment, collection, custom_field,
+        event_dtos::VaultChangeType,
+        organization,
+        organization_dtos::{
+            AddOrganizationMemberRequest, CollectionResponse, CreateCollectionRequest,
+            CreateOrganizationRequest, DeleteCollectionRequest, OrganizationMemberResponse,
+            OrganizationResponse, OrganizationsResponse, RemoveOrganizationMemberRequest,
+            RenameCollectionRequest, UpdateOrganizationMemberRequest,
+        },
+        organization_member::{self, OrganizationRole},
+        password, user,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        attachment::rekey_attachment,
+        crypto::{decrypt_password, encrypt_password, generate_dek, seal_item_key},
+        events::publish_vault_changed,
+        organization::OrganizationAccess,
+        password::next_revision,
+        share::{rekey_entry, update_rekeyed_entry},
+    },
+    utils::error::{AppError, AppResult},
+};
+
+pub fn to_collection_response(
+    collection: &collection::Model,
+    organization_key: &[u8; 32],
+) -> AppResult<CollectionResponse> {
+    Ok(CollectionResponse {
+        id: collection.id,
+        organization_id: collection.organization_id,
+        name: decrypt_password(&collection.encrypted_name, organization_key)?,
+    })
+}
+
+async fn find_member(
+    database_connection: &DatabaseConnection,
+    organization_id: Uuid,
+    email: &str,
+) -> AppResult<(organization_member::Model, user::Model)> {
+    organization_member::Entity::find()
+        .filter(organization_member::Column::OrganizationId.eq(organization_id))
+        .find_also_related(user::Entity)
+        .filter(user::Column::Email.eq(email))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find member: {}", e)))?
+        .and_then(|(member, user)| user.map(|user| (member, user)))
+        .ok_or(AppError::NotFound("Member not found".to_string()))
+}
+
+/// An organization always keeps at least one owner
+async fn ensure_other_owner(
+    database_connection: &DatabaseConnection,
+    member: &organization_member::Model,
+) -> AppResult<()> {
+    if member.role != OrganizationRole::Owner {
+        return Ok(());
+    }
+
+    let other_owners = organization_member::Entity::find()
+        .filter(organization_member::Column::OrganizationId.eq(member.organization_id))
+        .filter(organization_member::Column::Role.eq(OrganizationRole::Owner))
+        .filter(organization_member::Column::Id.ne(member.id))
+        .count(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count owners: {}", e)))?;
+
+    if other_owners == 0 {
+        return Err(AppError::Conflict(
+            "An organization needs at least one owner".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn create_organization(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: CreateOrganizationRequest,
+) -> AppResult<GraphqlResponse<OrganizationResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let public_key = user::Entity::find_by_id(user_id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .and_then(|user| user.public_key)
+        .ok_or(AppError::Crypto("Public key is missing".to_string()))?;
+
+    let name = request.name.trim();
+    let organization_key = generate_dek();
+    let organization_id = Uuid::new_v4();
+
+    let organization_model = organization::ActiveModel {
+        id: Set(organization_id),
+        encrypted_name: Set(encrypt_password(name, &organization_key)?),
+        ..Default::default()
+    };
+
+    let member_model = organization_member::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        organization_id: Set(organization_id),
+        user_id: Set(user_id),
+        role: Set(OrganizationRole::Owner),
+        wrapped_organization_key: Set(seal_item_key(&organization_key, &public_key)?),
+        ..Default::default()
+    };
+
+    let organization = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let organization = organization_model.insert(txn).await?;
+                member_model.insert(txn).await?;
+
+                Ok(organization)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to save organization: {}", e))
+        })?;
+
+    Ok(GraphqlResponse::<OrganizationResponse> {
+        success: true,
+        message: "Organization created successfully".to_string(),
+        data: OrganizationResponse {
+            id: organization.id,
+            name: name.to_string(),
+            role: OrganizationRole::Owner,
+            members: vec![OrganizationMemberResponse {
+                user_id,
+                email: user_redis_session.email.to_string(),
+                role: OrganizationRole::Owner,
+            }],
+            collections: vec![],
+            created_at: organization.created_at,
+        },
+    })
+}
+
+pub async fn get_organizations(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<OrganizationsResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    let organization_ids = access.organization_ids();
+
+    let organizations = organization::Entity::find()
+        .filter(organization::Column::Id.is_in(organization_ids.clone()))
+        .order_by(organization::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get organizations: {}", e)))?;
+
+    let mut members_by_organization: HashMap<Uuid, Vec<OrganizationMemberResponse>> =
+        HashMap::new();
+
+    for (member, user) in organization_member::Entity::find()
+        .filter(organization_member::Column::OrganizationId.is_in(organization_ids.clone()))
+        .find_also_related(user::Entity)
+        .order_by(organization_member::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get members: {}", e)))?
+    {
+        members_by_organization
+            .entry(member.organization_id)
+            .or_default()
+            .push(OrganizationMemberResponse {
+                user_id: member.user_id,
+                email: user.map(|user| user.email).unwrap_or_default(),
+                role: member.role,
+            });
+    }
+
+    let mut collections_by_organization: HashMap<Uuid, Vec<CollectionResponse>> = HashMap::new();
+
+    for collection in collection::Entity::find()
+        .filter(collection::Column::OrganizationId.is_in(organization_ids))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get collections: {}", e)))?
+    {
+        let organization_key = access.organization_key(collection.organization_id)?;
+
+        collections_by_organization
+            .entry(collection.organization_id)
+            .or_default()
+            .push(to_collection_response(&collection, &organization_key)?);
+    }
+
+    let mut organizations_response = vec![];
+
+    for organization in organizations {
+        let mut collections = collections_by_organization
+            .remove(&organization.id)
+            .unwrap_or_default();
+        collections.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+        organizations_response.push(OrganizationResponse {
+            id: organization.id,
+            name: decrypt_password(
+                &organization.encrypted_name,
+                &access.organization_key(organization.id)?,
+            )?,
+            role: access.role(organization.id)?,
+            members: members_by_organization
+                .remove(&organization.id)
+                .unwrap_or_default(),
+            collections,
+            created_at: organization.created_at,
+        });
+    }
+
+    Ok(GraphqlResponse::<OrganizationsResponse> {
+        success: true,
+        message: "Organizations found".to_string(),
+        data: OrganizationsResponse {
+            organizations: organizations_response,
+        },
+    })
+}
+
+pub async fn add_organization_member(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: AddOrganizationMemberRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    access.require_role(request.organization_id, |role| {
+        role.can_manage_role(&request.role)
+    })?;
+
+    let new_member = user::Entity::find()
+        .filter(user::Column::Email.eq(&request.email))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User Not Found".to_string()))?;
+
+    let public_key = new_member.public_key.as_ref().ok_or(AppError::Conflict(
+        "User has not set up sharing yet".to_string(),
+    ))?;
+
+    let existing_member = organization_member::Entity::find()
+        .filter(organization_member::Column::OrganizationId.eq(request.organization_id))
+        .filter(organization_member::Column::UserId.eq(new_member.id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find member: {}", e)))?;
+
+    if existing_member.is_some() {
+        return Err(AppError::Conflict(
+            "User is already a member of this organization".to_string(),
+        ));
+    }
+
+    let organization_key = access.organization_key(request.organization_id)?;
+
+    organization_member::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        organization_id: Set(request.organization_id),
+        user_id: Set(new_member.id),
+        role: Set(request.role),
+        wrapped_organization_key: Set(seal_item_key(&organization_key, public_key)?),
+        ..Default::default()
+    }
+    .insert(database_connection.as_ref())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to save member: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Member added successfully".to_string(),
+    })
+}
+
+pub async fn update_organization_member(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UpdateOrganizationMemberRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    let (member, _) =
+        find_member(database_connection, request.organization_id, &request.email).await?;
+
+    access.require_role(request.organization_id, |role| {
+        role.can_manage_role(&member.role) && role.can_manage_role(&request.role)
+    })?;
+
+    if request.role != OrganizationRole::Owner {
+        ensure_other_owner(database_connection, &member).await?;
+    }
+
+    let mut updated_member: organization_member::ActiveModel = member.into();
+    updated_member.role = Set(request.role);
+    updated_member.updated_at = Set(Utc::now());
+
+    updated_member
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update member: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Member updated successfully".to_string(),
+    })
+}
+
+/// Re-encrypt the name of a collection from one organization key to another
+pub fn rekey_collection(
+    collection: collection::Model,
+    old_key: &[u8; 32],
+    new_key: &[u8; 32],
+) -> AppResult<collection::ActiveModel> {
+    let encrypted_name = encrypt_password(
+        &decrypt_password(&collection.encrypted_name, old_key)?,
+        new_key,
+    )?;
+
+    let mut rekeyed_collection: collection::ActiveModel = collection.into();
+    rekeyed_collection.encrypted_name = Set(encrypted_name);
+
+    Ok(rekeyed_collection)
+}
+
+/// Seal a new organization key to each of the members that stay
+pub fn reseal_members(
+    remaining_members: Vec<(organization_member::Model, Option<user::Model>)>,
+    new_key: &[u8; 32],
+) -> AppResult<Vec<organization_member::ActiveModel>> {
+    remaining_members
+        .into_iter()
+        .map(|(remaining_member, remaining_user)| {
+            let public_key = remaining_user
+                .and_then(|remaining_user| remaining_user.public_key)
+                .ok_or(AppError::Crypto("Member public key is missing".to_string()))?;
+
+            let wrapped_organization_key = seal_item_key(new_key, &public_key)?;
+
+            let mut resealed_member: organization_member::ActiveModel = remaining_member.into();
+            resealed_member.wrapped_organization_key = Set(wrapped_organization_key);
+
+            Ok(resealed_member)
+        })
+        .collect()
+}
+
+pub async fn remove_organization_member(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RemoveOrganizationMemberRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    let organization_id = request.organization_id;
+
+    let (member, _) = find_member(database_connection, organization_id, &request.email).await?;
+
+    // anyone may leave, removing someone else needs a role that manages theirs
+    if member.user_id != user_id {
+        access.require_role(organization_id, |role| role.can_manage_role(&member.role))?;
+    } else {
+        access.role(organization_id)?;
+    }
+
+    ensure_other_owner(database_connection, &member).await?;
+
+    // everything the removed member could read moves to a new organization key
+    let old_key = access.organization_key(organization_id)?;
+    let new_key = generate_dek();
+    let removed_user_id = member.user_id;
+
+    // rows are read locked in the transaction, so concurrent edits wait for the new key
+    let affected_user_ids = database_connection
+        .transaction::<_, Vec<(Uuid, i64)>, AppError>(move |txn| {
+            Box::pin(async move {
+                let organization = organization::Entity::find_by_id(organization_id)
+                    .lock_exclusive()
+                    .one(txn)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to find organization: {}", e)))?
+                    .ok_or(AppError::NotFound("Organization not found".to_string()))?;
+
+                let encrypted_name = encrypt_password(
+                    &decrypt_password(&organization.encrypted_name, &old_key)?,
+                    &new_key,
+                )?;
+                let mut rekeyed_organization: organization::ActiveModel = organization.into();
+                rekeyed_organization.encrypted_name = Set(encrypted_name);
+                rekeyed_organization.updated_at = Set(Utc::now());
+
+                let collections = collection::Entity::find()
+                    .filter(collection::Column::OrganizationId.eq(organization_id))
+                    .lock_exclusive()
+                    .all(txn)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to get collections: {}", e)))?;
+                let collection_ids: Vec<Uuid> = collections.iter().map(|c| c.id).collect();
+
+                let rekeyed_collections = collections
+                    .into_iter()
+                    .map(|collection| rekey_collection(collection, &old_key, &new_key))
+                    .collect::<AppResult<Vec<_>>>()?;
+
+                let password_entries = password::Entity::find()
+                    .filter(password::Column::CollectionId.is_in(collection_ids))
+                    .lock_exclusive()
+                    .all(txn)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+                let password_ids: Vec<Uuid> = password_entries.iter().map(|p| p.id).collect();
+
+                let mut custom_fields_by_password: HashMap<Uuid, Vec<custom_field::Model>> =
+                    HashMap::new();
+
+                for custom_field in custom_field::Entity::find()
+                    .filter(custom_field::Column::PasswordId.is_in(password_ids.clone()))
+                    .lock_exclusive()
+                    .all(txn)
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(format!("Failed to get custom fields: {}", e))
+                    })?
+                {
+                    custom_fields_by_password
+                        .entry(custom_field.password_id)
+                        .or_default()
+                        .push(custom_field);
+                }
+
+                let mut rekeyed_passwords: Vec<password::ActiveModel> = vec![];
+                let mut rekeyed_custom_fields: Vec<custom_field::ActiveModel> = vec![];
+
+                for password_entry in password_entries {
+                    let custom_fields = custom_fields_by_password
+                        .remove(&password_entry.id)
+                        .unwrap_or_default();
+
+                    let (rekeyed_password, custom_fields) =
+                        rekey_entry(password_entry, custom_fields, &old_key, &new_key)?;
+
+                    rekeyed_passwords.push(rekeyed_password);
+                    rekeyed_custom_fields.extend(custom_fields);
+                }
+
+                let rekeyed_attachments = attachment::Entity::find()
+                    .filter(attachment::Column::PasswordId.is_in(password_ids))
+                    .lock_exclusive()
+                    .all(txn)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to get attachments: {}", e)))?
+                    .into_iter()
+                    .map(|attachment| rekey_attachment(attachment, &old_key, &new_key))
+                    .collect::<AppResult<Vec<attachment::ActiveModel>>>()?;
+
+                let remaining_members = organization_member::Entity::find()
+                    .filter(organization_member::Column::OrganizationId.eq(organization_id))
+                    .filter(organization_member::Column::Id.ne(member.id))
+                    .find_also_related(user::Entity)
+                    .all(txn)
+                    .await
+                    .map_err(|e| AppError::Internal(format!("Failed to get members: {}", e)))?;
+
+                let mut affected_user_ids: Vec<Uuid> = remaining_members
+                    .iter()
+                    .map(|(remaining_member, _)| remaining_member.user_id)
+                    .collect();
+                affected_user_ids.push(removed_user_id);
+
+                let resealed_members = reseal_members(remaining_members, &new_key)?;
+
+                let database_error =
+                    |e: DbErr| AppError::Internal(format!("Failed to remove member: {}", e));
+
+                organization_member::Entity::delete_by_id(member.id)
+                    .exec(txn)
+                    .await
+                    .map_err(database_error)?;
+
+                rekeyed_organization
+                    .update(txn)
+                    .await
+                    .map_err(database_error)?;
+
+                for rekeyed_collection in rekeyed_collections {
+                    rekeyed_collection
+                        .update(txn)
+                        .await
+                        .map_err(database_error)?;
+                }
+
+                for rekeyed_password in rekeyed_passwords {
+                    update_rekeyed_entry(txn, rekeyed_password)
+                        .await
+                        .map_err(database_error)?;
+                }
+
+                for rekeyed_custom_field in rekeyed_custom_fields {
+                    rekeyed_custom_field
+                        .update(txn)
+                        .await
+                        .map_err(database_error)?;
+                }
+
+                for rekeyed_attachment in rekeyed_attachments {
+                    rekeyed_attachment
+                        .update(txn)
+                        .await
+                        .map_err(database_error)?;
+                }
+
+                for resealed_member in resealed_members {
+                    resealed_member.update(txn).await.map_err(database_error)?;
+                }
+
+                // the collections of everyone involved changed, clients refetch them
+                let mut revisions = vec![];
+
+                for affected_user_id in affected_user_ids {
+                    let revision = next_revision(txn, affected_user_id)
+                        .await
+                        .map_err(database_error)?;
+
+                    revisions.push((affected_user_id, revision));
+                }
+
+                Ok(revisions)
+            })
+        })
+        .await
+        .map_err(|e| match e {
+            TransactionError::Connection(e) => {
+                AppError::Internal(format!("Failed to remove member: {}", e))
+            }
+            TransactionError::Transaction(e) => e,
+        })?;
+
+    for (affected_user_id, revision) in affected_user_ids {
+        publish_vault_changed(
+            app_state,
+            affected_user_id,
+            None,
+            VaultChangeType::Bulk,
+            revision,
+        );
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Member removed successfully".to_string(),
+    })
+}
+
+pub async fn create_collection(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: CreateCollectionRequest,
+) -> AppResult<GraphqlResponse<CollectionResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    access.require_role(
+        request.organization_id,
+        OrganizationRole::can_manage_collections,
+    )?;
+
+    let organization_key = access.organization_key(request.organization_id)?;
+
+    let collection = collection::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        organization_id: Set(request.organization_id),
+        encrypted_name: Set(encrypt_password(request.name.trim(), &organization_key)?),
+        ..Default::default()
+    }
+    .insert(database_connection.as_ref())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to save collection: {}", e)))?;
+
+    Ok(GraphqlResponse::<CollectionResponse> {
+        success: true,
+        message: "Collection created successfully".to_string(),
+        data: to_collection_response(&collection, &organization_key)?,
+    })
+}
+
+pub async fn rename_collection(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RenameCollectionRequest,
+) -> AppResult<GraphqlResponse<CollectionResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    let organization_id = access.collection_organization(request.id)?;
+    access.require_role(organization_id, OrganizationRole::can_manage_collections)?;
+
+    let organization_key = access.organization_key(organization_id)?;
+
+    let collection = collection::Entity::find_by_id(request.id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find collection: {}", e)))?
+        .ok_or(AppError::NotFound("Collection not found".to_string()))?;
+
+    let mut updated_collection: collection::ActiveModel = collection.into();
+    updated_collection.encrypted_name =
+        Set(encrypt_password(request.name.trim(), &organization_key)?);
+    updated_collection.updated_at = Set(Utc::now());
+
+    let collection = updated_collection
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update collection: {}", e)))?;
+
+    Ok(GraphqlResponse::<CollectionResponse> {
+        success: true,
+        message: "Collection renamed successfully".to_string(),
+        data: to_collection_response(&collection, &organization_key)?,
+    })
+}
+
+pub async fn delete_collection(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeleteCollectionRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+    let organization_id = access.collection_organization(request.id)?;
+    access.require_role(organization_id, OrganizationRole::can_manage_collections)?;
+
+    let entries = password::Entity::find()
+        .filter(password::Column::CollectionId.eq(request.id))
+        .count(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to count passwords: {}", e)))?;
+
+    if entries > 0 {
+        return Err(AppError::Conflict(
+            "Collection still has entries".to_string(),
+        ));
+    }
+
+    collection::Entity::delete_by_id(request.id)
+        .exec(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to delete collection: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Collection deleted successfully".to_string(),
+    })
+}
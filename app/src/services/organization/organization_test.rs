@@ -0,0 +1,177 @@
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use sea_orm::TryIntoModel;
+    use uuid::Uuid;
+
+    use crate::{
+        models::{
+            collection,
+            organization_member::{self, OrganizationRole},
+            user,
+        },
+        services::{
+            crypto::{
+                decrypt_password, encrypt_password, generate_dek, generate_key_pair, open_item_key,
+                seal_item_key,
+            },
+            organization::*,
+        },
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_organization_roles() -> AppResult<()> {
+        use OrganizationRole::*;
+
+        if Viewer.can_edit_entries() || !Member.can_edit_entries() {
+            return Err(AppError::Internal(
+                "Only viewers should be unable to edit entries".to_string(),
+            ));
+        }
+
+        if Member.can_manage_collections() || !Admin.can_manage_collections() {
+            return Err(AppError::Internal(
+                "Only owners and admins should manage collections".to_string(),
+            ));
+        }
+
+        if Admin.can_manage_role(&Owner) || Admin.can_manage_role(&Admin) {
+            return Err(AppError::Internal(
+                "Admins should not manage owners or other admins".to_string(),
+            ));
+        }
+
+        if !Admin.can_manage_role(&Viewer) || !Owner.can_manage_role(&Owner) {
+            return Err(AppError::Internal(
+                "Role management should follow the role hierarchy".to_string(),
+            ));
+        }
+
+        if Member.can_manage_role(&Viewer) {
+            return Err(AppError::Internal(
+                "Members should not manage anyone".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collection_response() -> AppResult<()> {
+        let organization_key = generate_dek();
+        let collection = collection::Model {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            encrypted_name: encrypt_password("Engineering", &organization_key)?,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let response = to_collection_response(&collection, &organization_key)?;
+
+        if response.name != "Engineering" || response.organization_id != collection.organization_id
+        {
+            return Err(AppError::Internal(
+                "Collection name should decrypt with the organization key".to_string(),
+            ));
+        }
+
+        if to_collection_response(&collection, &generate_dek()).is_ok() {
+            return Err(AppError::Internal(
+                "Collection name should not decrypt with another key".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_member_rekeying() -> AppResult<()> {
+        let organization_id = Uuid::new_v4();
+        let old_key = generate_dek();
+        let new_key = generate_dek();
+
+        let removed_dek = generate_dek();
+        let (removed_public_key, removed_private_key) = generate_key_pair(&removed_dek)?;
+        let remaining_dek = generate_dek();
+        let (remaining_public_key, remaining_private_key) = generate_key_pair(&remaining_dek)?;
+
+        let removed_wrapped_key = seal_item_key(&old_key, &removed_public_key)?;
+
+        let collection = collection::Model {
+            id: Uuid::new_v4(),
+            organization_id,
+            encrypted_name: encrypt_password("Engineering", &old_key)?,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let rekeyed_collection = rekey_collection(collection, &old_key, &new_key)?
+            .try_into_model()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // the removed member keeps the old key, which no longer opens anything
+        let removed_key = open_item_key(&removed_wrapped_key, &removed_private_key, &removed_dek)?;
+
+        if decrypt_password(&rekeyed_collection.encrypted_name, &removed_key).is_ok() {
+            return Err(AppError::Internal(
+                "Removed member can still read the organization".to_string(),
+            ));
+        }
+
+        let remaining_member = organization_member::Model {
+            id: Uuid::new_v4(),
+            organization_id,
+            user_id: Uuid::new_v4(),
+            role: OrganizationRole::Member,
+            wrapped_organization_key: seal_item_key(&old_key, &remaining_public_key)?,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let remaining_user = user::Model {
+            id: remaining_member.user_id,
+            email: "member@example.com".to_string(),
+            master_password_hash: String::new(),
+            encrypted_dek: String::new(),
+            public_key: Some(remaining_public_key),
+            encrypted_private_key: Some(remaining_private_key.clone()),
+            revision: 0,
+            session_idle_minutes: None,
+            session_max_lifetime_minutes: None,
+            email_verified_at: None,
+            deletion_requested_at: None,
+            deletion_scheduled_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let resealed_member = reseal_members(
+            vec![(remaining_member.clone(), Some(remaining_user))],
+            &new_key,
+        )?
+        .remove(0)
+        .try_into_model()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let remaining_key = open_item_key(
+            &resealed_member.wrapped_organization_key,
+            &remaining_private_key,
+            &remaining_dek,
+        )?;
+
+        if decrypt_password(&rekeyed_collection.encrypted_name, &remaining_key)? != "Engineering" {
+            return Err(AppError::Internal(
+                "Remaining member cannot read the organization".to_string(),
+            ));
+        }
+
+        if reseal_members(vec![(remaining_member, None)], &new_key).is_ok() {
+            return Err(AppError::Internal(
+                "Member was resealed without a public key".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use async_graphql::Context;
 use chrono::{DateTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, Set,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionError, TransactionTrait,
+    sea_query::{Expr, Query},
 };
+use uuid::Uuid;
 
 use crate::{
     dtos::{
@@ -12,17 +16,314 @@ use crate::{
         response::{GraphqlGenericResponse, GraphqlResponse},
     },
     models::{
-        password,
+        attachment_dtos::AttachmentResponse,
+        custom_field,
+        event_dtos::VaultChangeType,
+        organization_member::OrganizationRole,
+        password::{self, VaultItemType},
         password_dtos::{
-            AddPasswordRequest, DeletePasswordRequest, GetPasswordRequest, GetPasswordsRequest,
-            PasswordResponse, PasswordsPageResponse, UpdatePasswordRequest,
+            AddPasswordRequest, ChangesSinceRequest, ChangesSinceResponse,
+            CredentialsForUrlRequest, CredentialsForUrlResponse, DeletePasswordRequest,
+            DuplicateCluster, DuplicateClustersResponse, GetPasswordRequest, GetPasswordsRequest,
+            MovePasswordsRequest, PasswordResponse, PasswordUriInput, PasswordUriResponse,
+            PasswordsPageResponse, PurgePasswordRequest, RestorePasswordRequest,
+            UpdatePasswordRequest,
         },
+        password_tag, password_tombstone, password_uri, tag,
+        tag_dtos::TagResponse,
+        user,
         user_dtos::UserRedisSession,
     },
-    services::crypto::{decrypt_password, encrypt_password},
+    services::{
+        attachment::{
+            delete_attachment_blobs, find_attachments, find_entry_attachments,
+            to_attachment_responses,
+        },
+        crypto::{blind_index, decrypt_password, encrypt_password},
+        custom_field::{build_custom_fields, find_custom_fields, to_custom_field_responses},
+        events::publish_vault_changed,
+        folder::find_folder,
+        organization::OrganizationAccess,
+        share::entry_key,
+        tag::{build_password_tags, find_password_tags, to_tag_response},
+        uri::{UriMatcher, normalize_url, parse_url},
+    },
     utils::error::{AppError, AppResult},
 };
 
+pub fn build_password_uris(
+    password_id: Uuid,
+    user_id: Uuid,
+    uris: Vec<PasswordUriInput>,
+) -> Vec<password_uri::ActiveModel> {
+    uris.into_iter()
+        .enumerate()
+        .map(|(position, uri)| password_uri::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            password_id: Set(password_id),
+            user_id: Set(user_id),
+            uri: Set(uri.uri.trim().to_string()),
+            match_type: Set(uri.match_type.unwrap_or_default()),
+            position: Set(position as i32),
+            ..Default::default()
+        })
+        .collect()
+}
+
+pub async fn find_password_uris(
+    database_connection: &DatabaseConnection,
+    password_ids: Vec<Uuid>,
+) -> AppResult<HashMap<Uuid, Vec<password_uri::Model>>> {
+    let uris = password_uri::Entity::find()
+        .filter(password_uri::Column::PasswordId.is_in(password_ids))
+        .order_by(password_uri::Column::Position, Order::Asc)
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get password uris: {}", e)))?;
+
+    let mut uris_by_password: HashMap<Uuid, Vec<password_uri::Model>> = HashMap::new();
+
+    for uri in uris {
+        uris_by_password
+            .entry(uri.password_id)
+            .or_default()
+            .push(uri);
+    }
+
+    Ok(uris_by_password)
+}
+
+fn to_password_response(
+    password_entry: &password::Model,
+    uris: Vec<password_uri::Model>,
+    custom_fields: Vec<custom_field::Model>,
+    tags: Vec<TagResponse>,
+    attachments: Vec<AttachmentResponse>,
+    entry_key: &[u8; 32],
+) -> AppResult<PasswordResponse> {
+    let password = password_entry
+        .encrypted_password
+        .as_ref()
+        .map(|p| decrypt_password(p, entry_key))
+        .transpose()?
+        .unwrap_or_default();
+    let email = password_entry
+        .encrypted_email
+        .as_ref()
+        .map(|e| decrypt_password(e, entry_key))
+        .transpose()?;
+    let username = password_entry
+        .encrypted_username
+        .as_ref()
+        .map(|u| decrypt_password(u, entry_key))
+        .transpose()?;
+
+    Ok(PasswordResponse {
+        id: password_entry.id,
+        version: password_entry.version,
+        website_url: password_entry.website_url.clone(),
+        canonical_website_url: password_entry.canonical_website_url.clone(),
+        app_name: password_entry.app_name.clone(),
+        email,
+        username,
+        password,
+        uris: uris
+            .into_iter()
+            .map(|uri| PasswordUriResponse {
+                id: uri.id,
+                uri: uri.uri,
+                match_type: uri.match_type,
+            })
+            .collect(),
+        custom_fields: to_custom_field_responses(custom_fields, entry_key)?,
+        folder_id: password_entry.folder_id,
+        collection_id: password_entry.collection_id,
+        favorite: password_entry.favorite,
+        trashed: password_entry.is_deleted,
+        tags,
+        attachments,
+        created_at: password_entry.created_at,
+        updated_at: password_entry.updated_at,
+    })
+}
+
+/// The error for an update based on an outdated version, with the entry as it is stored now
+async fn version_conflict(
+    database_connection: &DatabaseConnection,
+    access: &OrganizationAccess,
+    password_entry: password::Model,
+    dek: &[u8; 32],
+) -> AppResult<AppError> {
+    let current = to_password_responses(database_connection, access, &[password_entry], dek)
+        .await?
+        .pop()
+        .ok_or(AppError::NotFound("Password not found".to_string()))?;
+
+    let current = serde_json::to_value(current)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize password: {}", e)))?;
+
+    Ok(AppError::VersionConflict {
+        message: "Password was changed since this version".to_string(),
+        current,
+    })
+}
+
+/// Decrypt entries together with their uris, custom fields, tags and attachments
+async fn to_password_responses(
+    database_connection: &DatabaseConnection,
+    access: &OrganizationAccess,
+    password_entries: &[password::Model],
+    dek: &[u8; 32],
+) -> AppResult<Vec<PasswordResponse>> {
+    let password_ids: Vec<Uuid> = password_entries.iter().map(|p| p.id).collect();
+
+    let mut uris_by_password =
+        find_password_uris(database_connection, password_ids.clone()).await?;
+    let mut custom_fields_by_password =
+        find_custom_fields(database_connection, password_ids.clone()).await?;
+    let mut tags_by_password =
+        find_password_tags(database_connection, access.user_id(), password_ids.clone()).await?;
+    let mut attachments_by_password = find_attachments(database_connection, password_ids).await?;
+
+    password_entries
+        .iter()
+        .map(|password_entry| {
+            to_password_response(
+                password_entry,
+                uris_by_password
+                    .remove(&password_entry.id)
+                    .unwrap_or_default(),
+                custom_fields_by_password
+                    .remove(&password_entry.id)
+                    .unwrap_or_default(),
+                tags_by_password
+                    .remove(&password_entry.id)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|tag| to_tag_response(tag, dek))
+                    .collect::<AppResult<Vec<_>>>()?,
+                to_attachment_responses(
+                    attachments_by_password
+                        .remove(&password_entry.id)
+                        .unwrap_or_default(),
+                    &access.attachment_key(password_entry, dek)?,
+                )?,
+                &access.entry_key(password_entry, dek)?,
+            )
+        })
+        .collect()
+}
+
+/// Decrypt entries shared with the user, without the owner's folder, tags and attachments
+pub async fn to_shared_password_responses(
+    database_connection: &DatabaseConnection,
+    password_entries: &[password::Model],
+    item_keys: &[[u8; 32]],
+) -> AppResult<Vec<PasswordResponse>> {
+    let password_ids: Vec<Uuid> = password_entries.iter().map(|p| p.id).collect();
+
+    let mut uris_by_password =
+        find_password_uris(database_connection, password_ids.clone()).await?;
+    let mut custom_fields_by_password =
+        find_custom_fields(database_connection, password_ids).await?;
+
+    password_entries
+        .iter()
+        .zip(item_keys)
+        .map(|(password_entry, item_key)| {
+            let password_response = to_password_response(
+                password_entry,
+                uris_by_password
+                    .remove(&password_entry.id)
+                    .unwrap_or_default(),
+                custom_fields_by_password
+                    .remove(&password_entry.id)
+                    .unwrap_or_default(),
+                vec![],
+                vec![],
+                item_key,
+            )?;
+
+            Ok(PasswordResponse {
+                folder_id: None,
+                favorite: false,
+                ..password_response
+            })
+        })
+        .collect()
+}
+
+/// The account an entry logs into, email preferred over username
+pub fn account_identity(username: Option<&str>, email: Option<&str>) -> Option<String> {
+    email
+        .or(username)
+        .map(|account| account.trim().to_lowercase())
+        .filter(|account| !account.is_empty())
+}
+
+pub fn decrypted_account_identity(
+    password_entry: &password::Model,
+    entry_key: &[u8; 32],
+) -> AppResult<Option<String>> {
+    let username = password_entry
+        .encrypted_username
+        .as_ref()
+        .map(|u| decrypt_password(u, entry_key))
+        .transpose()?;
+    let email = password_entry
+        .encrypted_email
+        .as_ref()
+        .map(|e| decrypt_password(e, entry_key))
+        .transpose()?;
+
+    Ok(account_identity(username.as_deref(), email.as_deref()))
+}
+
+fn is_same_account(
+    password_entry: &password::Model,
+    account: Option<&str>,
+    account_blind_index: Option<&str>,
+    entry_key: &[u8; 32],
+) -> AppResult<bool> {
+    // entries saved before blind indexes existed are compared decrypted
+    match &password_entry.account_blind_index {
+        Some(existing_blind_index) => {
+            Ok(Some(existing_blind_index.as_str()) == account_blind_index)
+        }
+        None => Ok(decrypted_account_identity(password_entry, entry_key)?.as_deref() == account),
+    }
+}
+
+pub fn site_key(password_entry: &password::Model) -> Option<String> {
+    password_entry.website_host.clone().or_else(|| {
+        password_entry
+            .app_name
+            .as_ref()
+            .map(|app_name| app_name.trim().to_lowercase())
+    })
+}
+
+/// Bump the revision of a user, returning the revision a change is stored at
+///
+/// Called inside the transaction of the change, whose row lock on the user keeps
+/// concurrent writers in revision order until they commit.
+pub async fn next_revision<C: ConnectionTrait>(
+    connection: &C,
+    user_id: Uuid,
+) -> Result<i64, DbErr> {
+    user::Entity::update_many()
+        .col_expr(
+            user::Column::Revision,
+            Expr::col(user::Column::Revision).add(1),
+        )
+        .filter(user::Column::Id.eq(user_id))
+        .exec_with_returning(connection)
+        .await?
+        .first()
+        .map(|user| user.revision)
+        .ok_or(DbErr::RecordNotFound("User not found".to_string()))
+}
+
 pub async fn add_password(
     ctx: &Context<'_>,
     user_redis_session: &UserRedisSession,
@@ -35,58 +336,196 @@ pub async fn add_password(
     let database_connection = &app_state.database_connection;
     let user_id = user_redis_session.id;
 
-    let mut existing_password_query =
-        password::Entity::find().filter(password::Column::UserId.eq(user_id));
+    let normalized_url = request
+        .website_url
+        .as_deref()
+        .map(normalize_url)
+        .transpose()?;
+
+    let dek = user_redis_session.dek.clone();
+
+    let dek_u8_32: [u8; 32] = dek
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    // collection entries are encrypted with the organization key instead of the DEK
+    let entry_key = match request.collection_id {
+        Some(collection_id) => {
+            let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+            let organization_id = access.collection_organization(collection_id)?;
+            access.require_role(organization_id, OrganizationRole::can_edit_entries)?;
+
+            access.organization_key(organization_id)?
+        }
+        None => dek_u8_32,
+    };
+
+    let mut existing_password_query = password::Entity::find()
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(false));
+
+    existing_password_query = match request.collection_id {
+        Some(collection_id) => {
+            existing_password_query.filter(password::Column::CollectionId.eq(collection_id))
+        }
+        None => existing_password_query
+            .filter(password::Column::UserId.eq(user_id))
+            .filter(password::Column::CollectionId.is_null()),
+    };
 
-    if let Some(website_url) = &request.website_url {
+    if let Some(normalized_url) = &normalized_url {
         existing_password_query =
-            existing_password_query.filter(password::Column::WebsiteUrl.eq(website_url));
+            existing_password_query.filter(password::Column::WebsiteHost.eq(&normalized_url.host));
     } else if let Some(app_name) = &request.app_name {
         existing_password_query =
             existing_password_query.filter(password::Column::AppName.eq(app_name));
     }
 
-    let existing_password = existing_password_query
-        .one(database_connection.as_ref())
+    let existing_passwords = existing_password_query
+        .all(database_connection.as_ref())
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e.to_string())))?;
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?;
 
-    if let Some(_) = existing_password {
-        return Err(AppError::Conflict(
-            "Password already exists for this website/app".to_string(),
-        ));
-    }
-
-    let dek = user_redis_session.dek.clone();
+    // blind indexes are keyed per user, so collection entries go without one
+    let account = account_identity(request.username.as_deref(), request.email.as_deref());
+    let account_blind_index = account
+        .as_deref()
+        .filter(|_| request.collection_id.is_none())
+        .map(|account| blind_index(account, &dek_u8_32))
+        .transpose()?;
 
-    let dek_u8_32: [u8; 32] = dek
-        .try_into()
-        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+    if !request.allow_duplicate.unwrap_or(false) {
+        for existing_password in &existing_passwords {
+            if is_same_account(
+                existing_password,
+                account.as_deref(),
+                account_blind_index.as_deref(),
+                &entry_key,
+            )? {
+                return Err(AppError::Conflict(
+                    "Password already exists for this account on this website/app".to_string(),
+                ));
+            }
+        }
+    }
 
-    let encrypted_password = encrypt_password(&request.password, &dek_u8_32)?;
+    let encrypted_password = encrypt_password(&request.password, &entry_key)?;
     let encrypted_email = request
         .email
         .as_ref()
-        .map(|e| encrypt_password(e, &dek_u8_32).unwrap());
+        .map(|e| encrypt_password(e, &entry_key).unwrap());
 
     let encrypted_username = request
         .username
         .as_ref()
-        .map(|u| encrypt_password(u, &dek_u8_32).unwrap());
+        .map(|u| encrypt_password(u, &entry_key).unwrap());
+
+    let password_id = Uuid::new_v4();
+
+    // without explicit uris the entry matches on the site it was saved for
+    let uris = request.uris.unwrap_or_else(|| {
+        normalized_url
+            .iter()
+            .map(|normalized_url| PasswordUriInput {
+                uri: normalized_url.canonical.to_string(),
+                match_type: None,
+            })
+            .collect()
+    });
+    let password_uris = build_password_uris(password_id, user_id, uris);
+    let custom_fields = build_custom_fields(
+        password_id,
+        user_id,
+        request.custom_fields.unwrap_or_default(),
+        &entry_key,
+    )?;
+
+    if let Some(folder_id) = request.folder_id {
+        find_folder(database_connection, user_id, folder_id).await?;
+    }
+
+    let password_tags = build_password_tags(
+        database_connection,
+        user_id,
+        password_id,
+        request.tag_ids.unwrap_or_default(),
+    )
+    .await?;
+
+    let (canonical_website_url, website_host) = normalized_url
+        .map(|normalized_url| (normalized_url.canonical, normalized_url.host))
+        .unzip();
 
-    password::ActiveModel {
-        id: Set(uuid::Uuid::new_v4()),
+    let is_personal_entry = request.collection_id.is_none();
+
+    let mut password_model = password::ActiveModel {
+        id: Set(password_id),
         website_url: Set(request.website_url),
+        canonical_website_url: Set(canonical_website_url),
+        website_host: Set(website_host),
         app_name: Set(request.app_name),
         encrypted_email: Set(encrypted_email),
         encrypted_username: Set(encrypted_username),
-        encrypted_password: Set(encrypted_password),
+        encrypted_password: Set(Some(encrypted_password)),
+        account_blind_index: Set(account_blind_index),
+        folder_id: Set(request.folder_id),
+        collection_id: Set(request.collection_id),
+        favorite: Set(request.favorite.unwrap_or(false)),
         user_id: Set(user_id),
         ..Default::default()
+    };
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                // revisions only order the personal vault
+                let mut revision = None;
+
+                if is_personal_entry {
+                    let next_revision = next_revision(txn, user_id).await?;
+
+                    password_model.revision = Set(next_revision);
+                    password_model.created_revision = Set(next_revision);
+                    revision = Some(next_revision);
+                }
+
+                password_model.insert(txn).await?;
+
+                if !password_uris.is_empty() {
+                    password_uri::Entity::insert_many(password_uris)
+                        .exec(txn)
+                        .await?;
+                }
+
+                if !custom_fields.is_empty() {
+                    custom_field::Entity::insert_many(custom_fields)
+                        .exec(txn)
+                        .await?;
+                }
+
+                if !password_tags.is_empty() {
+                    password_tag::Entity::insert_many(password_tags)
+                        .exec(txn)
+                        .await?;
+                }
+
+                Ok(revision)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to save password: {}", e))
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(
+            app_state,
+            user_id,
+            Some(password_id),
+            VaultChangeType::Created,
+            revision,
+        );
     }
-    .insert(database_connection.as_ref())
-    .await
-    .map_err(|e| AppError::Internal(format!("Failed to save password: {}", e.to_string())))?;
 
     Ok(GraphqlGenericResponse {
         success: true,
@@ -112,36 +551,26 @@ pub async fn get_password(
         .try_into()
         .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
 
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
     let password_entry = password::Entity::find()
-        .filter(password::Column::UserId.eq(user_id))
+        .filter(access.entries_condition())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
         .filter(password::Column::Id.eq(request.id))
         .one(database_connection.as_ref())
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e.to_string())))?
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
         .ok_or(AppError::NotFound("Password not found".to_string()))?;
 
-    let encrypted_password = password_entry.encrypted_password;
-    let password = decrypt_password(&encrypted_password, &dek_u8_32)?;
-    let email = password_entry
-        .encrypted_email
-        .map(|e| decrypt_password(&e, &dek_u8_32).unwrap());
-    let username = password_entry
-        .encrypted_username
-        .map(|u| decrypt_password(&u, &dek_u8_32).unwrap());
+    let password_response =
+        to_password_responses(database_connection, &access, &[password_entry], &dek_u8_32)
+            .await?
+            .remove(0);
 
     Ok(GraphqlResponse::<PasswordResponse> {
         success: true,
         message: "Password found".to_string(),
-        data: PasswordResponse {
-            id: password_entry.id,
-            website_url: password_entry.website_url.clone(),
-            app_name: password_entry.app_name.clone(),
-            email,
-            username,
-            password,
-            created_at: password_entry.created_at,
-            updated_at: password_entry.updated_at,
-        },
+        data: password_response,
     })
 }
 
@@ -158,31 +587,89 @@ pub async fn update_password(
     let user_id = user_redis_session.id;
     let dek = user_redis_session.dek.clone();
 
+    let dek_u8_32: [u8; 32] = dek
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
     let password_entry = password::Entity::find()
-        .filter(password::Column::UserId.eq(user_id))
+        .filter(access.entries_condition())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
         .filter(password::Column::Id.eq(request.id))
         .one(database_connection.as_ref())
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e.to_string())))?
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
         .ok_or(AppError::NotFound("Password not found".to_string()))?;
 
-    let dek_u8_32: [u8; 32] = dek
-        .try_into()
-        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+    access.require_entry_edit(&password_entry)?;
+
+    if password_entry.version != request.version {
+        return Err(
+            version_conflict(database_connection, &access, password_entry, &dek_u8_32).await?,
+        );
+    }
+
+    // shared and collection entries are encrypted with their own key
+    let entry_key = access.entry_key(&password_entry, &dek_u8_32)?;
+    let is_collection_entry = password_entry.collection_id.is_some();
+    let version = request.version;
+
+    let encrypted_password = encrypt_password(&request.password, &entry_key)?;
 
-    let encrypted_password = encrypt_password(&request.password, &dek_u8_32)?;
+    // fields left out of the request keep their stored values
+    let account_username = match &request.username {
+        Some(username) => Some(username.to_string()),
+        None => password_entry
+            .encrypted_username
+            .as_ref()
+            .map(|u| decrypt_password(u, &entry_key))
+            .transpose()?,
+    };
+    let account_email = match &request.email {
+        Some(email) => Some(email.to_string()),
+        None => password_entry
+            .encrypted_email
+            .as_ref()
+            .map(|e| decrypt_password(e, &entry_key))
+            .transpose()?,
+    };
+    let owner_id = password_entry.user_id;
+
+    let account = account_identity(account_username.as_deref(), account_email.as_deref());
+    let account_blind_index = if is_collection_entry {
+        None
+    } else {
+        account
+            .as_deref()
+            .map(|account| blind_index(account, &dek_u8_32))
+            .transpose()?
+    };
+    let stored_canonical_website_url = password_entry.canonical_website_url.clone();
 
     let mut updated_password: password::ActiveModel = password_entry.into();
 
-    updated_password.encrypted_password = Set(encrypted_password);
+    updated_password.account_blind_index = Set(account_blind_index);
+
+    updated_password.encrypted_password = Set(Some(encrypted_password));
 
     let website_url = request.website_url;
     let app_name = request.app_name;
     let username = request.username;
     let email = request.email;
 
+    let mut changed_canonical_website_url = None;
+
     if let Some(website_url) = &website_url {
+        let normalized_url = normalize_url(website_url)?;
+
+        if stored_canonical_website_url.as_deref() != Some(normalized_url.canonical.as_str()) {
+            changed_canonical_website_url = Some(normalized_url.canonical.to_string());
+        }
+
         updated_password.website_url = Set(Some(website_url.to_string()));
+        updated_password.canonical_website_url = Set(Some(normalized_url.canonical));
+        updated_password.website_host = Set(Some(normalized_url.host));
     }
 
     if let Some(app_name) = &app_name {
@@ -190,31 +677,240 @@ pub async fn update_password(
     }
 
     if let Some(username) = &username {
-        let encrypted_username = encrypt_password(username, &dek_u8_32)?;
+        let encrypted_username = encrypt_password(username, &entry_key)?;
         updated_password.encrypted_username = Set(Some(encrypted_username));
     }
 
     if let Some(email) = &email {
-        let encrypted_email = encrypt_password(email, &dek_u8_32)?;
+        let encrypted_email = encrypt_password(email, &entry_key)?;
         updated_password.encrypted_email = Set(Some(encrypted_email));
     }
+
+    if let Some(favorite) = request.favorite {
+        updated_password.favorite = Set(favorite);
+    }
     updated_password.updated_at = Set(Utc::now());
 
-    updated_password
-        .update(database_connection.as_ref())
+    let password_id = request.id;
+    // a new website without explicit uris matches on that website, like a new entry
+    let uris = request.uris.or_else(|| {
+        changed_canonical_website_url.map(|canonical_website_url| {
+            vec![PasswordUriInput {
+                uri: canonical_website_url,
+                match_type: None,
+            }]
+        })
+    });
+    let password_uris = uris.map(|uris| build_password_uris(password_id, owner_id, uris));
+    let custom_fields = request
+        .custom_fields
+        .map(|custom_fields| build_custom_fields(password_id, owner_id, custom_fields, &entry_key))
+        .transpose()?;
+    let password_tags = match request.tag_ids {
+        Some(tag_ids) => {
+            Some(build_password_tags(database_connection, user_id, password_id, tag_ids).await?)
+        }
+        None => None,
+    };
+
+    let update_result: Result<Option<i64>, TransactionError<DbErr>> = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let mut revision = None;
+
+                if !is_collection_entry {
+                    let next_revision = next_revision(txn, owner_id).await?;
+
+                    updated_password.revision = Set(next_revision);
+                    revision = Some(next_revision);
+                }
+
+                // fails when another update landed since the version was checked
+                updated_password.version = Set(version + 1);
+                password::Entity::update(updated_password)
+                    .filter(password::Column::Version.eq(version))
+                    .exec(txn)
+                    .await?;
+
+                // uris are replaced as a whole when given
+                if let Some(password_uris) = password_uris {
+                    password_uri::Entity::delete_many()
+                        .filter(password_uri::Column::PasswordId.eq(password_id))
+                        .exec(txn)
+                        .await?;
+
+                    if !password_uris.is_empty() {
+                        password_uri::Entity::insert_many(password_uris)
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                // custom fields keep the order they are given in
+                if let Some(custom_fields) = custom_fields {
+                    custom_field::Entity::delete_many()
+                        .filter(custom_field::Column::PasswordId.eq(password_id))
+                        .exec(txn)
+                        .await?;
+
+                    if !custom_fields.is_empty() {
+                        custom_field::Entity::insert_many(custom_fields)
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                // only the user's own tags are replaced, collection members tag separately
+                if let Some(password_tags) = password_tags {
+                    password_tag::Entity::delete_many()
+                        .filter(password_tag::Column::PasswordId.eq(password_id))
+                        .filter(
+                            password_tag::Column::TagId.in_subquery(
+                                Query::select()
+                                    .column(tag::Column::Id)
+                                    .from(tag::Entity)
+                                    .and_where(tag::Column::UserId.eq(user_id))
+                                    .to_owned(),
+                            ),
+                        )
+                        .exec(txn)
+                        .await?;
+
+                    if !password_tags.is_empty() {
+                        password_tag::Entity::insert_many(password_tags)
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                Ok(revision)
+            })
+        })
+        .await;
+
+    match update_result {
+        Ok(revision) => {
+            if let Some(revision) = revision {
+                publish_vault_changed(
+                    app_state,
+                    owner_id,
+                    Some(password_id),
+                    VaultChangeType::Updated,
+                    revision,
+                );
+            }
+
+            Ok(GraphqlGenericResponse {
+                success: true,
+                message: "Password updated successfully".to_string(),
+            })
+        }
+        Err(TransactionError::Transaction(DbErr::RecordNotUpdated)) => {
+            let password_entry = password::Entity::find()
+                .filter(password::Column::Id.eq(password_id))
+                .one(database_connection.as_ref())
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+                .ok_or(AppError::NotFound("Password not found".to_string()))?;
+
+            Err(version_conflict(database_connection, &access, password_entry, &dek_u8_32).await?)
+        }
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to update password: {}",
+            e
+        ))),
+    }
+}
+
+/// Move an entry to the trash, from where it can be restored or purged
+pub async fn delete_password(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeletePasswordRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let Some(password_entry) = password::Entity::find()
+        .filter(access.entries_condition())
+        .filter(password::Column::Id.eq(request.id))
+        .filter(password::Column::IsDeleted.eq(false))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+    else {
+        return Ok(GraphqlGenericResponse {
+            success: true,
+            message: "Password moved to trash".to_string(),
+        });
+    };
+
+    access.require_entry_edit(&password_entry)?;
+
+    let owner_id = password_entry.user_id;
+    let password_id = password_entry.id;
+    let is_personal_entry = password_entry.collection_id.is_none();
+
+    // the entry keeps its folder, restoring puts it back there
+    let mut trashed_password: password::ActiveModel = password_entry.into();
+    trashed_password.is_deleted = Set(true);
+    trashed_password.updated_at = Set(Utc::now());
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let mut revision = None;
+
+                if is_personal_entry {
+                    let next_revision = next_revision(txn, owner_id).await?;
+
+                    trashed_password.revision = Set(next_revision);
+                    revision = Some(next_revision);
+                }
+
+                trashed_password.update(txn).await?;
+
+                Ok(revision)
+            })
+        })
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to update password: {}", e.to_string())))?;
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to delete password: {}", e))
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(
+            app_state,
+            owner_id,
+            Some(password_id),
+            VaultChangeType::Updated,
+            revision,
+        );
+    }
 
     Ok(GraphqlGenericResponse {
         success: true,
-        message: "Password updated successfully".to_string(),
+        message: "Password moved to trash".to_string(),
     })
 }
 
-pub async fn delete_password(
+/// Delete an entry in the trash for good, with its attachments
+pub async fn purge_password(
     ctx: &Context<'_>,
     user_redis_session: &UserRedisSession,
-    request: DeletePasswordRequest,
+    request: PurgePasswordRequest,
 ) -> AppResult<GraphqlGenericResponse> {
     let app_state = ctx
         .data::<Arc<AppState>>()
@@ -223,16 +919,220 @@ pub async fn delete_password(
     let database_connection = &app_state.database_connection;
     let user_id = user_redis_session.id;
 
-    password::Entity::delete_many()
-        .filter(password::Column::UserId.eq(user_id))
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let password_entry = password::Entity::find()
+        .filter(access.entries_condition())
+        .filter(password::Column::Id.eq(request.id))
+        .filter(password::Column::IsDeleted.eq(true))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+        .ok_or(AppError::NotFound(
+            "Password not found in trash".to_string(),
+        ))?;
+
+    access.require_entry_edit(&password_entry)?;
+
+    let attachments = find_entry_attachments(database_connection, password_entry.id).await?;
+
+    let owner_id = password_entry.user_id;
+    let password_id = password_entry.id;
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                password::Entity::delete_by_id(password_entry.id)
+                    .exec(txn)
+                    .await?;
+
+                // clients drop deleted entries from their cache through the tombstone
+                if password_entry.collection_id.is_none() {
+                    let revision = next_revision(txn, password_entry.user_id).await?;
+
+                    password_tombstone::ActiveModel {
+                        id: Set(Uuid::new_v4()),
+                        user_id: Set(password_entry.user_id),
+                        password_id: Set(password_entry.id),
+                        revision: Set(revision),
+                        ..Default::default()
+                    }
+                    .insert(txn)
+                    .await?;
+
+                    return Ok(Some(revision));
+                }
+
+                Ok(None)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to purge password: {}", e))
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(
+            app_state,
+            owner_id,
+            Some(password_id),
+            VaultChangeType::Deleted,
+            revision,
+        );
+    }
+
+    delete_attachment_blobs(app_state.blob_store.as_ref(), &attachments).await?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Password deleted permanently".to_string(),
+    })
+}
+
+pub async fn restore_password(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RestorePasswordRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let password_entry = password::Entity::find()
+        .filter(access.entries_condition())
         .filter(password::Column::Id.eq(request.id))
-        .exec(database_connection.as_ref())
+        .filter(password::Column::IsDeleted.eq(true))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+        .ok_or(AppError::NotFound(
+            "Password not found in trash".to_string(),
+        ))?;
+
+    access.require_entry_edit(&password_entry)?;
+
+    let owner_id = password_entry.user_id;
+    let password_id = password_entry.id;
+    let is_personal_entry = password_entry.collection_id.is_none();
+
+    let mut restored_password: password::ActiveModel = password_entry.into();
+    restored_password.is_deleted = Set(false);
+    restored_password.updated_at = Set(Utc::now());
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let mut revision = None;
+
+                if is_personal_entry {
+                    let next_revision = next_revision(txn, owner_id).await?;
+
+                    restored_password.revision = Set(next_revision);
+                    revision = Some(next_revision);
+                }
+
+                restored_password.update(txn).await?;
+
+                Ok(revision)
+            })
+        })
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to delete password: {}", e.to_string())))?;
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to restore password: {}", e))
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(
+            app_state,
+            owner_id,
+            Some(password_id),
+            VaultChangeType::Updated,
+            revision,
+        );
+    }
 
     Ok(GraphqlGenericResponse {
         success: true,
-        message: "Password deleted successfully".to_string(),
+        message: "Password restored successfully".to_string(),
+    })
+}
+
+pub async fn move_passwords(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: MovePasswordsRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    if let Some(folder_id) = request.folder_id {
+        find_folder(database_connection, user_id, folder_id).await?;
+    }
+
+    let moved_condition = Condition::all()
+        .add(password::Column::UserId.eq(user_id))
+        .add(password::Column::CollectionId.is_null())
+        .add(password::Column::Id.is_in(request.ids));
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                // a move that matches no entries leaves the vault and its revision as they are
+                let moved = password::Entity::find()
+                    .filter(moved_condition.clone())
+                    .count(txn)
+                    .await?;
+
+                if moved == 0 {
+                    return Ok(None);
+                }
+
+                let revision = next_revision(txn, user_id).await?;
+
+                password::Entity::update_many()
+                    .col_expr(password::Column::FolderId, Expr::value(request.folder_id))
+                    .col_expr(password::Column::Revision, Expr::value(revision))
+                    .col_expr(password::Column::UpdatedAt, Expr::value(Utc::now()))
+                    .filter(moved_condition)
+                    .exec(txn)
+                    .await?;
+
+                Ok(Some(revision))
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to move passwords: {}", e))
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(app_state, user_id, None, VaultChangeType::Bulk, revision);
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Passwords moved successfully".to_string(),
     })
 }
 
@@ -258,8 +1158,37 @@ pub async fn get_passwords(
         .try_into()
         .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
 
-    let mut passwords_select =
-        password::Entity::find().filter(password::Column::UserId.eq(user_id));
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let mut passwords_select = password::Entity::find()
+        .filter(access.entries_condition())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(request.trashed.unwrap_or(false)));
+
+    if let Some(folder_id) = request.folder_id {
+        passwords_select = passwords_select.filter(password::Column::FolderId.eq(folder_id));
+    }
+
+    if let Some(collection_id) = request.collection_id {
+        passwords_select =
+            passwords_select.filter(password::Column::CollectionId.eq(collection_id));
+    }
+
+    if let Some(tag_id) = request.tag_id {
+        passwords_select = passwords_select.filter(
+            password::Column::Id.in_subquery(
+                Query::select()
+                    .column(password_tag::Column::PasswordId)
+                    .from(password_tag::Entity)
+                    .and_where(password_tag::Column::TagId.eq(tag_id))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    if let Some(favorite) = request.favorite {
+        passwords_select = passwords_select.filter(password::Column::Favorite.eq(favorite));
+    }
 
     if next_page_token.is_some() {
         let updated_at_str = decrypt_password(next_page_token.unwrap().as_str(), &dek_u8_32)?;
@@ -274,7 +1203,7 @@ pub async fn get_passwords(
         .limit(PAGE_SIZE)
         .all(database_connection.as_ref())
         .await
-        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e.to_string())))?;
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
 
     if passwords.is_empty() {
         return Ok(GraphqlResponse::<PasswordsPageResponse> {
@@ -288,31 +1217,8 @@ pub async fn get_passwords(
         });
     }
 
-    let mut passwords_response: Vec<PasswordResponse> = vec![];
-
-    for password_entry in &passwords {
-        let encrypted_password = &password_entry.encrypted_password;
-        let password = decrypt_password(&encrypted_password, &dek_u8_32)?;
-        let email = password_entry
-            .encrypted_email
-            .as_ref()
-            .map(|e| decrypt_password(&e, &dek_u8_32).unwrap());
-        let username = password_entry
-            .encrypted_username
-            .as_ref()
-            .map(|u| decrypt_password(&u, &dek_u8_32).unwrap());
-
-        passwords_response.push(PasswordResponse {
-            id: password_entry.id,
-            website_url: password_entry.website_url.clone(),
-            app_name: password_entry.app_name.clone(),
-            email,
-            username,
-            password,
-            created_at: password_entry.created_at,
-            updated_at: password_entry.updated_at,
-        });
-    }
+    let passwords_response =
+        to_password_responses(database_connection, &access, &passwords, &dek_u8_32).await?;
 
     let next_page_token = passwords
         .last()
@@ -328,3 +1234,301 @@ pub async fn get_passwords(
         },
     })
 }
+
+pub async fn changes_since(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ChangesSinceRequest,
+) -> AppResult<GraphqlResponse<ChangesSinceResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+    let since = request.revision;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    // read the revision first, changes made while reading are returned again next time
+    let revision = user::Entity::find_by_id(user_id)
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find user: {}", e)))?
+        .ok_or(AppError::NotFound("User not found".to_string()))?
+        .revision;
+
+    if since > revision {
+        return Err(AppError::Validation(
+            "Revision is ahead of the vault".to_string(),
+        ));
+    }
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let mut passwords_select = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::CollectionId.is_null())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login));
+
+    // entries from before revisions were kept are only part of a full sync
+    if since > 0 {
+        passwords_select = passwords_select.filter(password::Column::Revision.gt(since));
+    }
+
+    let passwords = passwords_select
+        .order_by(password::Column::Revision, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    let password_responses =
+        to_password_responses(database_connection, &access, &passwords, &dek_u8_32).await?;
+
+    let (created, updated): (Vec<_>, Vec<_>) = passwords
+        .iter()
+        .zip(password_responses)
+        .partition(|(password_entry, _)| since == 0 || password_entry.created_revision > since);
+
+    let deleted = if since > 0 {
+        password_tombstone::Entity::find()
+            .filter(password_tombstone::Column::UserId.eq(user_id))
+            .filter(password_tombstone::Column::Revision.gt(since))
+            .order_by(password_tombstone::Column::Revision, Order::Asc)
+            .all(database_connection.as_ref())
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to get deleted passwords: {}", e)))?
+            .into_iter()
+            .map(|tombstone| tombstone.password_id)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok(GraphqlResponse::<ChangesSinceResponse> {
+        success: true,
+        message: "Changes found".to_string(),
+        data: ChangesSinceResponse {
+            revision,
+            created: created.into_iter().map(|(_, response)| response).collect(),
+            updated: updated.into_iter().map(|(_, response)| response).collect(),
+            deleted,
+        },
+    })
+}
+
+/// Fill in the normalized website of entries saved before normalization, so they
+/// compare like entries saved since
+pub async fn backfill_normalized_urls(database_connection: &DatabaseConnection) -> AppResult<u64> {
+    let password_entries = password::Entity::find()
+        .filter(password::Column::WebsiteUrl.is_not_null())
+        .filter(password::Column::CanonicalWebsiteUrl.is_null())
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find passwords: {}", e)))?;
+
+    let mut backfilled = 0;
+
+    for password_entry in password_entries {
+        // entries whose website never parsed keep matching by app name only
+        let Some(normalized_url) = password_entry
+            .website_url
+            .as_deref()
+            .and_then(|website_url| normalize_url(website_url).ok())
+        else {
+            continue;
+        };
+
+        password::Entity::update_many()
+            .col_expr(
+                password::Column::CanonicalWebsiteUrl,
+                Expr::value(normalized_url.canonical),
+            )
+            .col_expr(
+                password::Column::WebsiteHost,
+                Expr::value(normalized_url.host),
+            )
+            .filter(password::Column::Id.eq(password_entry.id))
+            .exec(database_connection)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to backfill password: {}", e)))?;
+
+        backfilled += 1;
+    }
+
+    Ok(backfilled)
+}
+
+pub async fn credentials_for_url(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: CredentialsForUrlRequest,
+) -> AppResult<GraphqlResponse<CredentialsForUrlResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let page_url = parse_url(&request.url)?;
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let uris = password_uri::Entity::find()
+        .filter(
+            password_uri::Column::PasswordId.in_subquery(
+                Query::select()
+                    .column(password::Column::Id)
+                    .from(password::Entity)
+                    .cond_where(access.entries_condition())
+                    .to_owned(),
+            ),
+        )
+        .order_by(password_uri::Column::Position, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get password uris: {}", e)))?;
+
+    let mut uri_matcher = UriMatcher::new(page_url);
+    let mut matched_password_ids: Vec<Uuid> = vec![];
+
+    for uri in uris {
+        if uri_matcher.matches(&uri.uri, &uri.match_type)
+            && !matched_password_ids.contains(&uri.password_id)
+        {
+            matched_password_ids.push(uri.password_id);
+        }
+    }
+
+    if matched_password_ids.is_empty() {
+        return Ok(GraphqlResponse::<CredentialsForUrlResponse> {
+            success: true,
+            message: "No passwords found".to_string(),
+            data: CredentialsForUrlResponse { passwords: vec![] },
+        });
+    }
+
+    let passwords = password::Entity::find()
+        .filter(access.entries_condition())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(false))
+        .filter(password::Column::Id.is_in(matched_password_ids))
+        .order_by(password::Column::UpdatedAt, Order::Desc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    let passwords_response =
+        to_password_responses(database_connection, &access, &passwords, &dek_u8_32).await?;
+
+    Ok(GraphqlResponse::<CredentialsForUrlResponse> {
+        success: true,
+        message: "Passwords found".to_string(),
+        data: CredentialsForUrlResponse {
+            passwords: passwords_response,
+        },
+    })
+}
+
+pub async fn find_duplicates(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<DuplicateClustersResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let passwords = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::CollectionId.is_null())
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::IsDeleted.eq(false))
+        .order_by(password::Column::CreatedAt, Order::Asc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get passwords: {}", e)))?;
+
+    // entries are clustered on site plus decrypted account identity
+    let mut cluster_keys: Vec<(String, String)> = vec![];
+    let mut entries_by_cluster: HashMap<(String, String), Vec<&password::Model>> = HashMap::new();
+
+    for password_entry in &passwords {
+        let (Some(site), Some(account)) = (
+            site_key(password_entry),
+            decrypted_account_identity(password_entry, &entry_key(password_entry, &dek_u8_32)?)?,
+        ) else {
+            continue;
+        };
+
+        let cluster_key = (site, account);
+
+        if !entries_by_cluster.contains_key(&cluster_key) {
+            cluster_keys.push(cluster_key.clone());
+        }
+
+        entries_by_cluster
+            .entry(cluster_key)
+            .or_default()
+            .push(password_entry);
+    }
+
+    cluster_keys.retain(|cluster_key| entries_by_cluster[cluster_key].len() > 1);
+
+    let duplicate_entries: Vec<password::Model> = cluster_keys
+        .iter()
+        .flat_map(|cluster_key| entries_by_cluster[cluster_key].iter().map(|p| (*p).clone()))
+        .collect();
+
+    let access = OrganizationAccess::load(database_connection, user_id, &dek_u8_32).await?;
+
+    let mut duplicate_responses =
+        to_password_responses(database_connection, &access, &duplicate_entries, &dek_u8_32)
+            .await?
+            .into_iter();
+
+    let mut clusters: Vec<DuplicateCluster> = vec![];
+
+    for cluster_key in cluster_keys {
+        let passwords_response = duplicate_responses
+            .by_ref()
+            .take(entries_by_cluster[&cluster_key].len())
+            .collect();
+        let (site, account) = cluster_key;
+
+        clusters.push(DuplicateCluster {
+            site,
+            account,
+            passwords: passwords_response,
+        });
+    }
+
+    Ok(GraphqlResponse::<DuplicateClustersResponse> {
+        success: true,
+        message: if clusters.is_empty() {
+            "No duplicates found".to_string()
+        } else {
+            "Duplicates found".to_string()
+        },
+        data: DuplicateClustersResponse { clusters },
+    })
+}
@@ -0,0 +1,353 @@
+#[cfg(test)]
+mod test {
+    use serde_json::{Value, json};
+
+    use crate::{
+        configs::env::Env,
+        utils::{
+            error::{AppError, AppResult},
+            test_app::{TestApp, unique_email},
+        },
+    };
+
+    const MASTER_PASSWORD: &str = "correct horse battery staple";
+
+    const CHANGES_SINCE: &str = "query($revision: Int!) {
+        changesSince(request: { revision: $revision }) {
+            data { revision created { id version trashed } updated { id version trashed } deleted }
+        }
+    }";
+
+    async fn add_password(app: &TestApp, session: &str, website_url: &str) -> AppResult<()> {
+        app.execute(
+            Some(session),
+            "mutation($websiteUrl: String!) {
+                addPassword(request: { websiteUrl: $websiteUrl, username: \"octocat\", password: \"hunter2\" }) {
+                    success
+                }
+            }",
+            json!({ "websiteUrl": website_url }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn changes_since(app: &TestApp, session: &str, revision: i64) -> AppResult<Value> {
+        let data = app
+            .execute(
+                Some(session),
+                CHANGES_SINCE,
+                json!({ "revision": revision }),
+            )
+            .await?;
+
+        Ok(data["changesSince"]["data"].clone())
+    }
+
+    async fn entry_mutation(app: &TestApp, session: &str, name: &str, id: &str) -> AppResult<()> {
+        app.execute(
+            Some(session),
+            &format!(
+                "mutation($id: UUID!) {{ {}(request: {{ id: $id }}) {{ success }} }}",
+                name
+            ),
+            json!({ "id": id }),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn ids(entries: &Value) -> Vec<String> {
+        entries
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| entry["id"].as_str().or(entry.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn revision(changes: &Value) -> AppResult<i64> {
+        changes["revision"]
+            .as_i64()
+            .ok_or(AppError::Internal("Changes have no revision".to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_trash_restore_purge() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let session = app.signup(&unique_email(), MASTER_PASSWORD).await?;
+
+        add_password(&app, &session, "https://github.com").await?;
+
+        let changes = changes_since(&app, &session, 0).await?;
+        let id = ids(&changes["created"])
+            .pop()
+            .ok_or(AppError::Internal("Added entry is not synced".to_string()))?;
+        let added_at = revision(&changes)?;
+
+        entry_mutation(&app, &session, "deletePassword", &id).await?;
+
+        let changes = changes_since(&app, &session, added_at).await?;
+        if ids(&changes["updated"]) != vec![id.clone()] || changes["updated"][0]["trashed"] != true
+        {
+            return Err(AppError::Internal(format!(
+                "Trashing should sync as an update: {}",
+                changes
+            )));
+        }
+        let trashed_at = revision(&changes)?;
+
+        entry_mutation(&app, &session, "restorePassword", &id).await?;
+
+        let changes = changes_since(&app, &session, trashed_at).await?;
+        if ids(&changes["updated"]) != vec![id.clone()] || changes["updated"][0]["trashed"] != false
+        {
+            return Err(AppError::Internal(format!(
+                "Restoring should sync as an update: {}",
+                changes
+            )));
+        }
+        let restored_at = revision(&changes)?;
+
+        if entry_mutation(&app, &session, "purgePassword", &id)
+            .await
+            .is_ok()
+        {
+            return Err(AppError::Internal(
+                "Only trashed entries should be purged".to_string(),
+            ));
+        }
+
+        entry_mutation(&app, &session, "deletePassword", &id).await?;
+        entry_mutation(&app, &session, "purgePassword", &id).await?;
+
+        let changes = changes_since(&app, &session, restored_at).await?;
+        if ids(&changes["deleted"]) != vec![id.clone()] || !ids(&changes["updated"]).is_empty() {
+            return Err(AppError::Internal(format!(
+                "Purging should sync as a tombstone: {}",
+                changes
+            )));
+        }
+
+        let changes = changes_since(&app, &session, 0).await?;
+        if !ids(&changes["created"]).is_empty() || !ids(&changes["deleted"]).is_empty() {
+            return Err(AppError::Internal(format!(
+                "A full sync should not see purged entries: {}",
+                changes
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_changes_since_deltas() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let session = app.signup(&unique_email(), MASTER_PASSWORD).await?;
+
+        add_password(&app, &session, "https://github.com").await?;
+
+        let changes = changes_since(&app, &session, 0).await?;
+        let first_id = ids(&changes["created"])
+            .pop()
+            .ok_or(AppError::Internal("Added entry is not synced".to_string()))?;
+        let first_version = changes["created"][0]["version"].clone();
+        let synced_at = revision(&changes)?;
+
+        add_password(&app, &session, "https://gitlab.com").await?;
+        app.execute(
+            Some(&session),
+            "mutation($id: UUID!, $version: Int!) {
+                updatePassword(request: {
+                    id: $id, version: $version, websiteUrl: \"https://github.com\", username: \"octocat\", password: \"hunter3\"
+                }) {
+                    success
+                }
+            }",
+            json!({ "id": first_id, "version": first_version }),
+        )
+        .await?;
+
+        let changes = changes_since(&app, &session, synced_at).await?;
+        let created = ids(&changes["created"]);
+        if created.len() != 1 || created.contains(&first_id) {
+            return Err(AppError::Internal(format!(
+                "Only the new entry should sync as created: {}",
+                changes
+            )));
+        }
+        if ids(&changes["updated"]) != vec![first_id] {
+            return Err(AppError::Internal(format!(
+                "Only the edited entry should sync as updated: {}",
+                changes
+            )));
+        }
+
+        let latest = revision(&changes)?;
+        let changes = changes_since(&app, &session, latest).await?;
+        if revision(&changes)? != latest
+            || !ids(&changes["created"]).is_empty()
+            || !ids(&changes["updated"]).is_empty()
+            || !ids(&changes["deleted"]).is_empty()
+        {
+            return Err(AppError::Internal(format!(
+                "Nothing should sync without changes: {}",
+                changes
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_password_version_conflict() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let session = app.signup(&unique_email(), MASTER_PASSWORD).await?;
+
+        add_password(&app, &session, "https://github.com").await?;
+
+        let changes = changes_since(&app, &session, 0).await?;
+        let id = ids(&changes["created"])
+            .pop()
+            .ok_or(AppError::Internal("Added entry is not synced".to_string()))?;
+        let version = changes["created"][0]["version"]
+            .as_i64()
+            .ok_or(AppError::Internal("Entry has no version".to_string()))?;
+
+        let update = |password: &'static str| {
+            app.execute(
+                Some(&session),
+                "mutation($id: UUID!, $version: Int!, $password: String!) {
+                    updatePassword(request: {
+                        id: $id, version: $version, websiteUrl: \"https://github.com\", username: \"octocat\", password: $password
+                    }) {
+                        success
+                    }
+                }",
+                json!({ "id": id, "version": version, "password": password }),
+            )
+        };
+
+        update("from the first device").await?;
+
+        match update("from a stale device").await {
+            Err(AppError::VersionConflict { current, .. }) => {
+                if current["version"].as_i64() != Some(version + 1)
+                    || current["password"] != "from the first device"
+                {
+                    return Err(AppError::Internal(format!(
+                        "Conflict should carry the current entry: {}",
+                        current
+                    )));
+                }
+            }
+            other => {
+                return Err(AppError::Internal(format!(
+                    "Stale update should conflict: {:?}",
+                    other
+                )));
+            }
+        }
+
+        let entry = app
+            .execute(
+                Some(&session),
+                "query($id: UUID!) { getPassword(request: { id: $id }) { data { version password } } }",
+                json!({ "id": id }),
+            )
+            .await?;
+        if entry["getPassword"]["data"]["password"] != "from the first device" {
+            return Err(AppError::Internal(format!(
+                "Stale update should not be saved: {}",
+                entry
+            )));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_move_passwords() -> AppResult<()> {
+        let Some(app) = TestApp::start(Env::for_tests()).await? else {
+            return Ok(());
+        };
+        let session = app.signup(&unique_email(), MASTER_PASSWORD).await?;
+
+        add_password(&app, &session, "https://github.com").await?;
+
+        let folder = app
+            .execute(
+                Some(&session),
+                "mutation { createFolder(request: { name: \"Work\" }) { data { id } } }",
+                json!({}),
+            )
+            .await?;
+        let folder_id = folder["createFolder"]["data"]["id"].clone();
+
+        let changes = changes_since(&app, &session, 0).await?;
+        let id = ids(&changes["created"])
+            .pop()
+            .ok_or(AppError::Internal("Added entry is not synced".to_string()))?;
+        let synced_at = revision(&changes)?;
+
+        let move_passwords = |ids: Vec<String>| {
+            app.execute(
+                Some(&session),
+                "mutation($ids: [UUID!]!, $folderId: UUID) {
+                    movePasswords(request: { ids: $ids, folderId: $folderId }) { success }
+                }",
+                json!({ "ids": ids, "folderId": folder_id }),
+            )
+        };
+
+        move_passwords(vec![id.clone()]).await?;
+
+        let entry = app
+            .execute(
+                Some(&session),
+                "query($id: UUID!) { getPassword(request: { id: $id }) { data { folderId } } }",
+                json!({ "id": id }),
+            )
+            .await?;
+        if entry["getPassword"]["data"]["folderId"] != folder_id {
+            return Err(AppError::Internal(format!(
+                "Entry should be in the folder: {}",
+                entry
+            )));
+        }
+
+        let changes = changes_since(&app, &session, synced_at).await?;
+        if ids(&changes["updated"]) != vec![id] {
+            return Err(AppError::Internal(format!(
+                "Moved entry should sync as updated: {}",
+                changes
+            )));
+        }
+        let moved_at = revision(&changes)?;
+
+        // ids of no entry of this vault move nothing and leave the revision alone
+        move_passwords(vec![uuid::Uuid::new_v4().to_string()]).await?;
+
+        let changes = changes_since(&app, &session, moved_at).await?;
+        if revision(&changes)? != moved_at || !ids(&changes["updated"]).is_empty() {
+            return Err(AppError::Internal(format!(
+                "Moving nothing should not bump the revision: {}",
+                changes
+            )));
+        }
+
+        Ok(())
+    }
+}
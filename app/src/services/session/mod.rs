@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod session;
+mod session_test;
+
+pub use session::*;
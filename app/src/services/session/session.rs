@@ -0,0 +1,741 @@
+use std::{net::IpAddr, sync::Arc};
+
+use async_graphql::Context;
+use axum::http::{HeaderMap, header};
+use chrono::{DateTime, Duration, Utc};
+use redis::{
+    Commands, Connection, ConnectionLike, ExistenceCheck, RedisResult, SetExpiry, SetOptions,
+};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+
+use crate::{
+    configs::env::Env,
+    dtos::{
+        app_state::AppState,
+        graphql_context::GraphQLContext,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        session_dtos::{
+            RevokeSessionRequest, SessionPolicyResponse, SessionResponse,
+            SessionVerificationResponse, SessionsResponse, UpdateSessionPolicyRequest,
+            VerifySessionRequest,
+        },
+        user,
+        user_dtos::{RedisSession, SessionState, UnlockVaultRequest, UserRedisSession},
+    },
+    services::{
+        crypto::{decrypt_dek, derive_kek, hash_session_token, verify_master_password},
+        events::publish_session_revoked,
+        throttle::{
+            AuthAction, AuthAttempt, clear_failures, record_failure, release_attempt,
+            reserve_attempt,
+        },
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Longest user agent kept for a session
+const USER_AGENT_MAX_CHARS: usize = 256;
+
+/// Idle timeout and absolute lifetime of the sessions of a user
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionPolicy {
+    pub idle_minutes: i64,
+    pub max_lifetime_minutes: i64,
+}
+
+/// The server settings, shortened by the overrides of a user
+pub fn session_policy(
+    env_variables: &Env,
+    idle_minutes: Option<i32>,
+    max_lifetime_minutes: Option<i32>,
+) -> SessionPolicy {
+    let shortened = |setting: i64, user_setting: Option<i32>| match user_setting {
+        Some(user_setting) => setting.min(user_setting.max(1) as i64),
+        None => setting,
+    };
+
+    SessionPolicy {
+        idle_minutes: shortened(env_variables.session_idle_minutes, idle_minutes),
+        max_lifetime_minutes: shortened(
+            env_variables.session_max_lifetime_minutes,
+            max_lifetime_minutes,
+        ),
+    }
+}
+
+/// Seconds a session is kept from now, sliding never passes its deadline
+pub fn session_ttl_seconds(
+    idle_minutes: i64,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> u64 {
+    let remaining_seconds = (expires_at - now).num_seconds();
+
+    (idle_minutes * 60).min(remaining_seconds).max(1) as u64
+}
+
+fn user_sessions_key(user_id: Uuid) -> String {
+    format!("user_sessions:{}", user_id)
+}
+
+fn user_devices_key(user_id: Uuid) -> String {
+    format!("user_devices:{}", user_id)
+}
+
+fn last_seen_key(session_token: &str) -> String {
+    format!("session_last_seen:{}", hash_session_token(session_token))
+}
+
+fn session_dek_key(session_token: &str) -> String {
+    format!("session_dek:{}", hash_session_token(session_token))
+}
+
+/// Keep the DEK of a session until the vault auto-locks
+pub fn store_session_dek<C: ConnectionLike>(
+    redis_connection: &mut C,
+    session_token: &str,
+    dek: &[u8],
+    expire_seconds: u64,
+) -> RedisResult<()> {
+    redis_connection.set_ex::<String, &[u8], ()>(
+        session_dek_key(session_token),
+        dek,
+        expire_seconds,
+    )
+}
+
+/// The DEK of a session, missing while its vault is locked
+pub fn load_session_dek<C: ConnectionLike>(
+    redis_connection: &mut C,
+    session_token: &str,
+) -> RedisResult<Option<Vec<u8>>> {
+    redis_connection.get::<String, Option<Vec<u8>>>(session_dek_key(session_token))
+}
+
+pub fn extend_session_dek(
+    redis_connection: &mut Connection,
+    session_token: &str,
+    expire_seconds: u64,
+) -> RedisResult<()> {
+    redis_connection.expire::<String, ()>(session_dek_key(session_token), expire_seconds as i64)
+}
+
+fn session_verified_key(session_token: &str) -> String {
+    format!("session_verified:{}", hash_session_token(session_token))
+}
+
+/// Whether the session re-entered the master password recently
+pub fn is_session_verified<C: ConnectionLike>(
+    redis_connection: &mut C,
+    session_token: &str,
+) -> RedisResult<bool> {
+    redis_connection.exists::<String, bool>(session_verified_key(session_token))
+}
+
+/// Forget the DEK of a session and its recent verification, until the master password is
+/// entered again
+pub fn lock_session<C: ConnectionLike>(
+    redis_connection: &mut C,
+    session_token: &str,
+) -> RedisResult<()> {
+    redis_connection.del::<&[String], ()>(&[
+        session_dek_key(session_token),
+        session_verified_key(session_token),
+    ])
+}
+
+/// Check the master password re-entered in a session, counting failures like sign-ins do
+pub fn check_master_password<C: ConnectionLike>(
+    redis_connection: &mut C,
+    env_variables: &Env,
+    attempt: &AuthAttempt,
+    master_password: &str,
+    master_password_hash: &str,
+) -> AppResult<()> {
+    reserve_attempt(redis_connection, env_variables, attempt)?;
+
+    if !verify_master_password(master_password, master_password_hash)? {
+        record_failure(redis_connection, env_variables, attempt)?;
+        return Err(AppError::Authorization(
+            "Invalid Master Password".to_string(),
+        ));
+    }
+
+    release_attempt(redis_connection, env_variables, attempt)?;
+    clear_failures(redis_connection, attempt.action, &attempt.account)
+}
+
+/// Let a session through sensitive operations for the given number of seconds
+pub fn mark_session_verified<C: ConnectionLike>(
+    redis_connection: &mut C,
+    session_token: &str,
+    verified_seconds: u64,
+) -> RedisResult<()> {
+    redis_connection.set_ex::<String, bool, ()>(
+        session_verified_key(session_token),
+        true,
+        verified_seconds,
+    )
+}
+
+pub fn user_agent_from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(USER_AGENT_MAX_CHARS).collect())
+}
+
+/// Address of the client: the peer itself, or the last address a trusted proxy in front of
+/// the app forwarded for, since everything before it could have been sent by the client
+pub fn client_ip(gql_ctx: &GraphQLContext, trusted_proxies: &[IpAddr]) -> Option<String> {
+    let peer_ip = gql_ctx.peer_ip?;
+
+    if !trusted_proxies.contains(&peer_ip) {
+        return Some(peer_ip.to_string());
+    }
+
+    let Some(headers) = &gql_ctx.headers else {
+        return Some(peer_ip.to_string());
+    };
+
+    let mut forwarded_for: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+
+    if forwarded_for.is_empty() {
+        forwarded_for.extend(
+            headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok()),
+        );
+    }
+
+    let mut client_ip = peer_ip;
+
+    // walk back from the nearest hop while it is one of the trusted proxies
+    for forwarded_ip in forwarded_for.into_iter().rev() {
+        if !trusted_proxies.contains(&client_ip) {
+            break;
+        }
+
+        let Ok(forwarded_ip) = forwarded_ip.trim().parse::<IpAddr>() else {
+            break;
+        };
+
+        client_ip = forwarded_ip;
+    }
+
+    Some(client_ip.to_string())
+}
+
+/// Record the time a session was last used, expiring together with the session
+pub fn touch_session(
+    redis_connection: &mut Connection,
+    session_token: &str,
+    expire_seconds: u64,
+) -> RedisResult<()> {
+    redis_connection.set_ex::<String, String, ()>(
+        last_seen_key(session_token),
+        Utc::now().to_rfc3339(),
+        expire_seconds,
+    )
+}
+
+/// Add a new session to the sessions of its user, dropping the ones that expired
+pub fn index_session(
+    redis_connection: &mut Connection,
+    user_id: Uuid,
+    session_token: &str,
+    expire_seconds: u64,
+) -> RedisResult<()> {
+    let session_tokens =
+        redis_connection.smembers::<String, Vec<String>>(user_sessions_key(user_id))?;
+
+    let mut expired_session_tokens = vec![];
+
+    for session_token in session_tokens {
+        if !redis_connection.exists::<&str, bool>(&session_token)? {
+            expired_session_tokens.push(session_token);
+        }
+    }
+
+    if !expired_session_tokens.is_empty() {
+        redis_connection
+            .srem::<String, Vec<String>, ()>(user_sessions_key(user_id), expired_session_tokens)?;
+    }
+
+    redis_connection.sadd::<String, &str, ()>(user_sessions_key(user_id), session_token)?;
+
+    touch_session(redis_connection, session_token, expire_seconds)
+}
+
+/// Remember the device of a new session by its user agent, true when the user has signed in
+/// before but never from this device
+pub fn remember_device(
+    redis_connection: &mut Connection,
+    user_id: Uuid,
+    user_agent: Option<&str>,
+) -> RedisResult<bool> {
+    let device = hash_session_token(user_agent.unwrap_or_default());
+
+    let known_devices = redis_connection.scard::<String, usize>(user_devices_key(user_id))?;
+    let added =
+        redis_connection.sadd::<String, String, usize>(user_devices_key(user_id), device)?;
+
+    Ok(known_devices > 0 && added > 0)
+}
+
+/// Drop the devices remembered for a user
+pub fn forget_devices(redis_connection: &mut Connection, user_id: Uuid) -> RedisResult<()> {
+    redis_connection.del::<String, ()>(user_devices_key(user_id))
+}
+
+/// Rewrite the stored sessions of a user, keeping their expiry
+pub fn update_user_sessions(
+    app_state: &AppState,
+    user_id: Uuid,
+    update: impl Fn(&mut RedisSession),
+) -> AppResult<()> {
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let session_tokens = redis_connection
+        .smembers::<String, Vec<String>>(user_sessions_key(user_id))
+        .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?;
+
+    for session_token in session_tokens {
+        let Some(mut redis_session) = redis_connection
+            .get::<&str, Option<String>>(&session_token)
+            .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?
+            .and_then(|redis_session| serde_json::from_str::<RedisSession>(&redis_session).ok())
+        else {
+            continue;
+        };
+
+        update(&mut redis_session);
+
+        let redis_session_str =
+            serde_json::to_string(&redis_session).map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // XX so a session that expired in the meantime is not brought back
+        redis_connection
+            .set_options::<&str, String, ()>(
+                &session_token,
+                redis_session_str,
+                SetOptions::default()
+                    .conditional_set(ExistenceCheck::XX)
+                    .with_expiration(SetExpiry::KEEPTTL),
+            )
+            .map_err(|e| AppError::Internal(format!("Failed to update sessions: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// End sessions of a user, their open connections are told through sessionRevoked
+pub fn revoke_session_tokens(
+    app_state: &AppState,
+    user_id: Uuid,
+    session_tokens: &[String],
+) -> AppResult<()> {
+    if session_tokens.is_empty() {
+        return Ok(());
+    }
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let keys: Vec<String> = session_tokens
+        .iter()
+        .flat_map(|session_token| {
+            [
+                session_token.to_string(),
+                last_seen_key(session_token),
+                session_dek_key(session_token),
+                session_verified_key(session_token),
+            ]
+        })
+        .collect();
+
+    redis_connection
+        .del::<Vec<String>, ()>(keys)
+        .map_err(|e| AppError::Internal(format!("Failed to revoke sessions: {}", e)))?;
+
+    redis_connection
+        .srem::<String, &[String], ()>(user_sessions_key(user_id), session_tokens)
+        .map_err(|e| AppError::Internal(format!("Failed to revoke sessions: {}", e)))?;
+
+    for session_token in session_tokens {
+        publish_session_revoked(app_state, user_id, session_token);
+    }
+
+    Ok(())
+}
+
+/// End every session of a user except the one kept
+pub fn revoke_user_sessions(
+    app_state: &AppState,
+    user_id: Uuid,
+    keep_session_token: Option<&str>,
+) -> AppResult<()> {
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let session_tokens: Vec<String> = redis_connection
+        .smembers::<String, Vec<String>>(user_sessions_key(user_id))
+        .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?
+        .into_iter()
+        .filter(|session_token| Some(session_token.as_str()) != keep_session_token)
+        .collect();
+
+    revoke_session_tokens(app_state, user_id, &session_tokens)
+}
+
+fn current_session_token(ctx: &Context<'_>) -> AppResult<String> {
+    let gql_ctx = ctx
+        .data::<Arc<GraphQLContext>>()
+        .map_err(|_| AppError::Internal("GraphQL Context is not passed".to_string()))?;
+
+    gql_ctx.session_token.clone().ok_or(AppError::Authorization(
+        "Session token is missing".to_string(),
+    ))
+}
+
+pub async fn my_sessions(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<SessionsResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user_id = user_redis_session.id;
+    let current_session_token = current_session_token(ctx)?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let session_tokens = redis_connection
+        .smembers::<String, Vec<String>>(user_sessions_key(user_id))
+        .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?;
+
+    let mut sessions = vec![];
+
+    for session_token in session_tokens {
+        let redis_session = redis_connection
+            .get::<&str, Option<String>>(&session_token)
+            .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?
+            .and_then(|redis_session| serde_json::from_str::<RedisSession>(&redis_session).ok());
+
+        // expired sessions are only dropped from the index when it is read
+        let Some(redis_session) = redis_session else {
+            redis_connection
+                .srem::<String, &str, ()>(user_sessions_key(user_id), &session_token)
+                .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?;
+            continue;
+        };
+
+        let last_seen_at = redis_connection
+            .get::<String, Option<String>>(last_seen_key(&session_token))
+            .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?
+            .and_then(|last_seen_at| DateTime::parse_from_rfc3339(&last_seen_at).ok())
+            .map(|last_seen_at| last_seen_at.with_timezone(&Utc));
+
+        let locked = !redis_connection
+            .exists::<String, bool>(session_dek_key(&session_token))
+            .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?;
+
+        sessions.push(SessionResponse {
+            id: hash_session_token(&session_token),
+            created_at: redis_session.created_at,
+            last_seen_at,
+            user_agent: redis_session.user_agent,
+            ip_address: redis_session.ip_address,
+            locked,
+            current: session_token == current_session_token,
+        });
+    }
+
+    // most recently used first
+    sessions.sort_by(|a, b| b.last_seen_at.cmp(&a.last_seen_at));
+
+    Ok(GraphqlResponse::<SessionsResponse> {
+        success: true,
+        message: "Sessions fetched successfully".to_string(),
+        data: SessionsResponse { sessions },
+    })
+}
+
+pub async fn revoke_session(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RevokeSessionRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user_id = user_redis_session.id;
+    let current_session_token = current_session_token(ctx)?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let session_token = redis_connection
+        .smembers::<String, Vec<String>>(user_sessions_key(user_id))
+        .map_err(|e| AppError::Internal(format!("Failed to find sessions: {}", e)))?
+        .into_iter()
+        .find(|session_token| hash_session_token(session_token) == request.id)
+        .ok_or(AppError::NotFound("Session not found".to_string()))?;
+
+    if session_token == current_session_token {
+        return Err(AppError::Validation(
+            "Use logout to end the current session".to_string(),
+        ));
+    }
+
+    revoke_session_tokens(app_state, user_id, &[session_token])?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Session revoked successfully".to_string(),
+    })
+}
+
+fn to_session_policy_response(policy: SessionPolicy) -> SessionPolicyResponse {
+    SessionPolicyResponse {
+        idle_minutes: policy.idle_minutes,
+        max_lifetime_minutes: policy.max_lifetime_minutes,
+    }
+}
+
+async fn find_user(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<user::Model> {
+    user::Entity::find_by_id(user_id)
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find user: {}", e)))?
+        .ok_or(AppError::NotFound("User not found".to_string()))
+}
+
+pub async fn get_session_policy(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<SessionPolicyResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let user = find_user(&app_state.database_connection, user_redis_session.id).await?;
+
+    let policy = session_policy(
+        &app_state.env_variables,
+        user.session_idle_minutes,
+        user.session_max_lifetime_minutes,
+    );
+
+    Ok(GraphqlResponse::<SessionPolicyResponse> {
+        success: true,
+        message: "Session policy fetched successfully".to_string(),
+        data: to_session_policy_response(policy),
+    })
+}
+
+pub async fn update_session_policy(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UpdateSessionPolicyRequest,
+) -> AppResult<GraphqlResponse<SessionPolicyResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+
+    let user = find_user(database_connection, user_redis_session.id).await?;
+
+    let mut user_model: user::ActiveModel = user.into();
+    user_model.session_idle_minutes = Set(request.idle_minutes);
+    user_model.session_max_lifetime_minutes = Set(request.max_lifetime_minutes);
+    user_model.updated_at = Set(Utc::now());
+
+    user_model
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update session policy: {}", e)))?;
+
+    let policy = session_policy(
+        &app_state.env_variables,
+        request.idle_minutes,
+        request.max_lifetime_minutes,
+    );
+
+    Ok(GraphqlResponse::<SessionPolicyResponse> {
+        success: true,
+        message: "Session policy updated successfully".to_string(),
+        data: to_session_policy_response(policy),
+    })
+}
+
+pub async fn revoke_all_other_sessions(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let current_session_token = current_session_token(ctx)?;
+
+    revoke_user_sessions(
+        app_state,
+        user_redis_session.id,
+        Some(&current_session_token),
+    )?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Other sessions revoked successfully".to_string(),
+    })
+}
+
+pub async fn lock_vault(ctx: &Context<'_>, _: &SessionState) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let session_token = current_session_token(ctx)?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    // a locked session has to prove itself again for sensitive operations too
+    lock_session(&mut redis_connection, &session_token)
+        .map_err(|e| AppError::Internal(format!("Failed to lock vault: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Vault locked successfully".to_string(),
+    })
+}
+
+pub async fn unlock_vault(
+    ctx: &Context<'_>,
+    session_state: &SessionState,
+    request: UnlockVaultRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let SessionState::Locked(redis_session) = session_state else {
+        return Ok(GraphqlGenericResponse {
+            success: true,
+            message: "Vault is already unlocked".to_string(),
+        });
+    };
+
+    let session_token = current_session_token(ctx)?;
+
+    let user = find_user(&app_state.database_connection, redis_session.id).await?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let attempt = AuthAttempt::new(ctx, AuthAction::MasterPassword, &user.id.to_string());
+    check_master_password(
+        &mut redis_connection,
+        &app_state.env_variables,
+        &attempt,
+        &request.master_password,
+        &user.master_password_hash,
+    )?;
+
+    let kek = derive_kek(&request.master_password)?;
+    let dek = decrypt_dek(&user.encrypted_dek, &kek)?;
+
+    let auto_lock_seconds = session_ttl_seconds(
+        app_state.env_variables.vault_auto_lock_minutes,
+        redis_session.expires_at.unwrap_or_else(Utc::now),
+        Utc::now(),
+    );
+
+    store_session_dek(
+        &mut redis_connection,
+        &session_token,
+        &dek,
+        auto_lock_seconds,
+    )
+    .map_err(|e| AppError::Internal(format!("Failed to unlock vault: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Vault unlocked successfully".to_string(),
+    })
+}
+
+pub async fn verify_session(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: VerifySessionRequest,
+) -> AppResult<GraphqlResponse<SessionVerificationResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let session_token = current_session_token(ctx)?;
+
+    let user = find_user(&app_state.database_connection, user_redis_session.id).await?;
+
+    let mut redis_connection = app_state
+        .redis_pool_manager
+        .get()
+        .map_err(|_| AppError::Internal("Failed to get redis connection from pool".to_string()))?;
+
+    let attempt = AuthAttempt::new(ctx, AuthAction::MasterPassword, &user.id.to_string());
+    check_master_password(
+        &mut redis_connection,
+        &app_state.env_variables,
+        &attempt,
+        &request.master_password,
+        &user.master_password_hash,
+    )?;
+
+    let now = Utc::now();
+    let expires_at = user_redis_session.expires_at.unwrap_or(now);
+    let verified_seconds =
+        session_ttl_seconds(app_state.env_variables.step_up_minutes, expires_at, now);
+
+    mark_session_verified(&mut redis_connection, &session_token, verified_seconds)
+        .map_err(|e| AppError::Internal(format!("Failed to verify session: {}", e)))?;
+
+    Ok(GraphqlResponse::<SessionVerificationResponse> {
+        success: true,
+        message: "Session verified successfully".to_string(),
+        data: SessionVerificationResponse {
+            verified_until: now + Duration::seconds(verified_seconds as i64),
+        },
+    })
+}
@@ -0,0 +1,297 @@
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use axum::http::{HeaderMap, HeaderValue, header};
+    use chrono::{Duration, Utc};
+
+    use crate::{
+        configs::env::Env,
+        dtos::graphql_context::GraphQLContext,
+        services::{
+            crypto::hash_master_password,
+            session::{
+                SessionPolicy, check_master_password, client_ip, is_session_verified,
+                load_session_dek, lock_session, mark_session_verified, session_policy,
+                session_ttl_seconds, store_session_dek, user_agent_from_headers,
+            },
+            throttle::{AuthAction, AuthAttempt, FREE_FAILURES},
+        },
+        utils::{
+            error::{AppError, AppResult},
+            memory_redis::MemoryRedis,
+        },
+    };
+
+    fn master_password_attempt() -> AuthAttempt {
+        AuthAttempt {
+            action: AuthAction::MasterPassword,
+            account: "4b1a3a52-4f4c-4b43-9a8e-0d9f3c0e6a11".to_string(),
+            ip_address: Some("203.0.113.9".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_client_ip() -> AppResult<()> {
+        let proxy_ip: IpAddr = "10.0.0.1"
+            .parse()
+            .map_err(|_| AppError::Internal("Invalid address".to_string()))?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.7, 203.0.113.9"),
+        );
+
+        let mut gql_ctx = GraphQLContext {
+            session_token: None,
+            headers: Some(headers),
+            peer_ip: Some(proxy_ip),
+        };
+
+        // without trusted proxies the forwarded addresses are whatever the client sent
+        if client_ip(&gql_ctx, &[]).as_deref() != Some("10.0.0.1") {
+            return Err(AppError::Internal(
+                "Forwarded address was trusted without a proxy".to_string(),
+            ));
+        }
+
+        // the address before the proxy is the client, the ones before it could be forged
+        if client_ip(&gql_ctx, &[proxy_ip]).as_deref() != Some("203.0.113.9") {
+            return Err(AppError::Internal(
+                "Address forwarded by the proxy was not used".to_string(),
+            ));
+        }
+
+        gql_ctx.peer_ip = "192.0.2.4".parse().ok();
+
+        if client_ip(&gql_ctx, &[proxy_ip]).as_deref() != Some("192.0.2.4") {
+            return Err(AppError::Internal(
+                "Forwarded address was trusted from another peer".to_string(),
+            ));
+        }
+
+        gql_ctx.peer_ip = Some(proxy_ip);
+        gql_ctx.headers = Some(HeaderMap::new());
+
+        if client_ip(&gql_ctx, &[proxy_ip]).as_deref() != Some("10.0.0.1") {
+            return Err(AppError::Internal(
+                "Proxy without forwarded address was not used".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_agent_from_headers() -> AppResult<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_str(&"a".repeat(1000))
+                .map_err(|e| AppError::Internal(e.to_string()))?,
+        );
+
+        if user_agent_from_headers(&headers).map(|user_agent| user_agent.len()) != Some(256) {
+            return Err(AppError::Internal(
+                "User agent was not truncated".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_policy() -> AppResult<()> {
+        let env_variables = Env::for_tests();
+
+        // overrides only ever shorten the server settings
+        if session_policy(&env_variables, Some(10), Some(10_000))
+            != (SessionPolicy {
+                idle_minutes: 10,
+                max_lifetime_minutes: 720,
+            })
+        {
+            return Err(AppError::Internal(
+                "Overrides were not capped by the server settings".to_string(),
+            ));
+        }
+
+        if session_policy(&env_variables, None, None)
+            != (SessionPolicy {
+                idle_minutes: 30,
+                max_lifetime_minutes: 720,
+            })
+        {
+            return Err(AppError::Internal(
+                "Server settings were not used by default".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_session_ttl_seconds() -> AppResult<()> {
+        let now = Utc::now();
+
+        if session_ttl_seconds(30, now + Duration::hours(12), now) != 30 * 60 {
+            return Err(AppError::Internal(
+                "Idle timeout was not applied".to_string(),
+            ));
+        }
+
+        // sliding stops at the deadline
+        if session_ttl_seconds(30, now + Duration::minutes(5), now) != 5 * 60 {
+            return Err(AppError::Internal(
+                "Session was extended past its deadline".to_string(),
+            ));
+        }
+
+        if session_ttl_seconds(30, now - Duration::minutes(5), now) != 1 {
+            return Err(AppError::Internal(
+                "Elapsed deadline gave a session time".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_and_unlock_vault() -> AppResult<()> {
+        let mut redis_connection = MemoryRedis::default();
+        let env_variables = Env {
+            auth_max_failures: FREE_FAILURES,
+            ..Env::for_tests()
+        };
+
+        let master_password_hash = hash_master_password("correct horse battery staple")?;
+        let attempt = master_password_attempt();
+        let dek = [7u8; 32];
+
+        store_session_dek(&mut redis_connection, "session", &dek, 60)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        lock_session(&mut redis_connection, "session")
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if load_session_dek(&mut redis_connection, "session")
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .is_some()
+        {
+            return Err(AppError::Internal(
+                "DEK was kept by a locked session".to_string(),
+            ));
+        }
+
+        check_master_password(
+            &mut redis_connection,
+            &env_variables,
+            &attempt,
+            "correct horse battery staple",
+            &master_password_hash,
+        )?;
+
+        for _ in 0..FREE_FAILURES {
+            if check_master_password(
+                &mut redis_connection,
+                &env_variables,
+                &attempt,
+                "wrong",
+                &master_password_hash,
+            )
+            .is_ok()
+            {
+                return Err(AppError::Internal(
+                    "Wrong master password unlocked the vault".to_string(),
+                ));
+            }
+        }
+
+        // once locked out, even the right master password is not checked
+        if !matches!(
+            check_master_password(
+                &mut redis_connection,
+                &env_variables,
+                &attempt,
+                "correct horse battery staple",
+                &master_password_hash,
+            ),
+            Err(AppError::TooManyRequests { .. })
+        ) {
+            return Err(AppError::Internal(
+                "Master password was checked during a lockout".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_up_lockout() -> AppResult<()> {
+        let mut redis_connection = MemoryRedis::default();
+        let env_variables = Env {
+            auth_max_failures: FREE_FAILURES,
+            ..Env::for_tests()
+        };
+
+        let master_password_hash = hash_master_password("correct horse battery staple")?;
+        let attempt = master_password_attempt();
+
+        // the marker is only set once the master password was accepted, as verify_session does
+        let mut verify = |master_password: &str| -> AppResult<bool> {
+            check_master_password(
+                &mut redis_connection,
+                &env_variables,
+                &attempt,
+                master_password,
+                &master_password_hash,
+            )?;
+            mark_session_verified(&mut redis_connection, "session", 60)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+
+            is_session_verified(&mut redis_connection, "session")
+                .map_err(|e| AppError::Internal(e.to_string()))
+        };
+
+        for _ in 0..FREE_FAILURES {
+            if verify("wrong").is_ok() {
+                return Err(AppError::Internal(
+                    "Wrong master password verified the session".to_string(),
+                ));
+            }
+        }
+
+        if !matches!(
+            verify("correct horse battery staple"),
+            Err(AppError::TooManyRequests { .. })
+        ) {
+            return Err(AppError::Internal(
+                "Locked out user was verified".to_string(),
+            ));
+        }
+
+        if is_session_verified(&mut redis_connection, "session")
+            .map_err(|e| AppError::Internal(e.to_string()))?
+        {
+            return Err(AppError::Internal(
+                "Locked out user got the step-up marker".to_string(),
+            ));
+        }
+
+        // locking the vault drops a step-up given before
+        mark_session_verified(&mut redis_connection, "session", 60)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        lock_session(&mut redis_connection, "session")
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if is_session_verified(&mut redis_connection, "session")
+            .map_err(|e| AppError::Internal(e.to_string()))?
+        {
+            return Err(AppError::Internal(
+                "Locked session kept its step-up".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod share;
+mod share_test;
+
+pub use share::*;
@@ -0,0 +1,724 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::Context;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Order,
+    QueryFilter, QueryOrder, Set, TransactionError, TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        custom_field,
+        event_dtos::VaultChangeType,
+        password::{self, VaultItemType},
+        password_share::{self, SharePermission},
+        share_dtos::{
+            RevokeShareRequest, ShareEntryRequest, SharedEntriesResponse, SharedEntryResponse,
+            UpdateSharedEntryRequest,
+        },
+        user,
+        user_dtos::UserRedisSession,
+    },
+    services::{
+        crypto::{
+            decrypt_password, encrypt_password, generate_dek, open_item_key, seal_item_key,
+            unwrap_item_key, wrap_item_key,
+        },
+        custom_field::build_custom_fields,
+        events::publish_vault_changed,
+        organization::OrganizationAccess,
+        password::{next_revision, to_shared_password_responses},
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// The key an entry is encrypted with, its own item key once it has been shared
+pub fn entry_key(password_entry: &password::Model, dek: &[u8; 32]) -> AppResult<[u8; 32]> {
+    match &password_entry.encrypted_item_key {
+        Some(encrypted_item_key) => unwrap_item_key(encrypted_item_key, dek)?
+            .try_into()
+            .map_err(|_| AppError::Crypto("Unable to convert item key to [u8; 32]".to_string())),
+        None => Ok(*dek),
+    }
+}
+
+/// Open the item key of an entry shared with the user
+pub fn shared_item_key(
+    password_share: &password_share::Model,
+    recipient: &user::Model,
+    dek: &[u8; 32],
+) -> AppResult<[u8; 32]> {
+    let encrypted_private_key = recipient
+        .encrypted_private_key
+        .as_ref()
+        .ok_or(AppError::Crypto("Private key is missing".to_string()))?;
+
+    open_item_key(&password_share.wrapped_item_key, encrypted_private_key, dek)?
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert item key to [u8; 32]".to_string()))
+}
+
+/// The key of an entry the user owns, has been shared or can read through a collection
+pub async fn accessible_entry_key(
+    database_connection: &DatabaseConnection,
+    password_entry: &password::Model,
+    user_id: Uuid,
+    dek: &[u8; 32],
+) -> AppResult<[u8; 32]> {
+    if password_entry.collection_id.is_some() {
+        return OrganizationAccess::load(database_connection, user_id, dek)
+            .await?
+            .entry_key(password_entry, dek)
+            .map_err(|_| AppError::NotFound("Password not found".to_string()));
+    }
+
+    if password_entry.user_id == user_id {
+        return entry_key(password_entry, dek);
+    }
+
+    let share = password_share::Entity::find()
+        .filter(password_share::Column::PasswordId.eq(password_entry.id))
+        .filter(password_share::Column::RecipientId.eq(user_id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find share: {}", e)))?
+        .filter(|_| !password_entry.is_deleted)
+        .ok_or(AppError::NotFound("Password not found".to_string()))?;
+
+    let recipient = find_user(database_connection, user_id).await?;
+
+    shared_item_key(&share, &recipient, dek)
+}
+
+fn reencrypt(
+    value: &Option<String>,
+    old_key: &[u8; 32],
+    new_key: &[u8; 32],
+) -> AppResult<Option<String>> {
+    value
+        .as_ref()
+        .map(|value| encrypt_password(&decrypt_password(value, old_key)?, new_key))
+        .transpose()
+}
+
+/// Re-encrypt an entry, its payload and its custom fields from one key to another
+pub fn rekey_entry(
+    password_entry: password::Model,
+    custom_fields: Vec<custom_field::Model>,
+    old_key: &[u8; 32],
+    new_key: &[u8; 32],
+) -> AppResult<(password::ActiveModel, Vec<custom_field::ActiveModel>)> {
+    let encrypted_username = reencrypt(&password_entry.encrypted_username, old_key, new_key)?;
+    let encrypted_email = reencrypt(&password_entry.encrypted_email, old_key, new_key)?;
+    let encrypted_password = reencrypt(&password_entry.encrypted_password, old_key, new_key)?;
+    let encrypted_payload = reencrypt(&password_entry.encrypted_payload, old_key, new_key)?;
+
+    let mut rekeyed_password: password::ActiveModel = password_entry.into();
+    rekeyed_password.encrypted_username = Set(encrypted_username);
+    rekeyed_password.encrypted_email = Set(encrypted_email);
+    rekeyed_password.encrypted_password = Set(encrypted_password);
+    rekeyed_password.encrypted_payload = Set(encrypted_payload);
+
+    let rekeyed_custom_fields = custom_fields
+        .into_iter()
+        .map(|custom_field| {
+            let encrypted_name = encrypt_password(
+                &decrypt_password(&custom_field.encrypted_name, old_key)?,
+                new_key,
+            )?;
+            let encrypted_value = reencrypt(&custom_field.encrypted_value, old_key, new_key)?;
+
+            let mut rekeyed_custom_field: custom_field::ActiveModel = custom_field.into();
+            rekeyed_custom_field.encrypted_name = Set(encrypted_name);
+            rekeyed_custom_field.encrypted_value = Set(encrypted_value);
+
+            Ok(rekeyed_custom_field)
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    Ok((rekeyed_password, rekeyed_custom_fields))
+}
+
+/// Store a re-keyed entry as a new version, failing when it changed since it was read
+pub async fn update_rekeyed_entry<C: ConnectionTrait>(
+    connection: &C,
+    mut rekeyed_password: password::ActiveModel,
+) -> Result<(), DbErr> {
+    let version = *rekeyed_password.version.as_ref();
+
+    rekeyed_password.version = Set(version + 1);
+    password::Entity::update(rekeyed_password)
+        .filter(password::Column::Version.eq(version))
+        .exec(connection)
+        .await?;
+
+    Ok(())
+}
+
+async fn find_shared_entry(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    password_id: Uuid,
+) -> AppResult<password::Model> {
+    password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::ItemType.eq(VaultItemType::Login))
+        .filter(password::Column::Id.eq(password_id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+        .ok_or(AppError::NotFound("Password not found".to_string()))
+}
+
+async fn find_user(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+) -> AppResult<user::Model> {
+    user::Entity::find_by_id(user_id)
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("User Not Found".to_string()))
+}
+
+async fn find_recipient(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    recipient_email: &str,
+) -> AppResult<user::Model> {
+    let recipient = user::Entity::find()
+        .filter(user::Column::Email.eq(recipient_email))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or(AppError::NotFound("Recipient not found".to_string()))?;
+
+    if recipient.id == user_id {
+        return Err(AppError::Validation(
+            "Entries cannot be shared with yourself".to_string(),
+        ));
+    }
+
+    Ok(recipient)
+}
+
+async fn find_entry_custom_fields(
+    database_connection: &DatabaseConnection,
+    password_id: Uuid,
+) -> AppResult<Vec<custom_field::Model>> {
+    custom_field::Entity::find()
+        .filter(custom_field::Column::PasswordId.eq(password_id))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get custom fields: {}", e)))
+}
+
+pub async fn share_entry(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: ShareEntryRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let password_entry = find_shared_entry(database_connection, user_id, request.id).await?;
+
+    if password_entry.is_deleted {
+        return Err(AppError::Validation(
+            "Trashed entries cannot be shared".to_string(),
+        ));
+    }
+
+    if password_entry.collection_id.is_some() {
+        return Err(AppError::Validation(
+            "Entries in a collection are shared through their organization".to_string(),
+        ));
+    }
+
+    let recipient = find_recipient(database_connection, user_id, &request.recipient_email).await?;
+
+    let recipient_public_key = recipient.public_key.as_ref().ok_or(AppError::Conflict(
+        "Recipient has not set up sharing yet".to_string(),
+    ))?;
+
+    // the first share moves the entry from the owner's DEK to its own item key
+    let (item_key, rekeyed_entry) = if password_entry.encrypted_item_key.is_some() {
+        (entry_key(&password_entry, &dek_u8_32)?, None)
+    } else {
+        let item_key = generate_dek();
+        let custom_fields =
+            find_entry_custom_fields(database_connection, password_entry.id).await?;
+
+        let (mut rekeyed_password, rekeyed_custom_fields) =
+            rekey_entry(password_entry, custom_fields, &dek_u8_32, &item_key)?;
+        rekeyed_password.encrypted_item_key = Set(Some(wrap_item_key(&item_key, &dek_u8_32)?));
+
+        (item_key, Some((rekeyed_password, rekeyed_custom_fields)))
+    };
+
+    let wrapped_item_key = seal_item_key(&item_key, recipient_public_key)?;
+    let permission = request.permission.unwrap_or_default();
+
+    let existing_share = password_share::Entity::find()
+        .filter(password_share::Column::PasswordId.eq(request.id))
+        .filter(password_share::Column::RecipientId.eq(recipient.id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find share: {}", e)))?;
+
+    let password_share_model = match existing_share {
+        Some(existing_share) => {
+            let mut updated_share: password_share::ActiveModel = existing_share.into();
+            updated_share.wrapped_item_key = Set(wrapped_item_key);
+            updated_share.permission = Set(permission);
+            updated_share.updated_at = Set(Utc::now());
+            updated_share
+        }
+        None => password_share::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            password_id: Set(request.id),
+            owner_id: Set(user_id),
+            recipient_id: Set(recipient.id),
+            wrapped_item_key: Set(wrapped_item_key),
+            permission: Set(permission),
+            ..Default::default()
+        },
+    };
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                let mut revision = None;
+
+                if let Some((mut rekeyed_password, rekeyed_custom_fields)) = rekeyed_entry {
+                    let next_revision = next_revision(txn, user_id).await?;
+
+                    rekeyed_password.revision = Set(next_revision);
+                    update_rekeyed_entry(txn, rekeyed_password).await?;
+                    revision = Some(next_revision);
+
+                    for rekeyed_custom_field in rekeyed_custom_fields {
+                        rekeyed_custom_field.update(txn).await?;
+                    }
+                }
+
+                password_share_model.save(txn).await?;
+
+                Ok(revision)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| match e {
+            TransactionError::Transaction(DbErr::RecordNotUpdated) => {
+                AppError::Conflict("Password was changed while it was being shared".to_string())
+            }
+            e => AppError::Internal(format!("Failed to share password: {}", e)),
+        })?;
+
+    if let Some(revision) = revision {
+        publish_vault_changed(
+            app_state,
+            user_id,
+            Some(request.id),
+            VaultChangeType::Updated,
+            revision,
+        );
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Password shared successfully".to_string(),
+    })
+}
+
+/// Seal a new item key to each of the recipients that keep their share
+pub fn reseal_shares(
+    remaining_shares: Vec<password_share::Model>,
+    public_keys: &HashMap<Uuid, String>,
+    new_item_key: &[u8; 32],
+) -> AppResult<Vec<password_share::ActiveModel>> {
+    remaining_shares
+        .into_iter()
+        .map(|remaining_share| {
+            let public_key =
+                public_keys
+                    .get(&remaining_share.recipient_id)
+                    .ok_or(AppError::Crypto(
+                        "Recipient public key is missing".to_string(),
+                    ))?;
+
+            let wrapped_item_key = seal_item_key(new_item_key, public_key)?;
+
+            let mut resealed_share: password_share::ActiveModel = remaining_share.into();
+            resealed_share.wrapped_item_key = Set(wrapped_item_key);
+            resealed_share.updated_at = Set(Utc::now());
+
+            Ok(resealed_share)
+        })
+        .collect()
+}
+
+pub async fn revoke_share(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RevokeShareRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let password_entry = find_shared_entry(database_connection, user_id, request.id).await?;
+    let recipient = find_recipient(database_connection, user_id, &request.recipient_email).await?;
+
+    let revoked_share = password_share::Entity::find()
+        .filter(password_share::Column::PasswordId.eq(password_entry.id))
+        .filter(password_share::Column::RecipientId.eq(recipient.id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find share: {}", e)))?
+        .ok_or(AppError::NotFound("Share not found".to_string()))?;
+
+    let remaining_shares = password_share::Entity::find()
+        .filter(password_share::Column::PasswordId.eq(password_entry.id))
+        .filter(password_share::Column::Id.ne(revoked_share.id))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get shares: {}", e)))?;
+
+    let recipient_ids: Vec<Uuid> = remaining_shares
+        .iter()
+        .map(|share| share.recipient_id)
+        .collect();
+    let public_keys: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(recipient_ids))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .into_iter()
+        .filter_map(|recipient| {
+            recipient
+                .public_key
+                .map(|public_key| (recipient.id, public_key))
+        })
+        .collect();
+
+    // a new item key keeps the revoked recipient out of later changes
+    let old_item_key = entry_key(&password_entry, &dek_u8_32)?;
+    let new_item_key = generate_dek();
+
+    let custom_fields = find_entry_custom_fields(database_connection, password_entry.id).await?;
+    let password_id = password_entry.id;
+    let (mut rekeyed_password, rekeyed_custom_fields) =
+        rekey_entry(password_entry, custom_fields, &old_item_key, &new_item_key)?;
+    rekeyed_password.encrypted_item_key = Set(Some(wrap_item_key(&new_item_key, &dek_u8_32)?));
+
+    let resealed_shares = reseal_shares(remaining_shares, &public_keys, &new_item_key)?;
+
+    let revision = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                password_share::Entity::delete_by_id(revoked_share.id)
+                    .exec(txn)
+                    .await?;
+
+                let revision = next_revision(txn, user_id).await?;
+
+                rekeyed_password.revision = Set(revision);
+                update_rekeyed_entry(txn, rekeyed_password).await?;
+
+                for rekeyed_custom_field in rekeyed_custom_fields {
+                    rekeyed_custom_field.update(txn).await?;
+                }
+
+                for resealed_share in resealed_shares {
+                    resealed_share.update(txn).await?;
+                }
+
+                Ok(revision)
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| match e {
+            TransactionError::Transaction(DbErr::RecordNotUpdated) => AppError::Conflict(
+                "Password was changed while the share was being revoked".to_string(),
+            ),
+            e => AppError::Internal(format!("Failed to revoke share: {}", e)),
+        })?;
+
+    publish_vault_changed(
+        app_state,
+        user_id,
+        Some(password_id),
+        VaultChangeType::Updated,
+        revision,
+    );
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Share revoked successfully".to_string(),
+    })
+}
+
+pub async fn shared_with_me(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<SharedEntriesResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    // entries the owner moved to trash are hidden until restored
+    let shares: Vec<(password_share::Model, password::Model)> = password_share::Entity::find()
+        .filter(password_share::Column::RecipientId.eq(user_id))
+        .find_also_related(password::Entity)
+        .filter(password::Column::IsDeleted.eq(false))
+        .order_by(password_share::Column::CreatedAt, Order::Desc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get shared passwords: {}", e)))?
+        .into_iter()
+        .filter_map(|(share, password_entry)| password_entry.map(|p| (share, p)))
+        .collect();
+
+    if shares.is_empty() {
+        return Ok(GraphqlResponse::<SharedEntriesResponse> {
+            success: true,
+            message: "No shared passwords found".to_string(),
+            data: SharedEntriesResponse { entries: vec![] },
+        });
+    }
+
+    let recipient = find_user(database_connection, user_id).await?;
+
+    let owner_ids: Vec<Uuid> = shares.iter().map(|(share, _)| share.owner_id).collect();
+    let owner_emails: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(owner_ids))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .into_iter()
+        .map(|owner| (owner.id, owner.email))
+        .collect();
+
+    let item_keys = shares
+        .iter()
+        .map(|(share, _)| shared_item_key(share, &recipient, &dek_u8_32))
+        .collect::<AppResult<Vec<_>>>()?;
+    let password_entries: Vec<password::Model> = shares
+        .iter()
+        .map(|(_, password_entry)| password_entry.clone())
+        .collect();
+
+    let passwords_response =
+        to_shared_password_responses(database_connection, &password_entries, &item_keys).await?;
+
+    let entries = shares
+        .into_iter()
+        .map(|(share, _)| share)
+        .zip(passwords_response)
+        .map(|(share, entry)| SharedEntryResponse {
+            owner_email: owner_emails
+                .get(&share.owner_id)
+                .cloned()
+                .unwrap_or_default(),
+            permission: share.permission,
+            shared_at: share.created_at,
+            entry,
+        })
+        .collect();
+
+    Ok(GraphqlResponse::<SharedEntriesResponse> {
+        success: true,
+        message: "Shared passwords found".to_string(),
+        data: SharedEntriesResponse { entries },
+    })
+}
+
+/// The error for a shared update based on an outdated version, with the entry as it is stored now
+async fn shared_version_conflict(
+    database_connection: &DatabaseConnection,
+    password_entry: password::Model,
+    item_key: &[u8; 32],
+) -> AppResult<AppError> {
+    let current =
+        to_shared_password_responses(database_connection, &[password_entry], &[*item_key])
+            .await?
+            .pop()
+            .ok_or(AppError::NotFound("Shared password not found".to_string()))?;
+
+    let current = serde_json::to_value(current)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize password: {}", e)))?;
+
+    Ok(AppError::VersionConflict {
+        message: "Password was changed since this version".to_string(),
+        current,
+    })
+}
+
+pub async fn update_shared_entry(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UpdateSharedEntryRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let (share, password_entry) = password_share::Entity::find()
+        .filter(password_share::Column::RecipientId.eq(user_id))
+        .filter(password_share::Column::PasswordId.eq(request.id))
+        .find_also_related(password::Entity)
+        .filter(password::Column::IsDeleted.eq(false))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find shared password: {}", e)))?
+        .and_then(|(share, password_entry)| password_entry.map(|p| (share, p)))
+        .ok_or(AppError::NotFound("Shared password not found".to_string()))?;
+
+    if share.permission != SharePermission::Editable {
+        return Err(AppError::Authorization(
+            "Shared password is read only".to_string(),
+        ));
+    }
+
+    let recipient = find_user(database_connection, user_id).await?;
+    let item_key = shared_item_key(&share, &recipient, &dek_u8_32)?;
+
+    if password_entry.version != request.version {
+        return Err(shared_version_conflict(database_connection, password_entry, &item_key).await?);
+    }
+
+    let password_id = password_entry.id;
+    let owner_id = password_entry.user_id;
+    let version = request.version;
+
+    let mut updated_password: password::ActiveModel = password_entry.into();
+    updated_password.encrypted_password =
+        Set(Some(encrypt_password(&request.password, &item_key)?));
+
+    if let Some(username) = &request.username {
+        updated_password.encrypted_username = Set(Some(encrypt_password(username, &item_key)?));
+    }
+
+    if let Some(email) = &request.email {
+        updated_password.encrypted_email = Set(Some(encrypt_password(email, &item_key)?));
+    }
+
+    // the owner's blind index needs their DEK, duplicates are then compared decrypted
+    if request.username.is_some() || request.email.is_some() {
+        updated_password.account_blind_index = Set(None);
+    }
+    updated_password.updated_at = Set(Utc::now());
+
+    // custom fields stay owned by the entry's owner
+    let custom_fields = request
+        .custom_fields
+        .map(|custom_fields| build_custom_fields(password_id, owner_id, custom_fields, &item_key))
+        .transpose()?;
+
+    let update_result: Result<i64, TransactionError<DbErr>> = database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                // editable shares change the owner's vault
+                let revision = next_revision(txn, owner_id).await?;
+                updated_password.revision = Set(revision);
+
+                // fails when another update landed since the version was checked
+                updated_password.version = Set(version + 1);
+                password::Entity::update(updated_password)
+                    .filter(password::Column::Version.eq(version))
+                    .exec(txn)
+                    .await?;
+
+                if let Some(custom_fields) = custom_fields {
+                    custom_field::Entity::delete_many()
+                        .filter(custom_field::Column::PasswordId.eq(password_id))
+                        .exec(txn)
+                        .await?;
+
+                    if !custom_fields.is_empty() {
+                        custom_field::Entity::insert_many(custom_fields)
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                Ok(revision)
+            })
+        })
+        .await;
+
+    match update_result {
+        Ok(revision) => {
+            publish_vault_changed(
+                app_state,
+                owner_id,
+                Some(password_id),
+                VaultChangeType::Updated,
+                revision,
+            );
+
+            Ok(GraphqlGenericResponse {
+                success: true,
+                message: "Shared password updated successfully".to_string(),
+            })
+        }
+        Err(TransactionError::Transaction(DbErr::RecordNotUpdated)) => {
+            let password_entry = password::Entity::find()
+                .filter(password::Column::Id.eq(password_id))
+                .one(database_connection.as_ref())
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to find password: {}", e)))?
+                .ok_or(AppError::NotFound("Password not found".to_string()))?;
+
+            Err(shared_version_conflict(database_connection, password_entry, &item_key).await?)
+        }
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to update shared password: {}",
+            e
+        ))),
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+    use sea_orm::{Set, TryIntoModel};
+    use uuid::Uuid;
+
+    use crate::{
+        models::{
+            custom_field,
+            custom_field::CustomFieldType,
+            password,
+            password_share::{self, SharePermission},
+        },
+        services::{
+            crypto::{
+                decrypt_password, encrypt_password, generate_dek, generate_key_pair, open_item_key,
+                seal_item_key, wrap_item_key,
+            },
+            share::*,
+        },
+        utils::error::{AppError, AppResult},
+    };
+
+    fn login_entry(id: Uuid, user_id: Uuid, key: &[u8; 32]) -> AppResult<password::Model> {
+        Ok(password::Model {
+            id,
+            user_id,
+            item_type: password::VaultItemType::Login,
+            website_url: Some("https://github.com".to_string()),
+            canonical_website_url: Some("https://github.com".to_string()),
+            website_host: Some("github.com".to_string()),
+            app_name: None,
+            encrypted_username: Some(encrypt_password("octocat", key)?),
+            encrypted_email: None,
+            encrypted_password: Some(encrypt_password("hunter2", key)?),
+            encrypted_payload: None,
+            payload_version: None,
+            account_blind_index: None,
+            is_deleted: false,
+            folder_id: None,
+            favorite: false,
+            encrypted_item_key: None,
+            collection_id: None,
+            revision: 0,
+            created_revision: 0,
+            version: 1,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    #[test]
+    fn test_rekey_entry() -> AppResult<()> {
+        let dek = generate_dek();
+        let item_key = generate_dek();
+
+        let password_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        let password_entry = password::Model {
+            encrypted_payload: Some(encrypt_password("{\"version\":1}", &dek)?),
+            ..login_entry(password_id, user_id, &dek)?
+        };
+
+        let custom_field = custom_field::Model {
+            id: Uuid::new_v4(),
+            password_id,
+            user_id,
+            position: 0,
+            encrypted_name: encrypt_password("PIN", &dek)?,
+            field_type: CustomFieldType::Hidden,
+            encrypted_value: Some(encrypt_password("1234", &dek)?),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if entry_key(&password_entry, &dek)? != dek {
+            return Err(AppError::Crypto(
+                "Unshared entry should use the DEK".to_string(),
+            ));
+        }
+
+        let (mut rekeyed_password, rekeyed_custom_fields) =
+            rekey_entry(password_entry, vec![custom_field], &dek, &item_key)?;
+        rekeyed_password.encrypted_item_key = Set(Some(wrap_item_key(&item_key, &dek)?));
+
+        let rekeyed_password = rekeyed_password
+            .try_into_model()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let rekeyed_custom_field = rekeyed_custom_fields[0]
+            .clone()
+            .try_into_model()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if entry_key(&rekeyed_password, &dek)? != item_key {
+            return Err(AppError::Crypto("Item key mismatch".to_string()));
+        }
+
+        let username = decrypt_password(
+            rekeyed_password
+                .encrypted_username
+                .as_deref()
+                .unwrap_or_default(),
+            &item_key,
+        )?;
+        let custom_field_value = decrypt_password(
+            rekeyed_custom_field
+                .encrypted_value
+                .as_deref()
+                .unwrap_or_default(),
+            &item_key,
+        )?;
+
+        let payload = decrypt_password(
+            rekeyed_password
+                .encrypted_payload
+                .as_deref()
+                .unwrap_or_default(),
+            &item_key,
+        )?;
+
+        if username != "octocat" || custom_field_value != "1234" || payload != "{\"version\":1}" {
+            return Err(AppError::Crypto("Rekeyed entry mismatch".to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_revoke_share_rekeying() -> AppResult<()> {
+        let owner_id = Uuid::new_v4();
+        let old_item_key = generate_dek();
+        let new_item_key = generate_dek();
+
+        let revoked_dek = generate_dek();
+        let (revoked_public_key, revoked_private_key) = generate_key_pair(&revoked_dek)?;
+        let remaining_dek = generate_dek();
+        let (remaining_public_key, remaining_private_key) = generate_key_pair(&remaining_dek)?;
+
+        let password_entry = login_entry(Uuid::new_v4(), owner_id, &old_item_key)?;
+        let revoked_wrapped_item_key = seal_item_key(&old_item_key, &revoked_public_key)?;
+
+        let remaining_share = password_share::Model {
+            id: Uuid::new_v4(),
+            password_id: password_entry.id,
+            owner_id,
+            recipient_id: Uuid::new_v4(),
+            wrapped_item_key: seal_item_key(&old_item_key, &remaining_public_key)?,
+            permission: SharePermission::ReadOnly,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let (rekeyed_password, _) =
+            rekey_entry(password_entry, vec![], &old_item_key, &new_item_key)?;
+        let rekeyed_password = rekeyed_password
+            .try_into_model()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let encrypted_password = rekeyed_password
+            .encrypted_password
+            .as_deref()
+            .unwrap_or_default();
+
+        // the revoked recipient still opens the old item key, which no longer decrypts the entry
+        let revoked_item_key = open_item_key(
+            &revoked_wrapped_item_key,
+            &revoked_private_key,
+            &revoked_dek,
+        )?;
+
+        if decrypt_password(encrypted_password, &revoked_item_key).is_ok() {
+            return Err(AppError::Crypto(
+                "Revoked recipient can still read the entry".to_string(),
+            ));
+        }
+
+        let public_keys = HashMap::from([(remaining_share.recipient_id, remaining_public_key)]);
+        let resealed_share =
+            reseal_shares(vec![remaining_share.clone()], &public_keys, &new_item_key)?
+                .remove(0)
+                .try_into_model()
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let remaining_item_key = open_item_key(
+            &resealed_share.wrapped_item_key,
+            &remaining_private_key,
+            &remaining_dek,
+        )?;
+
+        if decrypt_password(encrypted_password, &remaining_item_key)? != "hunter2" {
+            return Err(AppError::Crypto(
+                "Remaining recipient cannot read the entry".to_string(),
+            ));
+        }
+
+        if reseal_shares(vec![remaining_share], &HashMap::new(), &new_item_key).is_ok() {
+            return Err(AppError::Crypto(
+                "Share was resealed without a public key".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod tag;
+mod tag_test;
+
+pub use tag::*;
@@ -0,0 +1,309 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use async_graphql::Context;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    Set,
+};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        password_tag, tag,
+        tag_dtos::{
+            CreateTagRequest, DeleteTagRequest, RenameTagRequest, TagResponse, TagsResponse,
+        },
+        user_dtos::UserRedisSession,
+    },
+    services::crypto::{blind_index, decrypt_password, encrypt_password},
+    utils::error::{AppError, AppResult},
+};
+
+pub fn to_tag_response(tag: &tag::Model, dek: &[u8; 32]) -> AppResult<TagResponse> {
+    Ok(TagResponse {
+        id: tag.id,
+        name: decrypt_password(&tag.encrypted_name, dek)?,
+    })
+}
+
+/// Link rows for an entry, after checking every tag belongs to the user
+pub async fn build_password_tags(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    password_id: Uuid,
+    tag_ids: Vec<Uuid>,
+) -> AppResult<Vec<password_tag::ActiveModel>> {
+    let tag_ids: Vec<Uuid> = tag_ids
+        .into_iter()
+        .collect::<HashSet<Uuid>>()
+        .into_iter()
+        .collect();
+
+    if tag_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let owned_tags = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(tag::Column::Id.is_in(tag_ids.clone()))
+        .count(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find tags: {}", e)))?;
+
+    if owned_tags != tag_ids.len() as u64 {
+        return Err(AppError::NotFound("Tag not found".to_string()));
+    }
+
+    Ok(tag_ids
+        .into_iter()
+        .map(|tag_id| password_tag::ActiveModel {
+            password_id: Set(password_id),
+            tag_id: Set(tag_id),
+        })
+        .collect())
+}
+
+/// Tags are personal, members of a collection only see their own tags on its entries
+pub async fn find_password_tags(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    password_ids: Vec<Uuid>,
+) -> AppResult<HashMap<Uuid, Vec<tag::Model>>> {
+    let password_tags = password_tag::Entity::find()
+        .filter(password_tag::Column::PasswordId.is_in(password_ids))
+        .find_also_related(tag::Entity)
+        .filter(tag::Column::UserId.eq(user_id))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get password tags: {}", e)))?;
+
+    let mut tags_by_password: HashMap<Uuid, Vec<tag::Model>> = HashMap::new();
+
+    for (password_tag, tag) in password_tags {
+        if let Some(tag) = tag {
+            tags_by_password
+                .entry(password_tag.password_id)
+                .or_default()
+                .push(tag);
+        }
+    }
+
+    Ok(tags_by_password)
+}
+
+/// Marks name indexes written under an earlier blind index key, they are rebuilt at login
+const STALE_NAME_BLIND_INDEX_PREFIX: &str = "stale:";
+
+/// Index the names of tags marked stale again, once the DEK of their user is available
+pub async fn rebuild_stale_tag_indexes(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    dek: &[u8; 32],
+) -> AppResult<()> {
+    let stale_tags = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(tag::Column::NameBlindIndex.starts_with(STALE_NAME_BLIND_INDEX_PREFIX))
+        .all(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find tags: {}", e)))?;
+
+    for stale_tag in stale_tags {
+        let name = decrypt_password(&stale_tag.encrypted_name, dek)?;
+
+        let mut updated_tag: tag::ActiveModel = stale_tag.into();
+        updated_tag.name_blind_index = Set(blind_index(&name, dek)?);
+
+        updated_tag
+            .update(database_connection)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to update tag: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+async fn ensure_tag_name_available(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    name_blind_index: &str,
+) -> AppResult<()> {
+    let existing_tag = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(tag::Column::NameBlindIndex.eq(name_blind_index))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find tag: {}", e)))?;
+
+    if existing_tag.is_some() {
+        return Err(AppError::Conflict("Tag already exists".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn create_tag(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: CreateTagRequest,
+) -> AppResult<GraphqlResponse<TagResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let name = request.name.trim();
+    let name_blind_index = blind_index(name, &dek_u8_32)?;
+
+    ensure_tag_name_available(database_connection, user_id, &name_blind_index).await?;
+
+    let tag = tag::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        encrypted_name: Set(encrypt_password(name, &dek_u8_32)?),
+        name_blind_index: Set(name_blind_index),
+        ..Default::default()
+    }
+    .insert(database_connection.as_ref())
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to save tag: {}", e)))?;
+
+    Ok(GraphqlResponse::<TagResponse> {
+        success: true,
+        message: "Tag created successfully".to_string(),
+        data: to_tag_response(&tag, &dek_u8_32)?,
+    })
+}
+
+pub async fn rename_tag(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: RenameTagRequest,
+) -> AppResult<GraphqlResponse<TagResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let tag = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(tag::Column::Id.eq(request.id))
+        .one(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find tag: {}", e)))?
+        .ok_or(AppError::NotFound("Tag not found".to_string()))?;
+
+    let name = request.name.trim();
+    let name_blind_index = blind_index(name, &dek_u8_32)?;
+
+    if name_blind_index != tag.name_blind_index {
+        ensure_tag_name_available(database_connection, user_id, &name_blind_index).await?;
+    }
+
+    let mut updated_tag: tag::ActiveModel = tag.into();
+    updated_tag.encrypted_name = Set(encrypt_password(name, &dek_u8_32)?);
+    updated_tag.name_blind_index = Set(name_blind_index);
+    updated_tag.updated_at = Set(Utc::now());
+
+    let tag = updated_tag
+        .update(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to update tag: {}", e)))?;
+
+    Ok(GraphqlResponse::<TagResponse> {
+        success: true,
+        message: "Tag renamed successfully".to_string(),
+        data: to_tag_response(&tag, &dek_u8_32)?,
+    })
+}
+
+pub async fn delete_tag(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeleteTagRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    // links to entries go with the tag through the foreign key
+    tag::Entity::delete_many()
+        .filter(tag::Column::UserId.eq(user_id))
+        .filter(tag::Column::Id.eq(request.id))
+        .exec(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to delete tag: {}", e)))?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Tag deleted successfully".to_string(),
+    })
+}
+
+pub async fn get_tags(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+) -> AppResult<GraphqlResponse<TagsResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let tags = tag::Entity::find()
+        .filter(tag::Column::UserId.eq(user_id))
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get tags: {}", e)))?;
+
+    let mut tags = tags
+        .iter()
+        .map(|tag| to_tag_response(tag, &dek_u8_32))
+        .collect::<AppResult<Vec<TagResponse>>>()?;
+
+    // names are encrypted, so they can only be sorted once decrypted
+    tags.sort_by_key(|tag| tag.name.to_lowercase());
+
+    Ok(GraphqlResponse::<TagsResponse> {
+        success: true,
+        message: if tags.is_empty() {
+            "No tags found".to_string()
+        } else {
+            "Tags found".to_string()
+        },
+        data: TagsResponse { tags },
+    })
+}
@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        models::tag,
+        services::{
+            crypto::{blind_index, encrypt_password, generate_dek},
+            tag::*,
+        },
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_tag_response() -> AppResult<()> {
+        let dek = generate_dek();
+
+        let tag = tag::Model {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            encrypted_name: encrypt_password("Finance", &dek)?,
+            name_blind_index: blind_index("Finance", &dek)?,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if to_tag_response(&tag, &dek)?.name != "Finance" {
+            return Err(AppError::Crypto("Tag name mismatch".to_string()));
+        }
+
+        // names differing only in case are the same tag
+        if blind_index(" finance ", &dek)? != tag.name_blind_index {
+            return Err(AppError::Crypto(
+                "Tag names should compare case-insensitively".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod throttle;
+mod throttle_test;
+
+pub use throttle::*;
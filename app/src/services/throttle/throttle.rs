@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use async_graphql::Context;
+use redis::{Commands, ConnectionLike};
+
+use crate::{
+    configs::env::Env,
+    dtos::{app_state::AppState, graphql_context::GraphQLContext},
+    services::session::client_ip,
+    utils::error::{AppError, AppResult},
+};
+
+/// Failures allowed before each further attempt has to wait
+pub const FREE_FAILURES: u64 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthAction {
+    Login,
+    /// Guessing recovery codes, counted apart so a login lockout can be lifted through recovery
+    Recovery,
+    /// Re-entering the master password in a session, counted against the user id
+    MasterPassword,
+}
+
+impl AuthAction {
+    fn name(&self) -> &'static str {
+        match self {
+            AuthAction::Login => "login",
+            AuthAction::Recovery => "recovery",
+            AuthAction::MasterPassword => "master_password",
+        }
+    }
+}
+
+/// An attempt to prove who one is, failures count against its account and its address
+#[derive(Clone, Debug)]
+pub struct AuthAttempt {
+    pub action: AuthAction,
+    /// The email signing in or recovering, the user id once signed in
+    pub account: String,
+    pub ip_address: Option<String>,
+}
+
+impl AuthAttempt {
+    pub fn new(ctx: &Context<'_>, action: AuthAction, account: &str) -> Self {
+        let ip_address = ctx
+            .data::<Arc<AppState>>()
+            .ok()
+            .zip(ctx.data::<Arc<GraphQLContext>>().ok())
+            .and_then(|(app_state, gql_ctx)| {
+                client_ip(gql_ctx, &app_state.env_variables.trusted_proxies)
+            });
+
+        AuthAttempt {
+            action,
+            account: account.trim().to_lowercase(),
+            ip_address,
+        }
+    }
+
+    /// Redis key suffix of each scope with the failures it allows
+    fn scopes(&self, env_variables: &Env) -> Vec<(String, u64)> {
+        let mut scopes = vec![(
+            account_scope(self.action, &self.account),
+            env_variables.auth_max_failures,
+        )];
+
+        if let Some(ip_address) = &self.ip_address {
+            scopes.push((
+                format!("{}:ip:{}", self.action.name(), ip_address),
+                env_variables.auth_ip_max_failures,
+            ));
+        }
+
+        scopes
+    }
+}
+
+fn account_scope(action: AuthAction, account: &str) -> String {
+    format!(
+        "{}:account:{}",
+        action.name(),
+        account.trim().to_lowercase()
+    )
+}
+
+fn failures_key(scope: &str) -> String {
+    format!("auth_failures:{}", scope)
+}
+
+fn blocked_key(scope: &str) -> String {
+    format!("auth_blocked:{}", scope)
+}
+
+/// Seconds to wait after the given number of failures: none for the first few,
+/// then doubling, then the full lockout once the limit is reached
+pub fn backoff_seconds(failures: u64, max_failures: u64, lockout_seconds: u64) -> u64 {
+    if failures >= max_failures {
+        return lockout_seconds;
+    }
+
+    if failures < FREE_FAILURES {
+        return 0;
+    }
+
+    let exponent = (failures - FREE_FAILURES).min(32) as u32;
+
+    2u64.pow(exponent).min(lockout_seconds)
+}
+
+/// Count the attempt against each of its scopes before its credential is checked, so
+/// that concurrent guesses cannot all pass before any of them is recorded as failed
+pub fn reserve_attempt<C: ConnectionLike>(
+    redis_connection: &mut C,
+    env_variables: &Env,
+    attempt: &AuthAttempt,
+) -> AppResult<()> {
+    let lockout_seconds = env_variables.auth_lockout_minutes * 60;
+    let scopes = attempt.scopes(env_variables);
+
+    let mut retry_after_seconds = 0;
+
+    // waiting out a backoff does not use up an attempt
+    for (scope, _) in &scopes {
+        let ttl = redis_connection
+            .ttl::<String, i64>(blocked_key(scope))
+            .map_err(|e| AppError::Internal(format!("Failed to check attempts: {}", e)))?;
+
+        retry_after_seconds = retry_after_seconds.max(ttl.max(0) as u64);
+    }
+
+    if retry_after_seconds == 0 {
+        for (scope, max_failures) in &scopes {
+            // failures are forgotten once the scope stays quiet for a lockout
+            let (attempts,): (u64,) = redis::pipe()
+                .atomic()
+                .incr(failures_key(scope), 1)
+                .expire(failures_key(scope), lockout_seconds as i64)
+                .ignore()
+                .query(redis_connection)
+                .map_err(|e| AppError::Internal(format!("Failed to reserve attempt: {}", e)))?;
+
+            if attempts > *max_failures {
+                retry_after_seconds = lockout_seconds;
+            }
+        }
+    }
+
+    if retry_after_seconds > 0 {
+        return Err(AppError::TooManyRequests {
+            message: "Too many failed attempts, try again later".to_string(),
+            retry_after_seconds,
+        });
+    }
+
+    Ok(())
+}
+
+/// Keep a reserved attempt as a failure, slowing down the attempts after it
+pub fn record_failure<C: ConnectionLike>(
+    redis_connection: &mut C,
+    env_variables: &Env,
+    attempt: &AuthAttempt,
+) -> AppResult<()> {
+    let lockout_seconds = env_variables.auth_lockout_minutes * 60;
+
+    for (scope, max_failures) in attempt.scopes(env_variables) {
+        let failures = redis_connection
+            .get::<String, Option<u64>>(failures_key(&scope))
+            .map_err(|e| AppError::Internal(format!("Failed to record attempt: {}", e)))?
+            .unwrap_or_default();
+
+        let backoff = backoff_seconds(failures, max_failures, lockout_seconds);
+
+        if backoff > 0 {
+            redis_connection
+                .set_ex::<String, u64, ()>(blocked_key(&scope), failures, backoff)
+                .map_err(|e| AppError::Internal(format!("Failed to record attempt: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Give back a reserved attempt that succeeded, it is no failure
+pub fn release_attempt<C: ConnectionLike>(
+    redis_connection: &mut C,
+    env_variables: &Env,
+    attempt: &AuthAttempt,
+) -> AppResult<()> {
+    for (scope, _) in attempt.scopes(env_variables) {
+        let attempts = redis_connection
+            .decr::<String, u64, i64>(failures_key(&scope), 1)
+            .map_err(|e| AppError::Internal(format!("Failed to release attempt: {}", e)))?;
+
+        // the count expired while the attempt ran
+        if attempts <= 0 {
+            redis_connection
+                .del::<String, ()>(failures_key(&scope))
+                .map_err(|e| AppError::Internal(format!("Failed to release attempt: {}", e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Forget the failures of an account after it proved itself, those of its addresses remain
+pub fn clear_failures<C: ConnectionLike>(
+    redis_connection: &mut C,
+    action: AuthAction,
+    account: &str,
+) -> AppResult<()> {
+    let scope = account_scope(action, account);
+
+    redis_connection
+        .del::<&[String], ()>(&[failures_key(&scope), blocked_key(&scope)])
+        .map_err(|e| AppError::Internal(format!("Failed to clear attempts: {}", e)))
+}
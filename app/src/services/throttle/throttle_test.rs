@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod test {
+    use crate::{
+        configs::env::Env,
+        services::throttle::{
+            AuthAction, AuthAttempt, FREE_FAILURES, backoff_seconds, clear_failures,
+            record_failure, release_attempt, reserve_attempt,
+        },
+        utils::{
+            error::{AppError, AppResult},
+            memory_redis::MemoryRedis,
+        },
+    };
+
+    fn login_attempt(account: &str, ip_address: &str) -> AuthAttempt {
+        AuthAttempt {
+            action: AuthAction::Login,
+            account: account.to_string(),
+            ip_address: Some(ip_address.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_backoff_seconds() -> AppResult<()> {
+        let lockout_seconds = 15 * 60;
+
+        for failures in 0..FREE_FAILURES {
+            if backoff_seconds(failures, 5, lockout_seconds) != 0 {
+                return Err(AppError::Internal(
+                    "First failures were slowed down".to_string(),
+                ));
+            }
+        }
+
+        if backoff_seconds(FREE_FAILURES, 5, lockout_seconds) != 1
+            || backoff_seconds(FREE_FAILURES + 1, 5, lockout_seconds) != 2
+        {
+            return Err(AppError::Internal("Backoff did not double".to_string()));
+        }
+
+        if backoff_seconds(5, 5, lockout_seconds) != lockout_seconds {
+            return Err(AppError::Internal(
+                "Limit of failures did not lock out".to_string(),
+            ));
+        }
+
+        // a high limit never waits longer than a lockout
+        if backoff_seconds(40, 50, lockout_seconds) != lockout_seconds {
+            return Err(AppError::Internal(
+                "Backoff grew past the lockout".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reserve_attempt_locks_out() -> AppResult<()> {
+        let mut redis_connection = MemoryRedis::default();
+        let env_variables = Env {
+            // the backoff between failures would hide the limit
+            auth_max_failures: FREE_FAILURES,
+            ..Env::for_tests()
+        };
+
+        let attempt = login_attempt("user@example.com", "203.0.113.9");
+
+        for _ in 0..FREE_FAILURES {
+            reserve_attempt(&mut redis_connection, &env_variables, &attempt)?;
+        }
+
+        // attempts are counted when they start, before any of them failed
+        match reserve_attempt(&mut redis_connection, &env_variables, &attempt) {
+            Err(AppError::TooManyRequests {
+                retry_after_seconds,
+                ..
+            }) if retry_after_seconds == env_variables.auth_lockout_minutes * 60 => {}
+            _ => {
+                return Err(AppError::Internal(
+                    "Concurrent attempts passed the limit".to_string(),
+                ));
+            }
+        }
+
+        // another account from the same address is still let through
+        reserve_attempt(
+            &mut redis_connection,
+            &env_variables,
+            &login_attempt("other@example.com", "203.0.113.9"),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_failure_backs_off() -> AppResult<()> {
+        let mut redis_connection = MemoryRedis::default();
+        let env_variables = Env::for_tests();
+
+        let attempt = login_attempt("user@example.com", "203.0.113.9");
+
+        for _ in 0..FREE_FAILURES {
+            reserve_attempt(&mut redis_connection, &env_variables, &attempt)?;
+            record_failure(&mut redis_connection, &env_variables, &attempt)?;
+        }
+
+        if !matches!(
+            reserve_attempt(&mut redis_connection, &env_variables, &attempt),
+            Err(AppError::TooManyRequests { .. })
+        ) {
+            return Err(AppError::Internal(
+                "Attempt was not slowed down after failures".to_string(),
+            ));
+        }
+
+        // proving the account lifts its backoff, the one of the address remains
+        clear_failures(&mut redis_connection, AuthAction::Login, &attempt.account)?;
+
+        if reserve_attempt(&mut redis_connection, &env_variables, &attempt).is_ok() {
+            return Err(AppError::Internal(
+                "Clearing the account lifted the backoff of its address".to_string(),
+            ));
+        }
+
+        reserve_attempt(
+            &mut redis_connection,
+            &env_variables,
+            &login_attempt("user@example.com", "198.51.100.7"),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_attempt() -> AppResult<()> {
+        let mut redis_connection = MemoryRedis::default();
+        let env_variables = Env {
+            auth_max_failures: 2,
+            ..Env::for_tests()
+        };
+
+        let attempt = login_attempt("user@example.com", "203.0.113.9");
+
+        // successful attempts never add up to a lockout
+        for _ in 0..10 {
+            reserve_attempt(&mut redis_connection, &env_variables, &attempt)?;
+            release_attempt(&mut redis_connection, &env_variables, &attempt)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod uri;
+mod uri_test;
+
+pub use uri::*;
@@ -0,0 +1,172 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use regex::Regex;
+use url::Url;
+
+use crate::{
+    models::password_uri::UriMatchType,
+    utils::error::{AppError, AppResult},
+};
+
+/// Parse a user supplied URL, assuming https when no scheme is given
+pub fn parse_url(raw_url: &str) -> AppResult<Url> {
+    let raw_url = raw_url.trim();
+
+    let with_scheme = if raw_url.contains("://") {
+        raw_url.to_string()
+    } else {
+        format!("https://{}", raw_url)
+    };
+
+    Url::parse(&with_scheme).map_err(|e| AppError::Validation(format!("Invalid URL: {}", e)))
+}
+
+/// Registrable domain (eTLD+1) of a host, falling back to the host itself
+pub fn base_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_lowercase();
+
+    if host.starts_with('[') || host.parse::<IpAddr>().is_ok() {
+        return host;
+    }
+
+    psl::domain_str(&host)
+        .map(|domain| domain.to_string())
+        .unwrap_or(host)
+}
+
+/// Check that a stored URI is usable with the given match strategy
+pub fn validate_uri(uri: &str, match_type: &UriMatchType) -> AppResult<()> {
+    match match_type {
+        UriMatchType::Regex => Regex::new(uri)
+            .map(|_| ())
+            .map_err(|e| AppError::Validation(format!("Invalid URI regex: {}", e))),
+        UriMatchType::StartsWith | UriMatchType::Never => Ok(()),
+        UriMatchType::BaseDomain | UriMatchType::Host => {
+            let url = parse_url(uri)?;
+
+            if url.host_str().is_none() {
+                return Err(AppError::Validation("URI has no host".to_string()));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Prefix compared by starts-with rules, so case and trailing slashes do not matter
+fn starts_with_prefix(url: &str) -> String {
+    normalize_url(url)
+        .map(|normalized_url| normalized_url.canonical)
+        .unwrap_or_else(|_| url.trim().to_string())
+}
+
+/// Matches stored URIs against one page, each regex is compiled only once
+pub struct UriMatcher {
+    page_url: Url,
+    page_prefix: String,
+    regexes: HashMap<String, Option<Regex>>,
+}
+
+impl UriMatcher {
+    pub fn new(page_url: Url) -> Self {
+        let page_prefix = starts_with_prefix(page_url.as_str());
+
+        UriMatcher {
+            page_url,
+            page_prefix,
+            regexes: HashMap::new(),
+        }
+    }
+
+    /// Check whether the page is matched by a stored URI
+    pub fn matches(&mut self, uri: &str, match_type: &UriMatchType) -> bool {
+        let page_url = &self.page_url;
+
+        match match_type {
+            UriMatchType::Never => false,
+            UriMatchType::StartsWith => self.page_prefix.starts_with(&starts_with_prefix(uri)),
+            UriMatchType::Regex => self
+                .regexes
+                .entry(uri.to_string())
+                .or_insert_with(|| Regex::new(uri).ok())
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(page_url.as_str())),
+            UriMatchType::Host => {
+                let Ok(uri_url) = parse_url(uri) else {
+                    return false;
+                };
+
+                uri_url.host_str().is_some()
+                    && uri_url.host_str() == page_url.host_str()
+                    && uri_url.port_or_known_default() == page_url.port_or_known_default()
+            }
+            UriMatchType::BaseDomain => {
+                let Ok(uri_url) = parse_url(uri) else {
+                    return false;
+                };
+
+                match (uri_url.host_str(), page_url.host_str()) {
+                    (Some(uri_host), Some(page_host)) => {
+                        base_domain(uri_host) == base_domain(page_host)
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Query parameters that only track where a visit came from
+const TRACKING_QUERY_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "mc_cid", "mc_eid", "igshid",
+    "yclid", "_hsenc", "_hsmi",
+];
+
+pub struct NormalizedUrl {
+    pub canonical: String,
+    pub host: String,
+}
+
+fn is_tracking_query_param(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("utm_") || TRACKING_QUERY_PARAMS.contains(&name.as_str())
+}
+
+/// Canonical form of a website URL, used as the duplicate-check key
+pub fn normalize_url(raw_url: &str) -> AppResult<NormalizedUrl> {
+    // the url crate lowercases the host, converts IDN to punycode and drops default ports
+    let url = parse_url(raw_url)?;
+
+    let host = url
+        .host_str()
+        .ok_or(AppError::Validation("URL has no host".to_string()))?
+        .trim_end_matches('.')
+        .to_string();
+
+    let mut canonical = format!("{}://{}", url.scheme(), host);
+
+    if let Some(port) = url.port() {
+        canonical.push_str(&format!(":{}", port));
+    }
+
+    canonical.push_str(url.path().trim_end_matches('/'));
+
+    let query = url
+        .query_pairs()
+        .filter(|(name, _)| !is_tracking_query_param(name))
+        .fold(
+            url::form_urlencoded::Serializer::new(String::new()),
+            |mut serializer, (name, value)| {
+                serializer.append_pair(&name, &value);
+                serializer
+            },
+        )
+        .finish();
+
+    if !query.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&query);
+    }
+
+    Ok(NormalizedUrl { canonical, host })
+}
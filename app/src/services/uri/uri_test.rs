@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod test {
+    use crate::{
+        models::password_uri::UriMatchType,
+        services::uri::*,
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_base_domain() -> AppResult<()> {
+        let cases = [
+            ("github.com", "github.com"),
+            ("gist.github.com", "github.com"),
+            ("accounts.google.co.uk", "google.co.uk"),
+            ("Login.Example.COM.", "example.com"),
+            ("192.168.1.10", "192.168.1.10"),
+        ];
+
+        for (host, expected) in cases {
+            if base_domain(host) != expected {
+                return Err(AppError::Internal(format!(
+                    "Base domain of {} should be {}",
+                    host, expected
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_uri_matches() -> AppResult<()> {
+        let mut uri_matcher = UriMatcher::new(parse_url(
+            "https://accounts.github.com/login?return_to=%2F",
+        )?);
+
+        let cases = [
+            ("github.com", UriMatchType::BaseDomain, true),
+            ("https://gitlab.com", UriMatchType::BaseDomain, false),
+            ("accounts.github.com", UriMatchType::Host, true),
+            ("github.com", UriMatchType::Host, false),
+            (
+                "https://accounts.github.com:8443",
+                UriMatchType::Host,
+                false,
+            ),
+            (
+                "https://accounts.github.com/login",
+                UriMatchType::StartsWith,
+                true,
+            ),
+            (
+                "https://accounts.github.com/logout",
+                UriMatchType::StartsWith,
+                false,
+            ),
+            (
+                "HTTPS://Accounts.GitHub.com/login/",
+                UriMatchType::StartsWith,
+                true,
+            ),
+            (r"^https://[a-z]+\.github\.com/", UriMatchType::Regex, true),
+            (r"^https://github\.com/", UriMatchType::Regex, false),
+            ("github.com", UriMatchType::Never, false),
+        ];
+
+        for (uri, match_type, expected) in cases {
+            if uri_matcher.matches(uri, &match_type) != expected {
+                return Err(AppError::Internal(format!(
+                    "{} with {:?} should match: {}",
+                    uri, match_type, expected
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_uri() -> AppResult<()> {
+        validate_uri("github.com", &UriMatchType::BaseDomain)?;
+        validate_uri(r"^https://.*\.github\.com/", &UriMatchType::Regex)?;
+
+        if validate_uri("^(unclosed", &UriMatchType::Regex).is_ok() {
+            return Err(AppError::Internal("Invalid regex was accepted".to_string()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_url() -> AppResult<()> {
+        let cases = [
+            ("https://GitHub.com/", "https://github.com", "github.com"),
+            ("github.com", "https://github.com", "github.com"),
+            (
+                "http://github.com/login",
+                "http://github.com/login",
+                "github.com",
+            ),
+            (
+                "https://github.com:443/login/?utm_source=mail&tab=security&fbclid=abc#top",
+                "https://github.com/login?tab=security",
+                "github.com",
+            ),
+            (
+                "https://example.com:8443/",
+                "https://example.com:8443",
+                "example.com",
+            ),
+            (
+                "https://bücher.de/",
+                "https://xn--bcher-kva.de",
+                "xn--bcher-kva.de",
+            ),
+        ];
+
+        for (raw_url, canonical, host) in cases {
+            let normalized_url = normalize_url(raw_url)?;
+
+            if normalized_url.canonical != canonical || normalized_url.host != host {
+                return Err(AppError::Internal(format!(
+                    "{} normalized to {} ({})",
+                    raw_url, normalized_url.canonical, normalized_url.host
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
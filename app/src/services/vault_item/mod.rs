@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod vault_item;
+mod vault_item_test;
+
+pub use vault_item::*;
@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use async_graphql::Context;
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter,
+    QueryOrder, Set, TransactionError, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    dtos::{
+        app_state::AppState,
+        response::{GraphqlGenericResponse, GraphqlResponse},
+    },
+    models::{
+        attachment, custom_field,
+        password::{self, VaultItemType},
+        password_dtos::DeletePasswordRequest,
+        user_dtos::UserRedisSession,
+        vault_item_dtos::{
+            AddVaultItemRequest, GetVaultItemRequest, GetVaultItemsRequest, UpdateVaultItemRequest,
+            VaultItemPayload, VaultItemResponse, VaultItemsResponse,
+        },
+    },
+    services::{
+        attachment::{
+            delete_attachment_blobs, find_attachments, find_entry_attachments,
+            to_attachment_responses,
+        },
+        crypto::{decrypt_password, encrypt_password},
+        custom_field::{build_custom_fields, find_custom_fields, to_custom_field_responses},
+    },
+    utils::error::{AppError, AppResult},
+};
+
+/// Version written with every new or updated payload
+pub const VAULT_ITEM_PAYLOAD_VERSION: i32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct StoredVaultItemPayload {
+    version: i32,
+    #[serde(flatten)]
+    payload: VaultItemPayload,
+}
+
+/// Serialize and encrypt a payload with DEK
+pub fn encrypt_vault_item_payload(payload: &VaultItemPayload, dek: &[u8; 32]) -> AppResult<String> {
+    let stored_payload = serde_json::to_string(&StoredVaultItemPayload {
+        version: VAULT_ITEM_PAYLOAD_VERSION,
+        payload: payload.clone(),
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to serialize payload: {}", e)))?;
+
+    encrypt_password(&stored_payload, dek)
+}
+
+/// Decrypt a payload with DEK, upgrading it from older schema versions
+pub fn decrypt_vault_item_payload(
+    encrypted_payload: &str,
+    dek: &[u8; 32],
+) -> AppResult<(i32, VaultItemPayload)> {
+    let stored_payload = decrypt_password(encrypted_payload, dek)?;
+
+    let value = serde_json::from_str::<serde_json::Value>(&stored_payload)
+        .map_err(|e| AppError::Crypto(format!("Invalid payload: {}", e)))?;
+
+    let version = value
+        .get("version")
+        .and_then(|version| version.as_i64())
+        .ok_or(AppError::Crypto("Payload has no version".to_string()))? as i32;
+
+    // add the upgrade steps here whenever VAULT_ITEM_PAYLOAD_VERSION is bumped
+    if version > VAULT_ITEM_PAYLOAD_VERSION {
+        return Err(AppError::Internal(format!(
+            "Unsupported payload version {}",
+            version
+        )));
+    }
+
+    let stored_payload = serde_json::from_value::<StoredVaultItemPayload>(value)
+        .map_err(|e| AppError::Crypto(format!("Invalid payload: {}", e)))?;
+
+    Ok((stored_payload.version, stored_payload.payload))
+}
+
+fn to_vault_item_response(
+    vault_item: &password::Model,
+    custom_fields: Vec<custom_field::Model>,
+    attachments: Vec<attachment::Model>,
+    dek: &[u8; 32],
+) -> AppResult<VaultItemResponse> {
+    let encrypted_payload = vault_item
+        .encrypted_payload
+        .as_ref()
+        .ok_or(AppError::Internal("Vault item has no payload".to_string()))?;
+
+    let (payload_version, payload) = decrypt_vault_item_payload(encrypted_payload, dek)?;
+
+    Ok(VaultItemResponse {
+        id: vault_item.id,
+        item_type: vault_item.item_type,
+        payload_version,
+        payload,
+        custom_fields: to_custom_field_responses(custom_fields, dek)?,
+        attachments: to_attachment_responses(attachments, dek)?,
+        created_at: vault_item.created_at,
+        updated_at: vault_item.updated_at,
+    })
+}
+
+/// Decrypt vault items together with their custom fields and attachments
+async fn to_vault_item_responses(
+    database_connection: &DatabaseConnection,
+    vault_items: &[password::Model],
+    dek: &[u8; 32],
+) -> AppResult<Vec<VaultItemResponse>> {
+    let vault_item_ids: Vec<Uuid> = vault_items.iter().map(|v| v.id).collect();
+
+    let mut custom_fields_by_item =
+        find_custom_fields(database_connection, vault_item_ids.clone()).await?;
+    let mut attachments_by_item = find_attachments(database_connection, vault_item_ids).await?;
+
+    vault_items
+        .iter()
+        .map(|vault_item| {
+            to_vault_item_response(
+                vault_item,
+                custom_fields_by_item
+                    .remove(&vault_item.id)
+                    .unwrap_or_default(),
+                attachments_by_item
+                    .remove(&vault_item.id)
+                    .unwrap_or_default(),
+                dek,
+            )
+        })
+        .collect()
+}
+
+async fn find_vault_item(
+    database_connection: &DatabaseConnection,
+    user_id: Uuid,
+    id: Uuid,
+) -> AppResult<password::Model> {
+    password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::ItemType.ne(VaultItemType::Login))
+        .filter(password::Column::Id.eq(id))
+        .one(database_connection)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to find vault item: {}", e)))?
+        .ok_or(AppError::NotFound("Vault item not found".to_string()))
+}
+
+pub async fn add_vault_item(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: AddVaultItemRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let payload: VaultItemPayload = request.payload.into();
+    let encrypted_payload = encrypt_vault_item_payload(&payload, &dek_u8_32)?;
+
+    let vault_item_id = Uuid::new_v4();
+
+    let vault_item_model = password::ActiveModel {
+        id: Set(vault_item_id),
+        user_id: Set(user_id),
+        item_type: Set(payload.item_type()),
+        encrypted_payload: Set(Some(encrypted_payload)),
+        payload_version: Set(Some(VAULT_ITEM_PAYLOAD_VERSION)),
+        ..Default::default()
+    };
+
+    let custom_fields = build_custom_fields(
+        vault_item_id,
+        user_id,
+        request.custom_fields.unwrap_or_default(),
+        &dek_u8_32,
+    )?;
+
+    database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                vault_item_model.insert(txn).await?;
+
+                if !custom_fields.is_empty() {
+                    custom_field::Entity::insert_many(custom_fields)
+                        .exec(txn)
+                        .await?;
+                }
+
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to save vault item: {}", e))
+        })?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Vault item added successfully".to_string(),
+    })
+}
+
+pub async fn update_vault_item(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: UpdateVaultItemRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let vault_item = find_vault_item(database_connection, user_id, request.id).await?;
+
+    let payload: VaultItemPayload = request.payload.into();
+
+    if payload.item_type() != vault_item.item_type {
+        return Err(AppError::Validation(
+            "Vault item type cannot be changed".to_string(),
+        ));
+    }
+
+    let encrypted_payload = encrypt_vault_item_payload(&payload, &dek_u8_32)?;
+
+    let mut updated_vault_item: password::ActiveModel = vault_item.into();
+    updated_vault_item.encrypted_payload = Set(Some(encrypted_payload));
+    updated_vault_item.payload_version = Set(Some(VAULT_ITEM_PAYLOAD_VERSION));
+    updated_vault_item.updated_at = Set(Utc::now());
+
+    let vault_item_id = request.id;
+    let custom_fields = request
+        .custom_fields
+        .map(|custom_fields| build_custom_fields(vault_item_id, user_id, custom_fields, &dek_u8_32))
+        .transpose()?;
+
+    database_connection
+        .transaction(move |txn| {
+            Box::pin(async move {
+                updated_vault_item.update(txn).await?;
+
+                // custom fields are replaced as a whole when given
+                if let Some(custom_fields) = custom_fields {
+                    custom_field::Entity::delete_many()
+                        .filter(custom_field::Column::PasswordId.eq(vault_item_id))
+                        .exec(txn)
+                        .await?;
+
+                    if !custom_fields.is_empty() {
+                        custom_field::Entity::insert_many(custom_fields)
+                            .exec(txn)
+                            .await?;
+                    }
+                }
+
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|e: TransactionError<DbErr>| {
+            AppError::Internal(format!("Failed to update vault item: {}", e))
+        })?;
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Vault item updated successfully".to_string(),
+    })
+}
+
+pub async fn delete_vault_item(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: DeletePasswordRequest,
+) -> AppResult<GraphqlGenericResponse> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let attachments = find_entry_attachments(database_connection, request.id).await?;
+
+    let delete_result = password::Entity::delete_many()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::ItemType.ne(VaultItemType::Login))
+        .filter(password::Column::Id.eq(request.id))
+        .exec(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to delete vault item: {}", e)))?;
+
+    // the id may belong to a login, whose attachments must stay
+    if delete_result.rows_affected > 0 {
+        delete_attachment_blobs(app_state.blob_store.as_ref(), &attachments).await?;
+    }
+
+    Ok(GraphqlGenericResponse {
+        success: true,
+        message: "Vault item deleted successfully".to_string(),
+    })
+}
+
+pub async fn get_vault_item(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: GetVaultItemRequest,
+) -> AppResult<GraphqlResponse<VaultItemResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let vault_item = find_vault_item(database_connection, user_id, request.id).await?;
+
+    let vault_item_response =
+        to_vault_item_responses(database_connection, &[vault_item], &dek_u8_32)
+            .await?
+            .remove(0);
+
+    Ok(GraphqlResponse::<VaultItemResponse> {
+        success: true,
+        message: "Vault item found".to_string(),
+        data: vault_item_response,
+    })
+}
+
+pub async fn get_vault_items(
+    ctx: &Context<'_>,
+    user_redis_session: &UserRedisSession,
+    request: GetVaultItemsRequest,
+) -> AppResult<GraphqlResponse<VaultItemsResponse>> {
+    let app_state = ctx
+        .data::<Arc<AppState>>()
+        .map_err(|_| AppError::Internal("App State is not passed".to_string()))?;
+
+    let database_connection = &app_state.database_connection;
+    let user_id = user_redis_session.id;
+
+    let dek_u8_32: [u8; 32] = user_redis_session
+        .dek
+        .clone()
+        .try_into()
+        .map_err(|_| AppError::Crypto("Unable to convert DEK to [u8; 32]".to_string()))?;
+
+    let mut vault_items_select = password::Entity::find()
+        .filter(password::Column::UserId.eq(user_id))
+        .filter(password::Column::ItemType.ne(VaultItemType::Login));
+
+    if let Some(item_type) = request.item_type {
+        vault_items_select = vault_items_select.filter(password::Column::ItemType.eq(item_type));
+    }
+
+    let vault_items = vault_items_select
+        .order_by(password::Column::CreatedAt, Order::Desc)
+        .all(database_connection.as_ref())
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to get vault items: {}", e)))?;
+
+    let items = to_vault_item_responses(database_connection, &vault_items, &dek_u8_32).await?;
+
+    Ok(GraphqlResponse::<VaultItemsResponse> {
+        success: true,
+        message: if items.is_empty() {
+            "No vault items found".to_string()
+        } else {
+            "Vault items found".to_string()
+        },
+        data: VaultItemsResponse { items },
+    })
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod test {
+    use crate::{
+        models::vault_item_dtos::{Card, SecureNote, VaultItemPayload},
+        services::{
+            crypto::{encrypt_password, generate_dek},
+            vault_item::*,
+        },
+        utils::error::{AppError, AppResult},
+    };
+
+    #[test]
+    fn test_vault_item_payload() -> AppResult<()> {
+        let dek = generate_dek();
+
+        let payloads = [
+            VaultItemPayload::SecureNote(SecureNote {
+                title: "Wifi".to_string(),
+                note: "ssid: home\npassword: hunter2".to_string(),
+            }),
+            VaultItemPayload::Card(Card {
+                title: "Work card".to_string(),
+                cardholder_name: "Jane Doe".to_string(),
+                number: "4111 1111 1111 1111".to_string(),
+                brand: Some("Visa".to_string()),
+                expiry_month: 12,
+                expiry_year: 2030,
+                security_code: Some("123".to_string()),
+            }),
+        ];
+
+        for payload in payloads {
+            let encrypted_payload = encrypt_vault_item_payload(&payload, &dek)?;
+
+            let (version, decrypted_payload) =
+                decrypt_vault_item_payload(&encrypted_payload, &dek)?;
+
+            if version != VAULT_ITEM_PAYLOAD_VERSION
+                || decrypted_payload.item_type() != payload.item_type()
+                || serde_json::to_value(&decrypted_payload).ok()
+                    != serde_json::to_value(&payload).ok()
+            {
+                return Err(AppError::Crypto("Vault item payload mismatch".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_item_payload_from_newer_version() -> AppResult<()> {
+        let dek = generate_dek();
+
+        let encrypted_payload = encrypt_password(
+            r#"{"version":99,"type":"secure_note","data":{"title":"t","note":"n"}}"#,
+            &dek,
+        )?;
+
+        if decrypt_vault_item_payload(&encrypted_payload, &dek).is_ok() {
+            return Err(AppError::Internal(
+                "Payload from a newer version was accepted".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
+use async_graphql::{ErrorExtensionValues, Value};
 use axum::{
+    Json,
     body::Body,
-    http::{Response, StatusCode},
+    http::{Response, StatusCode, header},
     response::IntoResponse,
-    Json,
 };
 use serde_json::json;
 use thiserror::Error;
@@ -24,6 +25,31 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Locked: {0}")]
+    Locked(String),
+
+    /// Too many failed attempts, the client may retry after the given number of seconds
+    #[error("Too many requests: {message}")]
+    TooManyRequests {
+        message: String,
+        retry_after_seconds: u64,
+    },
+
+    /// The session has to re-enter the master password before a sensitive operation
+    #[error("Step-up required: {0}")]
+    StepUpRequired(String),
+
+    /// The account has to verify its email before this operation
+    #[error("Email not verified: {0}")]
+    EmailNotVerified(String),
+
+    /// A write based on an outdated version, carries the current server state to merge with
+    #[error("Version conflict: {message}")]
+    VersionConflict {
+        message: String,
+        current: serde_json::Value,
+    },
+
     #[error("Database error: {0}")]
     Database(String),
 
@@ -36,18 +62,56 @@ pub enum AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response<Body> {
+        if let AppError::VersionConflict { message, current } = self {
+            let body = Json(json!({
+                "error": {
+                    "message": message,
+                    "code": StatusCode::CONFLICT.as_u16(),
+                    "current": current
+                }
+            }));
+
+            return (StatusCode::CONFLICT, body).into_response();
+        }
+
+        if let AppError::TooManyRequests {
+            message,
+            retry_after_seconds,
+        } = self
+        {
+            let body = Json(json!({
+                "error": {
+                    "message": message,
+                    "code": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+                    "retryAfter": retry_after_seconds
+                }
+            }));
+
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_seconds.to_string())],
+                body,
+            )
+                .into_response();
+        }
+
         let (status, error_message) = match self {
             AppError::Authentication(msg) => (StatusCode::UNAUTHORIZED, msg),
             AppError::Authorization(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::Locked(msg) => (StatusCode::LOCKED, msg),
+            AppError::StepUpRequired(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::EmailNotVerified(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::Database(error) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Database error: {}", error),
             ),
             AppError::Crypto(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            AppError::VersionConflict { message, .. } => (StatusCode::CONFLICT, message),
+            AppError::TooManyRequests { message, .. } => (StatusCode::TOO_MANY_REQUESTS, message),
         };
 
         let body = Json(json!({
@@ -61,4 +125,48 @@ impl IntoResponse for AppError {
     }
 }
 
+impl AppError {
+    /// Structured details for GraphQL clients, set as error extensions
+    pub fn graphql_extensions(&self) -> Option<ErrorExtensionValues> {
+        match self {
+            AppError::VersionConflict { current, .. } => {
+                let mut extensions = ErrorExtensionValues::default();
+                extensions.set("code", "VERSION_CONFLICT");
+                extensions.set("current", Value::from_json(current.clone()).ok()?);
+
+                Some(extensions)
+            }
+            AppError::TooManyRequests {
+                retry_after_seconds,
+                ..
+            } => {
+                let mut extensions = ErrorExtensionValues::default();
+                extensions.set("code", "TOO_MANY_REQUESTS");
+                extensions.set("retryAfter", *retry_after_seconds);
+
+                Some(extensions)
+            }
+            AppError::Locked(_) => {
+                let mut extensions = ErrorExtensionValues::default();
+                extensions.set("code", "VAULT_LOCKED");
+
+                Some(extensions)
+            }
+            AppError::StepUpRequired(_) => {
+                let mut extensions = ErrorExtensionValues::default();
+                extensions.set("code", "STEP_UP_REQUIRED");
+
+                Some(extensions)
+            }
+            AppError::EmailNotVerified(_) => {
+                let mut extensions = ErrorExtensionValues::default();
+                extensions.set("code", "EMAIL_NOT_VERIFIED");
+
+                Some(extensions)
+            }
+            _ => None,
+        }
+    }
+}
+
 pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,495 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use redis::{ConnectionLike, ErrorKind, RedisError, RedisResult, Value};
+
+enum Stored {
+    String(Vec<u8>),
+    Set(BTreeSet<Vec<u8>>),
+}
+
+struct Entry {
+    stored: Stored,
+    expires_at: Option<Instant>,
+}
+
+/// Keeps data in memory and answers the Redis commands the services use, so their
+/// behavior can be tested without a server
+#[derive(Default)]
+pub struct MemoryRedis {
+    entries: HashMap<Vec<u8>, Entry>,
+}
+
+fn command_error(message: &'static str) -> RedisError {
+    RedisError::from((ErrorKind::ResponseError, message))
+}
+
+fn parse_line(packed: &[u8], position: &mut usize) -> RedisResult<String> {
+    let rest = &packed[*position..];
+    let end = rest
+        .windows(2)
+        .position(|window| window == b"\r\n")
+        .ok_or(command_error("Unterminated line"))?;
+
+    *position += end + 2;
+
+    String::from_utf8(rest[..end].to_vec()).map_err(|_| command_error("Invalid line"))
+}
+
+fn parse_length(line: &str, prefix: char) -> RedisResult<usize> {
+    line.strip_prefix(prefix)
+        .and_then(|length| length.parse().ok())
+        .ok_or(command_error("Invalid length"))
+}
+
+/// Split packed commands into their arguments
+fn parse_commands(packed: &[u8]) -> RedisResult<Vec<Vec<Vec<u8>>>> {
+    let mut position = 0;
+    let mut commands = vec![];
+
+    while position < packed.len() {
+        let argument_count = parse_length(&parse_line(packed, &mut position)?, '*')?;
+        let mut arguments = vec![];
+
+        for _ in 0..argument_count {
+            let length = parse_length(&parse_line(packed, &mut position)?, '$')?;
+
+            arguments.push(packed[position..position + length].to_vec());
+            position += length + 2;
+        }
+
+        commands.push(arguments);
+    }
+
+    Ok(commands)
+}
+
+fn integer_argument(argument: Option<&Vec<u8>>) -> RedisResult<i64> {
+    argument
+        .and_then(|argument| std::str::from_utf8(argument).ok())
+        .and_then(|argument| argument.parse().ok())
+        .ok_or(command_error("Value is not an integer"))
+}
+
+impl MemoryRedis {
+    fn entry(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        let expired = self
+            .entries
+            .get(key)
+            .and_then(|entry| entry.expires_at)
+            .is_some_and(|expires_at| expires_at <= Instant::now());
+
+        if expired {
+            self.entries.remove(key);
+        }
+
+        self.entries.get_mut(key)
+    }
+
+    fn string(&mut self, key: &[u8]) -> RedisResult<Option<Vec<u8>>> {
+        match self.entry(key).map(|entry| &entry.stored) {
+            Some(Stored::String(value)) => Ok(Some(value.clone())),
+            Some(Stored::Set(_)) => Err(command_error("Key holds a set")),
+            None => Ok(None),
+        }
+    }
+
+    fn set_members(&mut self, key: &[u8]) -> RedisResult<Option<&mut BTreeSet<Vec<u8>>>> {
+        match self.entry(key) {
+            Some(Entry {
+                stored: Stored::Set(members),
+                ..
+            }) => Ok(Some(members)),
+            Some(_) => Err(command_error("Key does not hold a set")),
+            None => Ok(None),
+        }
+    }
+
+    fn put_string(&mut self, key: &[u8], value: Vec<u8>, expires_in: Option<Duration>) {
+        self.entries.insert(
+            key.to_vec(),
+            Entry {
+                stored: Stored::String(value),
+                expires_at: expires_in.map(|expires_in| Instant::now() + expires_in),
+            },
+        );
+    }
+
+    fn increment(&mut self, key: &[u8], delta: i64) -> RedisResult<Value> {
+        let current = match self.string(key)? {
+            Some(value) => integer_argument(Some(&value))?,
+            None => 0,
+        };
+        let expires_at = self.entry(key).and_then(|entry| entry.expires_at);
+
+        let value = current + delta;
+        self.entries.insert(
+            key.to_vec(),
+            Entry {
+                stored: Stored::String(value.to_string().into_bytes()),
+                expires_at,
+            },
+        );
+
+        Ok(Value::Int(value))
+    }
+
+    fn set(&mut self, arguments: &[Vec<u8>]) -> RedisResult<Value> {
+        let key = &arguments[1];
+        let value = arguments[2].clone();
+
+        let mut expires_in = None;
+        let mut keep_ttl = false;
+        let mut only_if_missing = false;
+        let mut only_if_present = false;
+        let mut options = arguments[3..].iter();
+
+        while let Some(option) = options.next() {
+            match option.to_ascii_uppercase().as_slice() {
+                b"EX" => {
+                    expires_in = Some(Duration::from_secs(integer_argument(options.next())? as u64))
+                }
+                b"PX" => {
+                    expires_in = Some(Duration::from_millis(
+                        integer_argument(options.next())? as u64
+                    ))
+                }
+                b"KEEPTTL" => keep_ttl = true,
+                b"NX" => only_if_missing = true,
+                b"XX" => only_if_present = true,
+                _ => return Err(command_error("Unsupported SET option")),
+            }
+        }
+
+        let existing = self.entry(key).map(|entry| entry.expires_at);
+
+        if (only_if_missing && existing.is_some()) || (only_if_present && existing.is_none()) {
+            return Ok(Value::Nil);
+        }
+
+        if keep_ttl {
+            expires_in = existing
+                .flatten()
+                .map(|expires_at| expires_at.saturating_duration_since(Instant::now()));
+        }
+
+        self.put_string(key, value, expires_in);
+
+        Ok(Value::Okay)
+    }
+
+    fn execute(&mut self, arguments: &[Vec<u8>]) -> RedisResult<Value> {
+        let name = arguments
+            .first()
+            .ok_or(command_error("Empty command"))?
+            .to_ascii_uppercase();
+
+        match name.as_slice() {
+            b"PING" => Ok(Value::SimpleString("PONG".to_string())),
+            // connection setup, nothing to keep
+            b"CLIENT" | b"SELECT" => Ok(Value::Okay),
+            b"GET" => Ok(self
+                .string(&arguments[1])?
+                .map(Value::BulkString)
+                .unwrap_or(Value::Nil)),
+            b"GETDEL" => {
+                let value = self.string(&arguments[1])?;
+
+                if value.is_some() {
+                    self.entries.remove(&arguments[1]);
+                }
+
+                Ok(value.map(Value::BulkString).unwrap_or(Value::Nil))
+            }
+            // there are no subscribers
+            b"PUBLISH" => Ok(Value::Int(0)),
+            b"SET" => self.set(arguments),
+            b"SETEX" => {
+                let seconds = integer_argument(arguments.get(2))? as u64;
+                self.put_string(
+                    &arguments[1],
+                    arguments[3].clone(),
+                    Some(Duration::from_secs(seconds)),
+                );
+
+                Ok(Value::Okay)
+            }
+            b"DEL" => {
+                let mut deleted = 0;
+
+                for key in &arguments[1..] {
+                    if self.entry(key).is_some() {
+                        self.entries.remove(key);
+                        deleted += 1;
+                    }
+                }
+
+                Ok(Value::Int(deleted))
+            }
+            b"EXISTS" => Ok(Value::Int(
+                arguments[1..]
+                    .iter()
+                    .filter(|key| self.entry(key).is_some())
+                    .count() as i64,
+            )),
+            b"INCRBY" => self.increment(&arguments[1], integer_argument(arguments.get(2))?),
+            b"DECRBY" => self.increment(&arguments[1], -integer_argument(arguments.get(2))?),
+            b"EXPIRE" => {
+                let seconds = integer_argument(arguments.get(2))?;
+
+                match self.entry(&arguments[1]) {
+                    Some(entry) => {
+                        entry.expires_at =
+                            Some(Instant::now() + Duration::from_secs(seconds.max(0) as u64));
+
+                        Ok(Value::Int(1))
+                    }
+                    None => Ok(Value::Int(0)),
+                }
+            }
+            b"TTL" => Ok(Value::Int(match self.entry(&arguments[1]) {
+                Some(Entry {
+                    expires_at: Some(expires_at),
+                    ..
+                }) => expires_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .ceil() as i64,
+                Some(_) => -1,
+                None => -2,
+            })),
+            b"SADD" => {
+                let key = &arguments[1];
+
+                if self.set_members(key)?.is_none() {
+                    self.entries.insert(
+                        key.to_vec(),
+                        Entry {
+                            stored: Stored::Set(BTreeSet::new()),
+                            expires_at: None,
+                        },
+                    );
+                }
+
+                let members = self.set_members(key)?.ok_or(command_error("Missing set"))?;
+                let added = arguments[2..]
+                    .iter()
+                    .filter(|member| members.insert(member.to_vec()))
+                    .count();
+
+                Ok(Value::Int(added as i64))
+            }
+            b"SREM" => {
+                let Some(members) = self.set_members(&arguments[1])? else {
+                    return Ok(Value::Int(0));
+                };
+
+                let removed = arguments[2..]
+                    .iter()
+                    .filter(|member| members.remove(member.as_slice()))
+                    .count();
+
+                Ok(Value::Int(removed as i64))
+            }
+            b"SMEMBERS" => Ok(Value::Array(
+                self.set_members(&arguments[1])?
+                    .map(|members| members.iter().cloned().map(Value::BulkString).collect())
+                    .unwrap_or_default(),
+            )),
+            b"SCARD" => Ok(Value::Int(
+                self.set_members(&arguments[1])?
+                    .map(|members| members.len())
+                    .unwrap_or_default() as i64,
+            )),
+            _ => Err(command_error("Unsupported command")),
+        }
+    }
+}
+
+/// Read the arguments of the next command a client sent, none once it hung up
+fn read_command(reader: &mut impl BufRead) -> io::Result<Option<Vec<Vec<u8>>>> {
+    let mut line = vec![];
+
+    if reader.read_until(b'\n', &mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let invalid = |_| io::Error::new(io::ErrorKind::InvalidData, "Invalid command");
+    let argument_count =
+        parse_length(String::from_utf8_lossy(&line).trim_end(), '*').map_err(invalid)?;
+    let mut arguments = vec![];
+
+    for _ in 0..argument_count {
+        line.clear();
+        reader.read_until(b'\n', &mut line)?;
+
+        let length =
+            parse_length(String::from_utf8_lossy(&line).trim_end(), '$').map_err(invalid)?;
+        let mut argument = vec![0; length + 2];
+        reader.read_exact(&mut argument)?;
+        argument.truncate(length);
+
+        arguments.push(argument);
+    }
+
+    Ok(Some(arguments))
+}
+
+fn encode_reply(reply: &RedisResult<Value>, encoded: &mut Vec<u8>) {
+    match reply {
+        Ok(Value::Nil) => encoded.extend_from_slice(b"$-1\r\n"),
+        Ok(Value::Int(value)) => encoded.extend_from_slice(format!(":{}\r\n", value).as_bytes()),
+        Ok(Value::BulkString(value)) => {
+            encoded.extend_from_slice(format!("${}\r\n", value.len()).as_bytes());
+            encoded.extend_from_slice(value);
+            encoded.extend_from_slice(b"\r\n");
+        }
+        Ok(Value::Array(values)) => {
+            encoded.extend_from_slice(format!("*{}\r\n", values.len()).as_bytes());
+
+            for value in values {
+                encode_reply(&Ok(value.clone()), encoded);
+            }
+        }
+        Ok(Value::SimpleString(value)) => {
+            encoded.extend_from_slice(format!("+{}\r\n", value).as_bytes())
+        }
+        Ok(Value::Okay) => encoded.extend_from_slice(b"+OK\r\n"),
+        Ok(_) => encoded.extend_from_slice(b"-ERR Unsupported reply\r\n"),
+        Err(e) => encoded.extend_from_slice(
+            format!("-ERR {}\r\n", e.to_string().replace(['\r', '\n'], " ")).as_bytes(),
+        ),
+    }
+}
+
+fn serve_connection(stream: TcpStream, redis: &Mutex<MemoryRedis>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    let mut transaction: Option<Vec<Vec<Vec<u8>>>> = None;
+
+    while let Some(arguments) = read_command(&mut reader)? {
+        let name = arguments
+            .first()
+            .map(|name| name.to_ascii_uppercase())
+            .unwrap_or_default();
+
+        // queued commands run together on EXEC, no other connection gets in between
+        let reply = match (name.as_slice(), transaction.as_mut()) {
+            (b"MULTI", _) => {
+                transaction = Some(vec![]);
+                Ok(Value::Okay)
+            }
+            (b"EXEC", Some(_)) => {
+                let mut redis = redis.lock().unwrap_or_else(PoisonError::into_inner);
+
+                transaction
+                    .take()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|arguments| redis.execute(arguments))
+                    .collect::<RedisResult<Vec<Value>>>()
+                    .map(Value::Array)
+            }
+            (b"EXEC", None) => Err(command_error("EXEC without MULTI")),
+            (_, Some(queued)) => {
+                queued.push(arguments);
+                Ok(Value::SimpleString("QUEUED".to_string()))
+            }
+            (_, None) => redis
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .execute(&arguments),
+        };
+
+        let mut encoded = vec![];
+        encode_reply(&reply, &mut encoded);
+        writer.write_all(&encoded)?;
+    }
+
+    Ok(())
+}
+
+impl MemoryRedis {
+    /// Answer Redis clients over TCP from background threads, for code that opens its own
+    /// connections through a pool
+    pub fn serve(self) -> io::Result<SocketAddr> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let redis = Arc::new(Mutex::new(self));
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let redis = redis.clone();
+
+                thread::spawn(move || serve_connection(stream, &redis));
+            }
+        });
+
+        Ok(address)
+    }
+}
+
+impl ConnectionLike for MemoryRedis {
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        let commands = parse_commands(cmd)?;
+
+        self.execute(commands.first().ok_or(command_error("Empty command"))?)
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        cmd: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        let mut replies = vec![];
+        // commands between MULTI and EXEC run right away, there is no one to interleave with
+        let mut transaction: Option<Vec<Value>> = None;
+
+        for arguments in parse_commands(cmd)? {
+            match arguments[0].to_ascii_uppercase().as_slice() {
+                b"MULTI" => {
+                    transaction = Some(vec![]);
+                    replies.push(Value::Okay);
+                }
+                b"EXEC" => {
+                    let results = transaction
+                        .take()
+                        .ok_or(command_error("EXEC without MULTI"))?;
+                    replies.push(Value::Array(results));
+                }
+                _ => {
+                    let reply = self.execute(&arguments)?;
+
+                    match transaction.as_mut() {
+                        Some(results) => {
+                            results.push(reply);
+                            replies.push(Value::SimpleString("QUEUED".to_string()));
+                        }
+                        None => replies.push(reply),
+                    }
+                }
+            }
+        }
+
+        Ok(replies.into_iter().skip(offset).take(count).collect())
+    }
+
+    fn get_db(&self) -> i64 {
+        0
+    }
+
+    fn check_connection(&mut self) -> bool {
+        true
+    }
+
+    fn is_open(&self) -> bool {
+        true
+    }
+}
@@ -1,2 +1,6 @@
 pub mod common;
 pub mod error;
+#[cfg(test)]
+pub mod memory_redis;
+#[cfg(test)]
+pub mod test_app;
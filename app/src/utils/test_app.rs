@@ -0,0 +1,247 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use async_graphql::{Request, Variables};
+use async_trait::async_trait;
+use axum::http::{HeaderMap, HeaderValue, header};
+use migration::{Migrator, MigratorTrait};
+use sea_orm::Database;
+use serde_json::Value;
+use tokio::{sync::OnceCell, time};
+
+use crate::{
+    configs::env::Env,
+    dtos::{app_state::AppState, graphql_context::GraphQLContext},
+    routes::{graphql::service_schema::ServiceSchema, schema},
+    services::{
+        attachment::LocalBlobStore,
+        events::EVENTS_BUFFER,
+        mail::{Mailer, OutgoingMail},
+    },
+    utils::{
+        error::{AppError, AppResult},
+        memory_redis::MemoryRedis,
+    },
+};
+
+/// Set to a Postgres database to run the tests that go through the whole app
+const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+static MIGRATED: OnceCell<()> = OnceCell::const_new();
+
+/// Keeps the mails the app sends instead of delivering them
+#[derive(Default)]
+struct RecordingMailer {
+    mails: Mutex<Vec<OutgoingMail>>,
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        self.mails
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(mail);
+
+        Ok(())
+    }
+}
+
+/// The app as a client sees it, on a migrated test database and a Redis of its own
+pub struct TestApp {
+    schema: ServiceSchema,
+    mailer: Arc<RecordingMailer>,
+}
+
+/// The session cookie an auth response set
+pub fn session_token(response: &async_graphql::Response) -> Option<String> {
+    response
+        .http_headers
+        .get(header::SET_COOKIE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|cookie| cookie.split(';').next())
+        .and_then(|cookie| cookie.strip_prefix("session_token="))
+        .map(str::to_string)
+}
+
+/// The token of the first link in a mail
+pub fn link_token(mail: &OutgoingMail) -> Option<String> {
+    mail.body
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+}
+
+impl TestApp {
+    /// Start an app, none when TEST_DATABASE_URL is not set
+    pub async fn start(env_variables: Env) -> AppResult<Option<TestApp>> {
+        let Ok(database_url) = std::env::var(TEST_DATABASE_URL) else {
+            eprintln!("{} is not set, skipping", TEST_DATABASE_URL);
+            return Ok(None);
+        };
+
+        let database_connection = Arc::new(
+            Database::connect(&database_url)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to connect: {}", e)))?,
+        );
+
+        MIGRATED
+            .get_or_try_init(|| Migrator::up(database_connection.as_ref(), None))
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to migrate: {}", e)))?;
+
+        let redis_address = MemoryRedis::default()
+            .serve()
+            .map_err(|e| AppError::Internal(format!("Failed to serve redis: {}", e)))?;
+        let redis_client = redis::Client::open(format!("redis://{}", redis_address))
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let redis_pool_manager = r2d2::Pool::builder()
+            .max_size(4)
+            .build(redis_client)
+            .map_err(|e| AppError::Internal(format!("Failed to connect to redis: {}", e)))?;
+
+        let mailer = Arc::new(RecordingMailer::default());
+        let (event_sender, _) = tokio::sync::broadcast::channel(EVENTS_BUFFER);
+
+        let app_state = Arc::new(AppState {
+            blob_store: Arc::new(LocalBlobStore::new(
+                std::env::temp_dir().join(uuid::Uuid::new_v4().to_string()),
+            )),
+            database_connection,
+            redis_pool_manager: Arc::new(redis_pool_manager),
+            env_variables: Arc::new(Env {
+                database_url,
+                redis_url: format!("redis://{}", redis_address),
+                ..env_variables
+            }),
+            mailer: mailer.clone(),
+            event_sender,
+        });
+
+        Ok(Some(TestApp {
+            schema: schema(app_state),
+            mailer,
+        }))
+    }
+
+    /// Run a query or mutation the way the GraphQL route does
+    pub async fn request(
+        &self,
+        session_token: Option<&str>,
+        query: &str,
+        variables: Value,
+    ) -> async_graphql::Response {
+        let mut headers = HeaderMap::new();
+
+        if let Some(session_token) = session_token {
+            if let Ok(cookie) = HeaderValue::from_str(&format!("session_token={}", session_token)) {
+                headers.insert(header::COOKIE, cookie);
+            }
+        }
+
+        let gql_ctx = Arc::new(GraphQLContext {
+            session_token: session_token.map(str::to_string),
+            headers: Some(headers),
+            peer_ip: Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        });
+
+        self.schema
+            .execute(
+                Request::new(query)
+                    .variables(Variables::from_json(variables))
+                    .data(gql_ctx),
+            )
+            .await
+    }
+
+    /// The data of a request, or the error it failed with
+    pub async fn execute(
+        &self,
+        session_token: Option<&str>,
+        query: &str,
+        variables: Value,
+    ) -> AppResult<Value> {
+        into_result(self.request(session_token, query, variables).await)
+    }
+
+    /// Mails sent to an address so far, waiting a little for the ones sent in the background
+    pub async fn mails_to(&self, to: &str, count: usize) -> Vec<OutgoingMail> {
+        for _ in 0..100 {
+            let mails: Vec<OutgoingMail> = self
+                .mailer
+                .mails
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .iter()
+                .filter(|mail| mail.to == to)
+                .cloned()
+                .collect();
+
+            if mails.len() >= count {
+                return mails;
+            }
+
+            time::sleep(Duration::from_millis(10)).await;
+        }
+
+        vec![]
+    }
+
+    /// Sign up through the emailed link and return the session it starts
+    pub async fn signup(&self, email: &str, master_password: &str) -> AppResult<String> {
+        self.execute(
+            None,
+            "mutation($email: String!) { signup(request: { email: $email }) { success } }",
+            serde_json::json!({ "email": email }),
+        )
+        .await?;
+
+        let token = self
+            .mails_to(email, 1)
+            .await
+            .first()
+            .and_then(link_token)
+            .ok_or(AppError::Internal("Signup mail was not sent".to_string()))?;
+
+        let response = self
+            .request(
+                None,
+                "mutation($token: String!, $masterPassword: String!) {
+                    completeSignup(request: { token: $token, masterPassword: $masterPassword }) {
+                        success
+                    }
+                }",
+                serde_json::json!({ "token": token, "masterPassword": master_password }),
+            )
+            .await;
+
+        let session_token = session_token(&response);
+        into_result(response)?;
+
+        session_token.ok_or(AppError::Internal("Signup started no session".to_string()))
+    }
+}
+
+/// The data of a response, or the error it failed with
+pub fn into_result(response: async_graphql::Response) -> AppResult<Value> {
+    match response.errors.first() {
+        Some(error) => Err(error
+            .source::<AppError>()
+            .cloned()
+            .unwrap_or(AppError::Internal(error.message.clone()))),
+        None => response
+            .data
+            .into_json()
+            .map_err(|e| AppError::Internal(e.to_string())),
+    }
+}
+
+/// An address no other test signs up with
+pub fn unique_email() -> String {
+    format!("{}@example.com", uuid::Uuid::new_v4())
+}
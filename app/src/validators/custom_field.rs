@@ -0,0 +1,34 @@
+use chrono::NaiveDate;
+use validator::ValidationError;
+
+use crate::{
+    models::{custom_field::CustomFieldType, custom_field_dtos::CustomFieldInput},
+    services::uri::parse_url,
+};
+
+pub fn validate_custom_fields(custom_fields: &[CustomFieldInput]) -> Result<(), ValidationError> {
+    for custom_field in custom_fields {
+        if custom_field.name.trim().is_empty() {
+            return Err(ValidationError::new("Custom field name is required"));
+        }
+
+        let Some(value) = &custom_field.value else {
+            continue;
+        };
+
+        let is_valid = match custom_field.field_type {
+            CustomFieldType::Text | CustomFieldType::Hidden => true,
+            CustomFieldType::Boolean => value == "true" || value == "false",
+            CustomFieldType::Url => parse_url(value).is_ok(),
+            CustomFieldType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        };
+
+        if !is_valid {
+            return Err(ValidationError::new(
+                "Invalid custom field value for the given field type",
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use validator::ValidationError;
+
+use crate::models::export_dtos::{ExportFormat, ExportVaultRequest};
+
+const MIN_ARCHIVE_PASSWORD_LENGTH: usize = 12;
+
+pub fn validate_export_vault_request(
+    export_vault_request: &ExportVaultRequest,
+) -> Result<(), ValidationError> {
+    match export_vault_request.format {
+        ExportFormat::EncryptedArchive => match &export_vault_request.archive_password {
+            Some(archive_password)
+                if archive_password.chars().count() >= MIN_ARCHIVE_PASSWORD_LENGTH =>
+            {
+                Ok(())
+            }
+            _ => Err(ValidationError::new(
+                "Archive password must be at least 12 characters",
+            )),
+        },
+        // plain exports leave the vault readable, so they need the master password again
+        ExportFormat::Json | ExportFormat::Csv => match &export_vault_request.master_password {
+            Some(_) => Ok(()),
+            None => Err(ValidationError::new(
+                "Master password is required for plain exports",
+            )),
+        },
+    }
+}
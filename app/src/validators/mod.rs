@@ -1 +1,4 @@
+pub mod custom_field;
+pub mod export;
 pub mod password;
+pub mod vault_item;
@@ -1,11 +1,25 @@
 use validator::ValidationError;
 
-use crate::models::password_dtos::{
-    AddPasswordRequest, GetPasswordRequest, GetPasswordsRequest, UpdatePasswordRequest,
+use crate::{
+    models::password_dtos::{
+        AddPasswordRequest, GetPasswordRequest, GetPasswordsRequest, PasswordUriInput,
+        UpdatePasswordRequest,
+    },
+    services::uri::validate_uri,
 };
 
-pub fn validate_add_password_request<'v_a>(
-    add_password_request: &'v_a AddPasswordRequest,
+fn validate_uris(uris: &Option<Vec<PasswordUriInput>>) -> Result<(), ValidationError> {
+    for uri in uris.iter().flatten() {
+        if validate_uri(&uri.uri, &uri.match_type.unwrap_or_default()).is_err() {
+            return Err(ValidationError::new("Invalid uri for the given match type"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_add_password_request(
+    add_password_request: &AddPasswordRequest,
 ) -> Result<(), ValidationError> {
     if add_password_request.website_url.is_none() && add_password_request.app_name.is_none() {
         return Err(ValidationError::new(
@@ -17,11 +31,20 @@ pub fn validate_add_password_request<'v_a>(
         return Err(ValidationError::new("Either username or email is required"));
     }
 
+    validate_uris(&add_password_request.uris)?;
+
+    // folders belong to one user, collection entries are organized by their collection
+    if add_password_request.collection_id.is_some() && add_password_request.folder_id.is_some() {
+        return Err(ValidationError::new(
+            "Entries in a collection cannot be put in a folder",
+        ));
+    }
+
     Ok(())
 }
 
-pub fn validate_update_password_request<'v_a>(
-    add_password_request: &'v_a UpdatePasswordRequest,
+pub fn validate_update_password_request(
+    add_password_request: &UpdatePasswordRequest,
 ) -> Result<(), ValidationError> {
     if add_password_request.website_url.is_none() && add_password_request.app_name.is_none() {
         return Err(ValidationError::new(
@@ -33,13 +56,15 @@ pub fn validate_update_password_request<'v_a>(
         return Err(ValidationError::new("Either username or email is required"));
     }
 
+    validate_uris(&add_password_request.uris)?;
+
     Ok(())
 }
 
-pub fn validate_get_passwords_request<'v_a>(
-    get_passwords_request: &'v_a GetPasswordsRequest,
+pub fn validate_get_passwords_request(
+    get_passwords_request: &GetPasswordsRequest,
 ) -> Result<(), ValidationError> {
-    if get_passwords_request.page <= 0 {
+    if get_passwords_request.page == 0 {
         return Err(ValidationError::new("Page must be greater than 0"));
     }
 
@@ -58,8 +83,8 @@ pub fn validate_get_passwords_request<'v_a>(
     Ok(())
 }
 
-pub fn validate_get_password_request<'v_a>(
-    get_passwords_request: &'v_a GetPasswordRequest,
+pub fn validate_get_password_request(
+    get_passwords_request: &GetPasswordRequest,
 ) -> Result<(), ValidationError> {
     if get_passwords_request.id.is_none() {
         if get_passwords_request.website_url.is_none() && get_passwords_request.app_name.is_none() {
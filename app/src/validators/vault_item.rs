@@ -0,0 +1,132 @@
+use chrono::{Datelike, Utc};
+use validator::ValidationError;
+
+use crate::models::vault_item_dtos::{ApiKey, Card, Identity, SecureNote, SshKey};
+
+fn validate_title(title: &str) -> Result<(), ValidationError> {
+    if title.trim().is_empty() {
+        return Err(ValidationError::new("Title is required"));
+    }
+
+    Ok(())
+}
+
+/// Luhn checksum used by payment card numbers
+pub fn is_valid_card_number(number: &str) -> bool {
+    let digits: Vec<u32> = number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_digit(10))
+        .collect::<Option<Vec<u32>>>()
+        .unwrap_or_default();
+
+    if digits.len() < 12 || digits.len() > 19 {
+        return false;
+    }
+
+    let checksum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            if index % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                *digit
+            }
+        })
+        .sum();
+
+    checksum % 10 == 0
+}
+
+pub fn validate_secure_note(secure_note: &SecureNote) -> Result<(), ValidationError> {
+    validate_title(&secure_note.title)?;
+
+    if secure_note.note.is_empty() {
+        return Err(ValidationError::new("Note is required"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_card(card: &Card) -> Result<(), ValidationError> {
+    validate_title(&card.title)?;
+
+    if card.cardholder_name.trim().is_empty() {
+        return Err(ValidationError::new("Cardholder name is required"));
+    }
+
+    if !is_valid_card_number(&card.number) {
+        return Err(ValidationError::new("Invalid card number"));
+    }
+
+    if !(1..=12).contains(&card.expiry_month) {
+        return Err(ValidationError::new(
+            "Expiry month must be between 1 and 12",
+        ));
+    }
+
+    if card.expiry_year < 2000 || card.expiry_year > Utc::now().year() as u32 + 50 {
+        return Err(ValidationError::new("Invalid expiry year"));
+    }
+
+    if let Some(security_code) = &card.security_code {
+        if !(3..=4).contains(&security_code.len())
+            || !security_code.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(ValidationError::new("Security code must be 3 or 4 digits"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_identity(identity: &Identity) -> Result<(), ValidationError> {
+    validate_title(&identity.title)?;
+
+    if identity.first_name.trim().is_empty() && identity.last_name.trim().is_empty() {
+        return Err(ValidationError::new(
+            "Either first_name or last_name is required",
+        ));
+    }
+
+    if let Some(email) = &identity.email {
+        if !validator::validate_email(email) {
+            return Err(ValidationError::new("Invalid email"));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_api_key(api_key: &ApiKey) -> Result<(), ValidationError> {
+    validate_title(&api_key.title)?;
+
+    if api_key.key.trim().is_empty() {
+        return Err(ValidationError::new("Key is required"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_ssh_key(ssh_key: &SshKey) -> Result<(), ValidationError> {
+    validate_title(&ssh_key.title)?;
+
+    let private_key = ssh_key.private_key.trim();
+
+    if !private_key.starts_with("-----BEGIN ") || !private_key.ends_with("PRIVATE KEY-----") {
+        return Err(ValidationError::new(
+            "Private key must be a PEM or OpenSSH encoded private key",
+        ));
+    }
+
+    if let Some(public_key) = &ssh_key.public_key {
+        if !public_key.trim().starts_with("ssh-") && !public_key.trim().starts_with("ecdsa-") {
+            return Err(ValidationError::new("Invalid public key"));
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::enum_variant_names)]
+
 pub use sea_orm_migration::prelude::*;
 
 mod m20250227_191111_create_table_password;
@@ -5,6 +7,35 @@ mod m20250227_191111_create_table_user;
 mod m20250227_191649_create_table_recovery_code;
 mod m20250304_182633_update_table_password;
 mod m20250306_191038_update_table_password;
+mod m20250310_101500_create_table_password_uri;
+mod m20250311_093000_update_table_password;
+mod m20250312_110000_update_table_password;
+mod m20250314_100000_update_table_password;
+mod m20250316_120000_create_table_custom_field;
+mod m20250318_100000_create_table_folder;
+mod m20250318_100100_create_table_tag;
+mod m20250318_100200_create_table_password_tag;
+mod m20250318_100300_update_table_password;
+mod m20250320_090000_create_table_attachment;
+mod m20250322_090000_update_table_user;
+mod m20250322_090100_update_table_password;
+mod m20250322_090200_create_table_password_share;
+mod m20250324_090000_create_table_organization;
+mod m20250324_090100_create_table_organization_member;
+mod m20250324_090200_create_table_collection;
+mod m20250324_090300_update_table_password;
+mod m20250326_090000_create_table_emergency_access;
+mod m20250328_090000_update_table_user;
+mod m20250328_090100_update_table_password;
+mod m20250328_090200_create_table_password_tombstone;
+mod m20250330_090000_update_table_password;
+mod m20250330_090100_update_table_user;
+mod m20250401_090000_update_table_user;
+mod m20250403_090000_update_table_user;
+mod m20250403_090100_create_table_deletion_receipt;
+mod m20250405_090000_update_table_password;
+mod m20250405_090100_update_table_tag;
+mod m20250407_090000_update_table_emergency_access;
 
 pub struct Migrator;
 
@@ -12,11 +43,41 @@ pub struct Migrator;
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
-            Box::new(m20250227_191111_create_table_password::Migration),
+            // users first, passwords reference them
             Box::new(m20250227_191111_create_table_user::Migration),
+            Box::new(m20250227_191111_create_table_password::Migration),
             Box::new(m20250227_191649_create_table_recovery_code::Migration),
             Box::new(m20250304_182633_update_table_password::Migration),
             Box::new(m20250306_191038_update_table_password::Migration),
+            Box::new(m20250310_101500_create_table_password_uri::Migration),
+            Box::new(m20250311_093000_update_table_password::Migration),
+            Box::new(m20250312_110000_update_table_password::Migration),
+            Box::new(m20250314_100000_update_table_password::Migration),
+            Box::new(m20250316_120000_create_table_custom_field::Migration),
+            Box::new(m20250318_100000_create_table_folder::Migration),
+            Box::new(m20250318_100100_create_table_tag::Migration),
+            Box::new(m20250318_100200_create_table_password_tag::Migration),
+            Box::new(m20250318_100300_update_table_password::Migration),
+            Box::new(m20250320_090000_create_table_attachment::Migration),
+            Box::new(m20250322_090000_update_table_user::Migration),
+            Box::new(m20250322_090100_update_table_password::Migration),
+            Box::new(m20250322_090200_create_table_password_share::Migration),
+            Box::new(m20250324_090000_create_table_organization::Migration),
+            Box::new(m20250324_090100_create_table_organization_member::Migration),
+            Box::new(m20250324_090200_create_table_collection::Migration),
+            Box::new(m20250324_090300_update_table_password::Migration),
+            Box::new(m20250326_090000_create_table_emergency_access::Migration),
+            Box::new(m20250328_090000_update_table_user::Migration),
+            Box::new(m20250328_090100_update_table_password::Migration),
+            Box::new(m20250328_090200_create_table_password_tombstone::Migration),
+            Box::new(m20250330_090000_update_table_password::Migration),
+            Box::new(m20250330_090100_update_table_user::Migration),
+            Box::new(m20250401_090000_update_table_user::Migration),
+            Box::new(m20250403_090000_update_table_user::Migration),
+            Box::new(m20250403_090100_create_table_deletion_receipt::Migration),
+            Box::new(m20250405_090000_update_table_password::Migration),
+            Box::new(m20250405_090100_update_table_tag::Migration),
+            Box::new(m20250407_090000_update_table_emergency_access::Migration),
         ]
     }
 }
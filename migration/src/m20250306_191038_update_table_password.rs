@@ -1,4 +1,3 @@
-use sea_orm::sea_query::extension::postgres::TypeAlterStatement;
 use sea_orm_migration::prelude::*;
 
 #[derive(DeriveIden)]
@@ -0,0 +1,147 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_password::Password, m20250227_191111_create_table_user::User,
+};
+
+#[derive(DeriveIden)]
+pub enum PasswordUri {
+    Table,
+    Id,
+    PasswordId,
+    UserId,
+    Uri,
+    MatchType,
+    Position,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordUri::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PasswordUri::Id).uuid().primary_key())
+                    .col(ColumnDef::new(PasswordUri::PasswordId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordUri::UserId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordUri::Uri).text().not_null())
+                    .col(
+                        ColumnDef::new(PasswordUri::MatchType)
+                            .string()
+                            .not_null()
+                            .default("base_domain"),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordUri::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordUri::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordUri::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_uri_password_id_fkey")
+                    .from_tbl(PasswordUri::Table)
+                    .from_col(PasswordUri::PasswordId)
+                    .to_tbl(Password::Table)
+                    .to_col(Password::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_uri_user_id_fkey")
+                    .from_tbl(PasswordUri::Table)
+                    .from_col(PasswordUri::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(PasswordUri::Table)
+                    .name("password_uri_user_id_index")
+                    .col(PasswordUri::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // every existing entry keeps matching on the site it was saved for
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"INSERT INTO "password_uri" ("id", "password_id", "user_id", "uri", "match_type", "position")
+                SELECT gen_random_uuid(), "id", "user_id", "website_url", 'base_domain', 0
+                FROM "password"
+                WHERE "website_url" IS NOT NULL"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_uri_password_id_fkey")
+                    .table(PasswordUri::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_uri_user_id_fkey")
+                    .table(PasswordUri::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_uri_user_id_index")
+                    .table(PasswordUri::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PasswordUri::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
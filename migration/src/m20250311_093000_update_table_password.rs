@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum Password {
+    Table,
+    UserId,
+    CanonicalWebsiteUrl,
+    WebsiteHost,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // existing rows are filled in by the app at startup, through the same
+        // normalization new writes go through
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .add_column(ColumnDef::new(Password::CanonicalWebsiteUrl).text())
+                    .add_column(ColumnDef::new(Password::WebsiteHost).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Password::Table)
+                    .name("password_user_id_website_host_index")
+                    .col(Password::UserId)
+                    .col(Password::WebsiteHost)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_user_id_website_host_index")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .drop_column(Password::CanonicalWebsiteUrl)
+                    .drop_column(Password::WebsiteHost)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
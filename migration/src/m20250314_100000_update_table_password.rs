@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum Password {
+    Table,
+    UserId,
+    ItemType,
+    EncryptedPassword,
+    EncryptedPayload,
+    PayloadVersion,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // existing entries become logins through the column default
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .add_column(
+                        ColumnDef::new(Password::ItemType)
+                            .string()
+                            .not_null()
+                            .default("login"),
+                    )
+                    .add_column(ColumnDef::new(Password::EncryptedPayload).text())
+                    .add_column(ColumnDef::new(Password::PayloadVersion).integer())
+                    .modify_column(ColumnDef::new(Password::EncryptedPassword).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Password::Table)
+                    .name("password_user_id_item_type_index")
+                    .col(Password::UserId)
+                    .col(Password::ItemType)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_user_id_item_type_index")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(r#"DELETE FROM "password" WHERE "item_type" <> 'login'"#)
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .drop_column(Password::ItemType)
+                    .drop_column(Password::EncryptedPayload)
+                    .drop_column(Password::PayloadVersion)
+                    .modify_column(
+                        ColumnDef::new(Password::EncryptedPassword)
+                            .text()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
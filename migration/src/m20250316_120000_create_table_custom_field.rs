@@ -0,0 +1,133 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_password::Password, m20250227_191111_create_table_user::User,
+};
+
+#[derive(DeriveIden)]
+pub enum CustomField {
+    Table,
+    Id,
+    PasswordId,
+    UserId,
+    Position,
+    EncryptedName,
+    FieldType,
+    EncryptedValue,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CustomField::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(CustomField::Id).uuid().primary_key())
+                    .col(ColumnDef::new(CustomField::PasswordId).uuid().not_null())
+                    .col(ColumnDef::new(CustomField::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(CustomField::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(CustomField::EncryptedName).text().not_null())
+                    .col(ColumnDef::new(CustomField::FieldType).string().not_null())
+                    .col(ColumnDef::new(CustomField::EncryptedValue).text())
+                    .col(
+                        ColumnDef::new(CustomField::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(CustomField::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("custom_field_password_id_fkey")
+                    .from_tbl(CustomField::Table)
+                    .from_col(CustomField::PasswordId)
+                    .to_tbl(Password::Table)
+                    .to_col(Password::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("custom_field_user_id_fkey")
+                    .from_tbl(CustomField::Table)
+                    .from_col(CustomField::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(CustomField::Table)
+                    .name("custom_field_password_id_index")
+                    .col(CustomField::PasswordId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("custom_field_password_id_fkey")
+                    .table(CustomField::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("custom_field_user_id_fkey")
+                    .table(CustomField::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("custom_field_password_id_index")
+                    .table(CustomField::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CustomField::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250227_191111_create_table_user::User;
+
+#[derive(DeriveIden)]
+pub enum Folder {
+    Table,
+    Id,
+    UserId,
+    ParentId,
+    EncryptedName,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Folder::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Folder::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Folder::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Folder::ParentId).uuid())
+                    .col(ColumnDef::new(Folder::EncryptedName).text().not_null())
+                    .col(
+                        ColumnDef::new(Folder::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Folder::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("folder_user_id_fkey")
+                    .from_tbl(Folder::Table)
+                    .from_col(Folder::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("folder_parent_id_fkey")
+                    .from_tbl(Folder::Table)
+                    .from_col(Folder::ParentId)
+                    .to_tbl(Folder::Table)
+                    .to_col(Folder::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Folder::Table)
+                    .name("folder_user_id_index")
+                    .col(Folder::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("folder_user_id_fkey")
+                    .table(Folder::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("folder_parent_id_fkey")
+                    .table(Folder::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("folder_user_id_index")
+                    .table(Folder::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Folder::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
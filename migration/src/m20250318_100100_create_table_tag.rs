@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250227_191111_create_table_user::User;
+
+#[derive(DeriveIden)]
+pub enum Tag {
+    Table,
+    Id,
+    UserId,
+    EncryptedName,
+    NameBlindIndex,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tag::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Tag::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Tag::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Tag::EncryptedName).text().not_null())
+                    .col(ColumnDef::new(Tag::NameBlindIndex).text().not_null())
+                    .col(
+                        ColumnDef::new(Tag::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Tag::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("tag_user_id_fkey")
+                    .from_tbl(Tag::Table)
+                    .from_col(Tag::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // a tag name is unique per user, compared through its blind index
+        manager
+            .create_index(
+                Index::create()
+                    .table(Tag::Table)
+                    .name("tag_user_id_name_blind_index_index")
+                    .col(Tag::UserId)
+                    .col(Tag::NameBlindIndex)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("tag_user_id_fkey")
+                    .table(Tag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("tag_user_id_name_blind_index_index")
+                    .table(Tag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Tag::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_password::Password, m20250318_100100_create_table_tag::Tag,
+};
+
+#[derive(DeriveIden)]
+pub enum PasswordTag {
+    Table,
+    PasswordId,
+    TagId,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordTag::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PasswordTag::PasswordId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordTag::TagId).uuid().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(PasswordTag::PasswordId)
+                            .col(PasswordTag::TagId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_tag_password_id_fkey")
+                    .from_tbl(PasswordTag::Table)
+                    .from_col(PasswordTag::PasswordId)
+                    .to_tbl(Password::Table)
+                    .to_col(Password::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_tag_tag_id_fkey")
+                    .from_tbl(PasswordTag::Table)
+                    .from_col(PasswordTag::TagId)
+                    .to_tbl(Tag::Table)
+                    .to_col(Tag::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(PasswordTag::Table)
+                    .name("password_tag_tag_id_index")
+                    .col(PasswordTag::TagId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_tag_password_id_fkey")
+                    .table(PasswordTag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_tag_tag_id_fkey")
+                    .table(PasswordTag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_tag_tag_id_index")
+                    .table(PasswordTag::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PasswordTag::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250318_100000_create_table_folder::Folder;
+
+#[derive(DeriveIden)]
+enum Password {
+    Table,
+    UserId,
+    FolderId,
+    Favorite,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .add_column(ColumnDef::new(Password::FolderId).uuid())
+                    .add_column(
+                        ColumnDef::new(Password::Favorite)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // folders are removed by the service, this only guards against dangling ids
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_folder_id_fkey")
+                    .from_tbl(Password::Table)
+                    .from_col(Password::FolderId)
+                    .to_tbl(Folder::Table)
+                    .to_col(Folder::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Password::Table)
+                    .name("password_user_id_folder_id_index")
+                    .col(Password::UserId)
+                    .col(Password::FolderId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_user_id_folder_id_index")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_folder_id_fkey")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .drop_column(Password::FolderId)
+                    .drop_column(Password::Favorite)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
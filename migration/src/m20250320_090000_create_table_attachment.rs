@@ -0,0 +1,166 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_password::Password, m20250227_191111_create_table_user::User,
+};
+
+#[derive(DeriveIden)]
+pub enum Attachment {
+    Table,
+    Id,
+    PasswordId,
+    UserId,
+    EncryptedFileName,
+    EncryptedContentType,
+    Size,
+    ClientEncrypted,
+    WrappedFileKey,
+    StorageBackend,
+    StorageKey,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Attachment::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Attachment::PasswordId).uuid().not_null())
+                    .col(ColumnDef::new(Attachment::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::EncryptedFileName)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Attachment::EncryptedContentType).text())
+                    .col(ColumnDef::new(Attachment::Size).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::ClientEncrypted)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Attachment::WrappedFileKey).text().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::StorageBackend)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Attachment::StorageKey).text().not_null())
+                    .col(
+                        ColumnDef::new(Attachment::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Attachment::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("attachment_password_id_fkey")
+                    .from_tbl(Attachment::Table)
+                    .from_col(Attachment::PasswordId)
+                    .to_tbl(Password::Table)
+                    .to_col(Password::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("attachment_user_id_fkey")
+                    .from_tbl(Attachment::Table)
+                    .from_col(Attachment::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Attachment::Table)
+                    .name("attachment_password_id_index")
+                    .col(Attachment::PasswordId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Attachment::Table)
+                    .name("attachment_user_id_index")
+                    .col(Attachment::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("attachment_password_id_fkey")
+                    .table(Attachment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("attachment_user_id_fkey")
+                    .table(Attachment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("attachment_password_id_index")
+                    .table(Attachment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("attachment_user_id_index")
+                    .table(Attachment::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    PublicKey,
+    EncryptedPrivateKey,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // existing users get their keypair on their next login
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(User::PublicKey).text())
+                    .add_column(ColumnDef::new(User::EncryptedPrivateKey).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::PublicKey)
+                    .drop_column(User::EncryptedPrivateKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
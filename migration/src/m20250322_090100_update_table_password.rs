@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum Password {
+    Table,
+    EncryptedItemKey,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // entries without an item key stay encrypted with the owner's DEK
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .add_column(ColumnDef::new(Password::EncryptedItemKey).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .drop_column(Password::EncryptedItemKey)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_password::Password, m20250227_191111_create_table_user::User,
+};
+
+#[derive(DeriveIden)]
+pub enum PasswordShare {
+    Table,
+    Id,
+    PasswordId,
+    OwnerId,
+    RecipientId,
+    WrappedItemKey,
+    Permission,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordShare::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PasswordShare::Id).uuid().primary_key())
+                    .col(ColumnDef::new(PasswordShare::PasswordId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordShare::OwnerId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordShare::RecipientId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(PasswordShare::WrappedItemKey)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordShare::Permission)
+                            .string()
+                            .not_null()
+                            .default("read_only"),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordShare::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordShare::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_share_password_id_fkey")
+                    .from_tbl(PasswordShare::Table)
+                    .from_col(PasswordShare::PasswordId)
+                    .to_tbl(Password::Table)
+                    .to_col(Password::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_share_owner_id_fkey")
+                    .from_tbl(PasswordShare::Table)
+                    .from_col(PasswordShare::OwnerId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_share_recipient_id_fkey")
+                    .from_tbl(PasswordShare::Table)
+                    .from_col(PasswordShare::RecipientId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // an entry is shared with a recipient at most once
+        manager
+            .create_index(
+                Index::create()
+                    .table(PasswordShare::Table)
+                    .name("password_share_password_id_recipient_id_index")
+                    .col(PasswordShare::PasswordId)
+                    .col(PasswordShare::RecipientId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(PasswordShare::Table)
+                    .name("password_share_recipient_id_index")
+                    .col(PasswordShare::RecipientId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_share_password_id_fkey")
+                    .table(PasswordShare::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_share_owner_id_fkey")
+                    .table(PasswordShare::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_share_recipient_id_fkey")
+                    .table(PasswordShare::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_share_password_id_recipient_id_index")
+                    .table(PasswordShare::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_share_recipient_id_index")
+                    .table(PasswordShare::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PasswordShare::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20250227_191111_create_table_user::User,
+    m20250324_090000_create_table_organization::Organization,
+};
+
+#[derive(DeriveIden)]
+pub enum OrganizationMember {
+    Table,
+    Id,
+    OrganizationId,
+    UserId,
+    Role,
+    WrappedOrganizationKey,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OrganizationMember::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(OrganizationMember::Id).uuid().primary_key())
+                    .col(
+                        ColumnDef::new(OrganizationMember::OrganizationId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OrganizationMember::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(OrganizationMember::Role)
+                            .string()
+                            .not_null()
+                            .default("member"),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationMember::WrappedOrganizationKey)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationMember::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OrganizationMember::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("organization_member_organization_id_fkey")
+                    .from_tbl(OrganizationMember::Table)
+                    .from_col(OrganizationMember::OrganizationId)
+                    .to_tbl(Organization::Table)
+                    .to_col(Organization::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("organization_member_user_id_fkey")
+                    .from_tbl(OrganizationMember::Table)
+                    .from_col(OrganizationMember::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // a user is a member of an organization at most once
+        manager
+            .create_index(
+                Index::create()
+                    .table(OrganizationMember::Table)
+                    .name("organization_member_organization_id_user_id_index")
+                    .col(OrganizationMember::OrganizationId)
+                    .col(OrganizationMember::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(OrganizationMember::Table)
+                    .name("organization_member_user_id_index")
+                    .col(OrganizationMember::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("organization_member_organization_id_fkey")
+                    .table(OrganizationMember::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("organization_member_user_id_fkey")
+                    .table(OrganizationMember::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("organization_member_organization_id_user_id_index")
+                    .table(OrganizationMember::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("organization_member_user_id_index")
+                    .table(OrganizationMember::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(OrganizationMember::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250324_090000_create_table_organization::Organization;
+
+#[derive(DeriveIden)]
+pub enum Collection {
+    Table,
+    Id,
+    OrganizationId,
+    EncryptedName,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Collection::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Collection::Id).uuid().primary_key())
+                    .col(ColumnDef::new(Collection::OrganizationId).uuid().not_null())
+                    .col(ColumnDef::new(Collection::EncryptedName).text().not_null())
+                    .col(
+                        ColumnDef::new(Collection::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Collection::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("collection_organization_id_fkey")
+                    .from_tbl(Collection::Table)
+                    .from_col(Collection::OrganizationId)
+                    .to_tbl(Organization::Table)
+                    .to_col(Organization::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Collection::Table)
+                    .name("collection_organization_id_index")
+                    .col(Collection::OrganizationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("collection_organization_id_fkey")
+                    .table(Collection::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("collection_organization_id_index")
+                    .table(Collection::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Collection::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
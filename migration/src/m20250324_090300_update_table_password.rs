@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250324_090200_create_table_collection::Collection;
+
+#[derive(DeriveIden)]
+enum Password {
+    Table,
+    CollectionId,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .add_column(ColumnDef::new(Password::CollectionId).uuid())
+                    .to_owned(),
+            )
+            .await?;
+
+        // collections are only deleted once they are empty
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_collection_id_fkey")
+                    .from_tbl(Password::Table)
+                    .from_col(Password::CollectionId)
+                    .to_tbl(Collection::Table)
+                    .to_col(Collection::Id)
+                    .on_delete(ForeignKeyAction::Restrict)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(Password::Table)
+                    .name("password_collection_id_index")
+                    .col(Password::CollectionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_collection_id_index")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_collection_id_fkey")
+                    .table(Password::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Password::Table)
+                    .drop_column(Password::CollectionId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250227_191111_create_table_user::User;
+
+#[derive(DeriveIden)]
+pub enum EmergencyAccess {
+    Table,
+    Id,
+    GrantorId,
+    GranteeId,
+    AccessType,
+    Status,
+    WaitDays,
+    WrappedDek,
+    RequestedAt,
+    GrantedAt,
+    DeclinedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmergencyAccess::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(EmergencyAccess::Id).uuid().primary_key())
+                    .col(ColumnDef::new(EmergencyAccess::GrantorId).uuid().not_null())
+                    .col(ColumnDef::new(EmergencyAccess::GranteeId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(EmergencyAccess::AccessType)
+                            .string()
+                            .not_null()
+                            .default("view"),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::Status)
+                            .string()
+                            .not_null()
+                            .default("idle"),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::WaitDays)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::WrappedDek)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::RequestedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::GrantedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::DeclinedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(EmergencyAccess::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("emergency_access_grantor_id_fkey")
+                    .from_tbl(EmergencyAccess::Table)
+                    .from_col(EmergencyAccess::GrantorId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("emergency_access_grantee_id_fkey")
+                    .from_tbl(EmergencyAccess::Table)
+                    .from_col(EmergencyAccess::GranteeId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        // a user designates the same contact at most once
+        manager
+            .create_index(
+                Index::create()
+                    .table(EmergencyAccess::Table)
+                    .name("emergency_access_grantor_id_grantee_id_index")
+                    .col(EmergencyAccess::GrantorId)
+                    .col(EmergencyAccess::GranteeId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(EmergencyAccess::Table)
+                    .name("emergency_access_grantee_id_index")
+                    .col(EmergencyAccess::GranteeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // the scheduled job looks up pending requests by status
+        manager
+            .create_index(
+                Index::create()
+                    .table(EmergencyAccess::Table)
+                    .name("emergency_access_status_index")
+                    .col(EmergencyAccess::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("emergency_access_grantor_id_fkey")
+                    .table(EmergencyAccess::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("emergency_access_grantee_id_fkey")
+                    .table(EmergencyAccess::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("emergency_access_grantor_id_grantee_id_index")
+                    .table(EmergencyAccess::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("emergency_access_grantee_id_index")
+                    .table(EmergencyAccess::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("emergency_access_status_index")
+                    .table(EmergencyAccess::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(EmergencyAccess::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
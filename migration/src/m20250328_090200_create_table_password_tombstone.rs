@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20250227_191111_create_table_user::User;
+
+#[derive(DeriveIden)]
+pub enum PasswordTombstone {
+    Table,
+    Id,
+    UserId,
+    PasswordId,
+    Revision,
+    CreatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // the deleted entry is gone, so the password id has no foreign key
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordTombstone::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PasswordTombstone::Id).uuid().primary_key())
+                    .col(ColumnDef::new(PasswordTombstone::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(PasswordTombstone::PasswordId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordTombstone::Revision)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PasswordTombstone::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("password_tombstone_user_id_fkey")
+                    .from_tbl(PasswordTombstone::Table)
+                    .from_col(PasswordTombstone::UserId)
+                    .to_tbl(User::Table)
+                    .to_col(User::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(PasswordTombstone::Table)
+                    .name("password_tombstone_user_id_revision_index")
+                    .col(PasswordTombstone::UserId)
+                    .col(PasswordTombstone::Revision)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("password_tombstone_user_id_fkey")
+                    .table(PasswordTombstone::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("password_tombstone_user_id_revision_index")
+                    .table(PasswordTombstone::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PasswordTombstone::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
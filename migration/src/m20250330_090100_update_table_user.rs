@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    SessionIdleMinutes,
+    SessionMaxLifetimeMinutes,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // the server settings apply while these are null
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(ColumnDef::new(User::SessionIdleMinutes).integer().null())
+                    .add_column(
+                        ColumnDef::new(User::SessionMaxLifetimeMinutes)
+                            .integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::SessionIdleMinutes)
+                    .drop_column(User::SessionMaxLifetimeMinutes)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
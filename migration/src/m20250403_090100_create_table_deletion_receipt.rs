@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+pub enum DeletionReceipt {
+    Table,
+    Id,
+    UserId,
+    EmailHash,
+    RequestedAt,
+    DeletedAt,
+    PasswordCount,
+    AttachmentCount,
+    RecoveryCodeCount,
+    CreatedAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // receipts outlive the user, so the user id has no foreign key
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeletionReceipt::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(DeletionReceipt::Id).uuid().primary_key())
+                    .col(ColumnDef::new(DeletionReceipt::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(DeletionReceipt::EmailHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::RequestedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::DeletedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::PasswordCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::AttachmentCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::RecoveryCodeCount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionReceipt::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .table(DeletionReceipt::Table)
+                    .name("deletion_receipt_user_id_index")
+                    .col(DeletionReceipt::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("deletion_receipt_user_id_index")
+                    .table(DeletionReceipt::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(DeletionReceipt::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // blind indexes are keyed with a subkey of the DEK now, entries without one are
+        // compared decrypted until they are saved again
+        manager
+            .get_connection()
+            .execute_unprepared(r#"UPDATE "password" SET "account_blind_index" = NULL"#)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // blind indexes are keyed with a subkey of the DEK now, the app indexes
+        // these names again when their user next logs in
+        manager
+            .get_connection()
+            .execute_unprepared(r#"UPDATE "tag" SET "name_blind_index" = 'stale:' || "id"::text"#)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
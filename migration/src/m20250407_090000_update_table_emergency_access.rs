@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveIden)]
+enum EmergencyAccess {
+    Table,
+    TakenOverAt,
+}
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // set once a takeover used up the grant
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EmergencyAccess::Table)
+                    .add_column(
+                        ColumnDef::new(EmergencyAccess::TakenOverAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(EmergencyAccess::Table)
+                    .drop_column(EmergencyAccess::TakenOverAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}